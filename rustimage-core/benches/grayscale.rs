@@ -0,0 +1,54 @@
+//! 灰度转换基准测试
+//!
+//! 比较 `to_luma`（启用 `simd` feature 时为向量化路径）与逐像素标量转换：
+//!
+//! ```text
+//! cargo bench -p rustimage-core --bench grayscale --features simd
+//! ```
+
+use rustimage_core::codecs::{ImageBuffer, PixelFormat};
+use rustimage_core::{Luma8, Rgba, Rgba8};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const WIDTH: u32 = 2048;
+const HEIGHT: u32 = 2048;
+const ITERATIONS: u32 = 20;
+
+fn create_buffer() -> ImageBuffer<Rgba8> {
+    let pixels = (0..WIDTH * HEIGHT)
+        .map(|i| Rgba { r: i as u8, g: (i >> 8) as u8, b: (i >> 16) as u8, a: 255 })
+        .collect();
+    ImageBuffer::from_raw(WIDTH, HEIGHT, pixels, PixelFormat::Rgba8).expect("valid buffer")
+}
+
+fn measure<F: FnMut()>(name: &str, mut f: F) -> Duration {
+    f(); // 预热
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let per_iteration = start.elapsed() / ITERATIONS;
+    let megapixels = (WIDTH * HEIGHT) as f64 / 1_000_000.0;
+    println!(
+        "{:<8} {:>10.3} ms/iter  {:>8.1} MP/s",
+        name,
+        per_iteration.as_secs_f64() * 1000.0,
+        megapixels / per_iteration.as_secs_f64()
+    );
+    per_iteration
+}
+
+fn main() {
+    let buffer = create_buffer();
+    println!("RGBA -> Luma8, {}x{}, simd feature: {}", WIDTH, HEIGHT, cfg!(feature = "simd"));
+
+    let scalar = measure("scalar", || {
+        black_box(black_box(&buffer).clone().convert_pixel_format::<Luma8>());
+    });
+    let simd = measure("to_luma", || {
+        black_box(black_box(&buffer).to_luma());
+    });
+
+    println!("speedup: {:.2}x", scalar.as_secs_f64() / simd.as_secs_f64());
+}
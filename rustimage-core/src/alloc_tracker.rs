@@ -0,0 +1,105 @@
+//! 分配跟踪 - 统计堆分配次数和峰值占用
+//!
+//! 启用`track-alloc` feature时安装为全局分配器，包装系统分配器并以原子计数器
+//! 记录分配、释放和当前占用字节数。每次分配/释放额外付出数次原子操作，
+//! 通常带来个位数百分比的开销，仅建议在性能分析时启用。
+//!
+//! 同一程序只能有一个全局分配器：已自行设置`#[global_allocator]`的程序
+//! （如启用`wee_alloc`的WASM构建）不能同时启用本feature。
+//! 计数器是进程级的，并发转换会相互计入对方的分配。
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// 跟踪分配器 - 包装系统分配器
+struct TrackingAllocator;
+
+#[global_allocator]
+static GLOBAL: TrackingAllocator = TrackingAllocator;
+
+static CURRENT_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static DEALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            // 重新分配视为一次释放加一次分配
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[inline]
+fn record_alloc(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    let current = CURRENT_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK_BYTES.fetch_max(current, Ordering::Relaxed);
+}
+
+#[inline]
+fn record_dealloc(size: usize) {
+    DEALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    CURRENT_BYTES.fetch_sub(size, Ordering::Relaxed);
+}
+
+/// 分配计数快照 - 用于计算一段操作期间的增量
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AllocSnapshot {
+    /// 快照时的占用字节数
+    current_bytes: usize,
+    /// 快照时的累计分配次数
+    allocations: u64,
+    /// 快照时的累计释放次数
+    deallocations: u64,
+}
+
+impl AllocSnapshot {
+    /// 开始一段测量 - 将峰值重置为当前占用
+    pub(crate) fn begin() -> Self {
+        let current_bytes = CURRENT_BYTES.load(Ordering::Relaxed);
+        PEAK_BYTES.store(current_bytes, Ordering::Relaxed);
+        Self {
+            current_bytes,
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            deallocations: DEALLOCATIONS.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 自快照以来的内存指标 - 峰值为超出起始占用的部分
+    pub(crate) fn finish(&self) -> crate::types::MemoryMetrics {
+        let count = |now: u64, then: u64| now.saturating_sub(then).min(u32::MAX as u64) as u32;
+        crate::types::MemoryMetrics {
+            peak_memory_bytes: PEAK_BYTES
+                .load(Ordering::Relaxed)
+                .saturating_sub(self.current_bytes) as u64,
+            allocations_count: count(ALLOCATIONS.load(Ordering::Relaxed), self.allocations),
+            deallocations_count: count(DEALLOCATIONS.load(Ordering::Relaxed), self.deallocations),
+        }
+    }
+}
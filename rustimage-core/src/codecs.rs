@@ -7,13 +7,21 @@
 //! - **零成本抽象**：编译时特化和内联优化
 
 use crate::{
+    converter::Cancellation,
     error::{ImageError, Result},
+    processing,
     types::*,
 };
 
+use serde::{Deserialize, Serialize};
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use std::io::Cursor;
-use image::{ImageFormat as ImageCrateFormat, ImageEncoder};
+use std::time::Duration;
+use image::{AnimationDecoder, ImageFormat as ImageCrateFormat, ImageEncoder};
 
 // =============================================================================
 // 公共API - 深模块的简单接口
@@ -26,6 +34,7 @@ use image::{ImageFormat as ImageCrateFormat, ImageEncoder};
 pub struct CodecEngine {
     // 私有字段：隐藏所有实现细节
     codecs: CodecRegistry, // 管理所有编解码器实例
+    custom_codecs: HashMap<String, Box<dyn Codec<Rgba8>>>, // 用户注册的自定义编解码器，按键查找
     config: CodecConfig, // 编解码器配置
 }
 
@@ -40,10 +49,15 @@ pub struct CodecConfig {
     pub simd_enabled: bool,
     /// 内存限制（字节）
     pub memory_limit: Option<u64>,
+    /// 解码的最大像素数 - 按文件头声明的尺寸在解码前检查
+    pub max_decoded_pixels: Option<u64>,
     /// 质量优先级
     pub quality_priority: QualityPriority,
 }
 
+/// 默认的解码像素上限 - 1亿像素，防止声明超大尺寸的解压炸弹
+pub const DEFAULT_MAX_DECODED_PIXELS: u64 = 100_000_000;
+
 /// 质量优先级策略
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum QualityPriority {
@@ -70,10 +84,15 @@ pub struct ImageBuffer<P: Pixel> {
     dimensions: ImageDimensions,
     /// 像素格式 - 私有：类型安全
     pixel_format: PixelFormat,
+    /// 嵌入的ICC色彩配置文件 - 私有：随像素一起传递
+    icc_profile: Option<Vec<u8>>,
 }
 
+/// 动画帧 - 像素缓冲区及其显示时长
+pub type AnimationFrame<P> = (ImageBuffer<P>, Duration);
+
 /// 像素格式枚举
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PixelFormat {
     /// 8位RGB
     Rgb8,
@@ -111,6 +130,16 @@ struct CodecRegistry {
     gif: Box<dyn Codec<Rgba8> + Send + Sync>,
     /// ICO编解码器
     ico: Box<dyn Codec<Rgba8> + Send + Sync>,
+    /// PNM编解码器
+    pnm: Box<dyn Codec<Rgba8> + Send + Sync>,
+    /// TGA编解码器
+    tga: Box<dyn Codec<Rgba8> + Send + Sync>,
+    /// HEIC编解码器 - 仅在启用`heif`特性时存在
+    #[cfg(feature = "heif")]
+    heic: Box<dyn Codec<Rgba8> + Send + Sync>,
+    /// JPEG XL编解码器 - 仅在启用`jxl`特性时存在
+    #[cfg(feature = "jxl")]
+    jxl: Box<dyn Codec<Rgba8> + Send + Sync>,
 }
 
 // =============================================================================
@@ -131,6 +160,11 @@ pub trait Codec<P: Pixel>: Send + Sync {
     /// - `Err(ImageError)`: 解码失败的错误信息
     fn decode(&self, data: &[u8]) -> Result<ImageBuffer<P>>;
 
+    /// 按转换选项解码 - 默认忽略选项，等同于`decode`
+    fn decode_with_options(&self, data: &[u8], _options: &ConversionOptions) -> Result<ImageBuffer<P>> {
+        self.decode(data)
+    }
+
     /// 编码图像数据
     ///
     /// # 参数
@@ -142,6 +176,70 @@ pub trait Codec<P: Pixel>: Send + Sync {
     /// - `Err(ImageError)`: 编码失败的错误信息
     fn encode(&self, buffer: &ImageBuffer<P>, options: &ConversionOptions) -> Result<Vec<u8>>;
 
+    /// 解码全部动画帧 - 默认按单帧静态图像处理
+    fn decode_frames(&self, data: &[u8]) -> Result<Vec<AnimationFrame<P>>> {
+        Ok(vec![(self.decode(data)?, Duration::ZERO)])
+    }
+
+    /// 编码动画帧序列 - 默认不支持
+    fn encode_frames(&self, _frames: &[AnimationFrame<P>], _options: &ConversionOptions) -> Result<Vec<u8>> {
+        Err(ImageError::UnsupportedOperation {
+            operation: format!("{} animation encode", self.info().name),
+        })
+    }
+
+    /// 可取消地解码动画帧 - 默认只在解码前检查一次取消信号
+    fn decode_frames_cancellable(&self, data: &[u8], cancel: &dyn Cancellation) -> Result<Vec<AnimationFrame<P>>> {
+        if cancel.is_cancelled() {
+            return Err(ImageError::cancelled(format!("{} frame decode", self.info().name), 0, 1));
+        }
+        self.decode_frames(data)
+    }
+
+    /// 可取消地编码动画帧 - 默认只在编码前检查一次取消信号
+    fn encode_frames_cancellable(
+        &self,
+        frames: &[AnimationFrame<P>],
+        options: &ConversionOptions,
+        cancel: &dyn Cancellation,
+    ) -> Result<Vec<u8>> {
+        if cancel.is_cancelled() {
+            return Err(ImageError::cancelled(
+                format!("{} frame encode", self.info().name),
+                0,
+                frames.len(),
+            ));
+        }
+        self.encode_frames(frames, options)
+    }
+
+    /// 将同一图像按多个边长编码进单个文件 - 默认不支持
+    fn encode_multisize(&self, _buffer: &ImageBuffer<P>, _sizes: &[u32]) -> Result<Vec<u8>> {
+        Err(ImageError::UnsupportedOperation {
+            operation: format!("{} multi-size encode", self.info().name),
+        })
+    }
+
+    /// 解码多页文档的全部页面 - 默认按单页图像处理
+    fn decode_pages(&self, data: &[u8]) -> Result<Vec<ImageBuffer<P>>> {
+        Ok(vec![self.decode(data)?])
+    }
+
+    /// 可取消地解码全部页面 - 默认只在解码前检查一次取消信号
+    fn decode_pages_cancellable(&self, data: &[u8], cancel: &dyn Cancellation) -> Result<Vec<ImageBuffer<P>>> {
+        if cancel.is_cancelled() {
+            return Err(ImageError::cancelled(format!("{} page decode", self.info().name), 0, 1));
+        }
+        self.decode_pages(data)
+    }
+
+    /// 将多个页面编码进单个文件 - 默认不支持
+    fn encode_pages(&self, _pages: &[ImageBuffer<P>], _options: &ConversionOptions) -> Result<Vec<u8>> {
+        Err(ImageError::UnsupportedOperation {
+            operation: format!("{} multi-page encode", self.info().name),
+        })
+    }
+
     /// 获取编解码器信息
     fn info(&self) -> CodecInfo;
 
@@ -213,10 +311,13 @@ pub struct QualityFeatures {
 
 // =============================================================================
 // 具体编解码器实现 - 每个都是独立的深模块
+//
+// 引擎通过`Codec`特征分派到这些类型；它们也可以用`Default`构造后直接使用，
+// 或包装后经`CodecEngine::register_codec`注册。配置字段保持私有
 // =============================================================================
 
 /// JPEG编解码器 - 有损压缩专家
-struct JpegCodec {
+pub struct JpegCodec {
     /// 预计算的量化表 - 私有：优化实现
     quality_tables: Option<Arc<[u8; 64]>>,
     /// 优化级别 - 私有：内部配置
@@ -237,7 +338,7 @@ enum DctImplementation {
 }
 
 /// PNG编解码器 - 无损压缩专家
-struct PngCodec {
+pub struct PngCodec {
     /// 压缩策略 - 私有：算法选择
     compression_strategy: CompressionStrategy,
     /// 滤波器类型 - 私有：预处理选择
@@ -246,30 +347,8 @@ struct PngCodec {
     compression_level: u8,
 }
 
-/// PNG压缩策略 - 私有枚举
-#[derive(Debug, Clone, Copy)]
-enum CompressionStrategy {
-    Default,
-    Filtered,
-    HuffmanOnly,
-    Rle,
-    Fixed,
-}
-
-/// PNG滤波器类型 - 私有枚举
-#[derive(Debug, Clone, Copy)]
-enum PngFilter {
-    None,
-    Sub,
-    Up,
-    Average,
-    Paeth,
-    /// 自适应选择最佳滤波器
-    Adaptive,
-}
-
 /// WebP编解码器 - 现代格式处理器
-struct WebPCodec {
+pub struct WebPCodec {
     /// 编码模式 - 私有：模式选择
     encoding_mode: WebPMode,
     /// 预处理选项 - 私有：优化配置
@@ -310,7 +389,7 @@ enum WebPPrediction {
 }
 
 /// AVIF编解码器 - 下一代格式处理器
-struct AvifCodec {
+pub struct AvifCodec {
     /// 编码器设置 - 私有：配置管理
     encoder_settings: AvifEncoderSettings,
     /// 分块模式 - 私有：并行优化
@@ -356,12 +435,13 @@ enum AvifPixelFormat {
     Yuv444,
 }
 
+/// BMP编解码器 - 写出24位无压缩位图
+#[derive(Default)]
+pub struct BmpCodec;
+
 // 其他格式的简化编解码器
-struct BmpCodec {
-    support_compression: bool,
-}
 
-struct TiffCodec {
+pub struct TiffCodec {
     compression_type: TiffCompression,
 }
 
@@ -374,15 +454,34 @@ enum TiffCompression {
     Jpeg,
 }
 
-struct GifCodec {
+pub struct GifCodec {
     animation_support: bool,
     palette_optimization: bool,
 }
 
-struct IcoCodec {
+pub struct IcoCodec {
     supported_sizes: Vec<u32>,
 }
 
+/// PNM编解码器 - PPM/PGM/PBM 无压缩格式
+#[derive(Default)]
+pub struct PnmCodec;
+
+/// TGA编解码器 - 游戏贴图常用的Truevision格式
+pub struct TgaCodec {
+    rle_compression: bool,
+}
+
+/// HEIC编解码器 - 通过libheif解码，不支持编码
+#[cfg(feature = "heif")]
+#[derive(Default)]
+pub struct HeicCodec;
+
+/// JPEG XL编解码器 - jxl-oxide解码，zune-jpegxl无损编码
+#[cfg(feature = "jxl")]
+#[derive(Default)]
+pub struct JxlCodec;
+
 // =============================================================================
 // 公共实现 - 深模块接口的实现
 // =============================================================================
@@ -401,6 +500,7 @@ impl CodecEngine {
 
         Ok(Self {
             codecs,
+            custom_codecs: HashMap::new(),
             config,
         })
     }
@@ -418,14 +518,29 @@ impl CodecEngine {
     where
         P: From<Rgba8> + 'static,
     {
-        // 1. 格式验证 - 内部逻辑
+        self.decode_with_options(data, format, &ConversionOptions::default())
+    }
+
+    /// 按转换选项解码 - 选项影响解码的部分（如16位来源的抖动）交给编解码器处理
+    pub fn decode_with_options<P>(
+        &self,
+        data: &[u8],
+        format: ImageFormat,
+        options: &ConversionOptions,
+    ) -> Result<ImageBuffer<P>>
+    where
+        P: Pixel + From<Rgba8> + 'static,
+    {
+        // 1. 格式验证 - 内部逻辑，按文件头尺寸拒绝解压炸弹
         self.validate_format_data(data, format)?;
+        self.check_decoded_pixels(data)?;
 
         // 2. 获取对应的编解码器 - 信息隐藏
         let codec = self.codecs.get_codec(format)?;
 
-        // 3. 执行解码 - 委托给具体实现
-        let rgba_buffer = codec.decode(data)?;
+        // 3. 执行解码 - 委托给具体实现，损坏文件可能解出空图像
+        let rgba_buffer = codec.decode_with_options(data, options)?;
+        ensure_nonzero_dimensions(&rgba_buffer)?;
 
         // 4. 像素格式转换 - 零成本抽象
         self.convert_buffer::<Rgba8, P>(rgba_buffer)
@@ -452,23 +567,198 @@ impl CodecEngine {
         codec.encode(&rgba_buffer, options)
     }
 
+    /// 解码动画的全部帧 - 静态格式返回单帧
+    pub fn decode_frames(&self, data: &[u8], format: ImageFormat) -> Result<Vec<AnimationFrame<Rgba8>>> {
+        self.decode_frames_cancellable(data, format, &AtomicBool::new(false))
+    }
+
+    /// 可取消地解码动画 - 每帧开始前检查取消信号，已取消时返回带帧数的`OperationCancelled`
+    pub fn decode_frames_cancellable(
+        &self,
+        data: &[u8],
+        format: ImageFormat,
+        cancel: &dyn Cancellation,
+    ) -> Result<Vec<AnimationFrame<Rgba8>>> {
+        self.validate_format_data(data, format)?;
+        self.check_decoded_pixels(data)?;
+
+        let codec = self.codecs.get_codec(format)?;
+        let frames = codec.decode_frames_cancellable(data, cancel)?;
+        for (frame, _) in &frames {
+            ensure_nonzero_dimensions(frame)?;
+        }
+        Ok(frames)
+    }
+
+    /// 将帧序列编码为动画
+    pub fn encode_frames(
+        &self,
+        frames: &[AnimationFrame<Rgba8>],
+        format: ImageFormat,
+        options: &ConversionOptions,
+    ) -> Result<Vec<u8>> {
+        self.encode_frames_cancellable(frames, format, options, &AtomicBool::new(false))
+    }
+
+    /// 可取消地编码动画 - 每帧编码前检查取消信号，已取消时返回带帧数的`OperationCancelled`
+    pub fn encode_frames_cancellable(
+        &self,
+        frames: &[AnimationFrame<Rgba8>],
+        format: ImageFormat,
+        options: &ConversionOptions,
+        cancel: &dyn Cancellation,
+    ) -> Result<Vec<u8>> {
+        if frames.is_empty() {
+            return Err(ImageError::InvalidParameters {
+                details: "Animation requires at least one frame".to_string(),
+            });
+        }
+
+        self.validate_encode_params(format, options)?;
+
+        let codec = self.codecs.get_codec(format)?;
+        codec.encode_frames_cancellable(frames, options, cancel)
+    }
+
+    /// 解码多页文档的全部页面 - 单页格式返回一页
+    pub fn decode_pages(&self, data: &[u8], format: ImageFormat) -> Result<Vec<ImageBuffer<Rgba8>>> {
+        self.decode_pages_cancellable(data, format, &AtomicBool::new(false))
+    }
+
+    /// 可取消地解码多页文档 - 每页开始前检查取消信号，已取消时返回带页数的`OperationCancelled`
+    pub fn decode_pages_cancellable(
+        &self,
+        data: &[u8],
+        format: ImageFormat,
+        cancel: &dyn Cancellation,
+    ) -> Result<Vec<ImageBuffer<Rgba8>>> {
+        self.validate_format_data(data, format)?;
+        self.check_decoded_pixels(data)?;
+
+        let codec = self.codecs.get_codec(format)?;
+        let pages = codec.decode_pages_cancellable(data, cancel)?;
+        for page in &pages {
+            ensure_nonzero_dimensions(page)?;
+        }
+        Ok(pages)
+    }
+
+    /// 将多个页面编码为多页文档
+    pub fn encode_pages(
+        &self,
+        pages: &[ImageBuffer<Rgba8>],
+        format: ImageFormat,
+        options: &ConversionOptions,
+    ) -> Result<Vec<u8>> {
+        if pages.is_empty() {
+            return Err(ImageError::InvalidParameters {
+                details: "Multi-page encoding requires at least one page".to_string(),
+            });
+        }
+
+        self.validate_encode_params(format, options)?;
+
+        let codec = self.codecs.get_codec(format)?;
+        codec.encode_pages(pages, options)
+    }
+
+    /// 将图像缩放到多个边长并打包为单个文件（如多尺寸ICO）
+    pub fn encode_multisize(
+        &self,
+        buffer: &ImageBuffer<Rgba8>,
+        format: ImageFormat,
+        sizes: &[u32],
+    ) -> Result<Vec<u8>> {
+        if sizes.is_empty() {
+            return Err(ImageError::InvalidParameters {
+                details: "Multi-size encoding requires at least one size".to_string(),
+            });
+        }
+
+        let codec = self.codecs.get_codec(format)?;
+        codec.encode_multisize(buffer, sizes)
+    }
+
+    /// 注册自定义编解码器 - 与内置格式并行，通过`format_key`引用
+    ///
+    /// 同一个键再次注册时替换之前的编解码器；键不能为空，也不能与内置格式的
+    /// 名称或扩展名相同，以免`FormatConverter::convert_custom`解析时产生歧义
+    pub fn register_codec(&mut self, format_key: impl Into<String>, codec: Box<dyn Codec<Rgba8>>) -> Result<()> {
+        let key = format_key.into();
+        if key.trim().is_empty() {
+            return Err(ImageError::InvalidParameters {
+                details: "Custom codec key must not be empty".to_string(),
+            });
+        }
+        if let Ok(format) = key.parse::<ImageFormat>() {
+            return Err(ImageError::InvalidParameters {
+                details: format!("Custom codec key '{}' collides with built-in format {}", key, format),
+            });
+        }
+
+        self.custom_codecs.insert(key, codec);
+        Ok(())
+    }
+
+    /// 已注册的自定义格式键 - 按字母顺序
+    pub fn custom_formats(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.custom_codecs.keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    /// 使用自定义编解码器解码
+    pub fn decode_custom(&self, data: &[u8], format_key: &str) -> Result<ImageBuffer<Rgba8>> {
+        let codec = self.custom_codec(format_key)?;
+        if data.is_empty() || !codec.validate_format(data) {
+            return Err(ImageError::InvalidFormat {
+                format: format!("Data does not match custom format '{}'", format_key),
+            });
+        }
+
+        let buffer = codec.decode(data)?;
+        ensure_nonzero_dimensions(&buffer)?;
+        Ok(buffer)
+    }
+
+    /// 使用自定义编解码器编码
+    pub fn encode_custom(
+        &self,
+        buffer: &ImageBuffer<Rgba8>,
+        format_key: &str,
+        options: &ConversionOptions,
+    ) -> Result<Vec<u8>> {
+        self.custom_codec(format_key)?.encode(buffer, options)
+    }
+
+    /// 用声明格式的编解码器检查数据签名 - 不符时返回`InvalidFormat`
+    ///
+    /// 只检查魔数和文件头结构，不解码像素；`decode`系列方法在解码前自动调用
+    pub fn validate_format_data(&self, data: &[u8], format: ImageFormat) -> Result<()> {
+        if data.is_empty() {
+            return Err(ImageError::InvalidFormat {
+                format: format!("Empty data for format {}", format),
+            });
+        }
+
+        let codec = self.codecs.get_codec(format)?;
+        if !codec.validate_format(data) {
+            return Err(ImageError::InvalidFormat {
+                format: format!("Data does not match format {}", format),
+            });
+        }
+
+        Ok(())
+    }
+
     /// 检测图像格式 - 便民方法
     pub fn detect_format(&self, data: &[u8]) -> Result<ImageFormat> {
         FormatDetector::detect(data)
     }
 
-    /// 获取支持的格式列表
+    /// 获取支持的格式列表 - 不含当前构建未启用的格式
     pub fn supported_formats(&self) -> Vec<ImageFormat> {
-        vec![
-            ImageFormat::Jpeg,
-            ImageFormat::Png,
-            ImageFormat::WebP,
-            ImageFormat::Avif,
-            ImageFormat::Bmp,
-            ImageFormat::Tiff,
-            ImageFormat::Gif,
-            ImageFormat::Ico,
-        ]
+        enabled_formats()
     }
 
     /// 检查格式转换是否支持
@@ -476,12 +766,23 @@ impl CodecEngine {
         self.supported_formats().contains(&from) && self.supported_formats().contains(&to)
     }
 
+    /// 获取格式的推荐编码选项 - 由对应编解码器的`default_options`给出
+    pub fn default_options(&self, format: ImageFormat) -> Result<ConversionOptions> {
+        let codec = self.codecs.get_codec(format)?;
+        Ok(codec.default_options())
+    }
+
     /// 获取编解码器信息
     pub fn get_codec_info(&self, format: ImageFormat) -> Result<CodecInfo> {
         let codec = self.codecs.get_codec(format)?;
         Ok(codec.info())
     }
 
+    /// 获取自定义编解码器信息 - 未注册时列出全部可用的格式
+    pub fn custom_codec_info(&self, format_key: &str) -> Result<CodecInfo> {
+        Ok(self.custom_codec(format_key)?.info())
+    }
+
     /// 更新配置 - 运行时重配置
     pub fn update_config(&mut self, config: CodecConfig) -> Result<()> {
         // 重新创建编解码器注册表
@@ -496,22 +797,59 @@ impl CodecEngine {
 // =============================================================================
 
 impl CodecEngine {
-    /// 验证格式和数据的匹配性 - 私有方法
-    fn validate_format_data(&self, data: &[u8], format: ImageFormat) -> Result<()> {
-        if data.is_empty() {
-            return Err(ImageError::InvalidFormat {
-                format: format!("Empty data for format {}", format),
+    /// 检查文件头声明的像素数 - 超出`max_decoded_pixels`时在分配像素缓冲区之前报错
+    ///
+    /// 无法从文件头读出尺寸的数据交给解码器处理
+    fn check_decoded_pixels(&self, data: &[u8]) -> Result<()> {
+        let Some(limit) = self.config.max_decoded_pixels else {
+            return Ok(());
+        };
+        let Some(dims) = declared_dimensions(data) else {
+            return Ok(());
+        };
+
+        let pixels = dims.width as u64 * dims.height as u64;
+        if pixels > limit {
+            return Err(ImageError::SystemLimitExceeded {
+                limit_type: "pixels".to_string(),
+                current: pixels,
+                limit,
             });
         }
+        Ok(())
+    }
 
-        let codec = self.codecs.get_codec(format)?;
-        if !codec.validate_format(data) {
-            return Err(ImageError::InvalidFormat {
-                format: format!("Data does not match format {}", format),
+    /// 直通前的文件头检查 - 不解码像素，但与解码路径一样拒绝零尺寸和超出像素上限的图像
+    ///
+    /// 无法从文件头读出尺寸时返回`None`，由调用方改走完整解码
+    pub(crate) fn check_header(&self, data: &[u8]) -> Result<Option<ImageDimensions>> {
+        let Some(dims) = FormatDetector::read_dimensions(data) else {
+            return Ok(None);
+        };
+        if dims.width == 0 || dims.height == 0 {
+            return Err(ImageError::InvalidDimensions {
+                width: dims.width,
+                height: dims.height,
+                reason: "zero dimension".to_string(),
             });
         }
+        self.check_decoded_pixels(data)?;
+        Ok(Some(dims))
+    }
 
-        Ok(())
+    /// 查找自定义编解码器 - 未注册时列出全部可用的格式
+    fn custom_codec(&self, format_key: &str) -> Result<&dyn Codec<Rgba8>> {
+        self.custom_codecs
+            .get(format_key)
+            .map(|codec| codec.as_ref())
+            .ok_or_else(|| ImageError::UnsupportedFormat {
+                format: format_key.to_string(),
+                supported: enabled_formats()
+                    .iter()
+                    .map(ToString::to_string)
+                    .chain(self.custom_formats())
+                    .collect(),
+            })
     }
     
     /// 验证编码参数 - 私有方法
@@ -546,32 +884,21 @@ impl CodecEngine {
     /// 像素格式转换 - 零成本抽象的私有实现
     fn convert_buffer<From, To>(&self, buffer: ImageBuffer<From>) -> Result<ImageBuffer<To>>
     where
-        From: Pixel + Into<To>,
-        To: Pixel,
+        From: Pixel + Into<To> + 'static,
+        To: Pixel + 'static,
     {
-        let converted_pixels: Vec<To> = buffer.pixels
-            .into_iter()
-            .map(|pixel| pixel.into())
-            .collect();
-
-        Ok(ImageBuffer {
-            pixels: converted_pixels,
-            dimensions: buffer.dimensions,
-            pixel_format: self.infer_pixel_format::<To>(),
-        })
-    }
-
-    /// 推断像素格式 - 编译时特化
-    fn infer_pixel_format<P: Pixel>(&self) -> PixelFormat {
-        match (P::CHANNEL_COUNT, P::BITS_PER_CHANNEL, P::HAS_ALPHA) {
-            (3, 8, false) => PixelFormat::Rgb8,
-            (4, 8, true) => PixelFormat::Rgba8,
-            (1, 8, false) => PixelFormat::Gray8,
-            (3, 16, false) => PixelFormat::Rgb16,
-            (4, 16, true) => PixelFormat::Rgba16,
-            (1, 16, false) => PixelFormat::Gray16,
-            _ => PixelFormat::Rgba8, // 默认回退
+        // RGBA -> 灰度 是最常见的降维转换，允许时走SIMD路径
+        if self.config.simd_enabled && TypeId::of::<To>() == TypeId::of::<Luma8>() {
+            let any: &dyn Any = &buffer;
+            if let Some(rgba) = any.downcast_ref::<ImageBuffer<Rgba8>>() {
+                let luma_buffer: Box<dyn Any> = Box::new(rgba.to_luma());
+                if let Ok(converted) = luma_buffer.downcast::<ImageBuffer<To>>() {
+                    return Ok(*converted);
+                }
+            }
         }
+        
+        Ok(buffer.convert_pixel_format())
     }
 }
 
@@ -591,6 +918,12 @@ impl CodecRegistry {
             tiff: Box::new(TiffCodec::new(config)?),
             gif: Box::new(GifCodec::new(config)?),
             ico: Box::new(IcoCodec::new(config)?),
+            pnm: Box::new(PnmCodec::new(config)?),
+            tga: Box::new(TgaCodec::new(config)?),
+            #[cfg(feature = "heif")]
+            heic: Box::new(HeicCodec::new(config)?),
+            #[cfg(feature = "jxl")]
+            jxl: Box::new(JxlCodec::new(config)?),
         })
     }
 
@@ -605,6 +938,16 @@ impl CodecRegistry {
             ImageFormat::Tiff => self.tiff.as_ref(),
             ImageFormat::Gif => self.gif.as_ref(),
             ImageFormat::Ico => self.ico.as_ref(),
+            ImageFormat::Pnm => self.pnm.as_ref(),
+            ImageFormat::Tga => self.tga.as_ref(),
+            #[cfg(feature = "heif")]
+            ImageFormat::Heic => self.heic.as_ref(),
+            #[cfg(not(feature = "heif"))]
+            ImageFormat::Heic => return Err(format_not_enabled(format)),
+            #[cfg(feature = "jxl")]
+            ImageFormat::Jxl => self.jxl.as_ref(),
+            #[cfg(not(feature = "jxl"))]
+            ImageFormat::Jxl => return Err(format_not_enabled(format)),
         };
         Ok(codec)
     }
@@ -624,6 +967,7 @@ impl<P: Pixel> ImageBuffer<P> {
             pixels,
             dimensions: ImageDimensions { width, height },
             pixel_format,
+            icc_profile: None,
         }
     }
 
@@ -648,6 +992,7 @@ impl<P: Pixel> ImageBuffer<P> {
             pixels: data,
             dimensions: ImageDimensions { width, height },
             pixel_format,
+            icc_profile: None,
         })
     }
 
@@ -682,6 +1027,83 @@ impl<P: Pixel> ImageBuffer<P> {
         }
     }
 
+    /// 复制矩形区域为新缓冲区 - 逐行整段拷贝，保留色彩配置文件
+    pub fn copy_region(&self, x: u32, y: u32, width: u32, height: u32) -> Result<ImageBuffer<P>> {
+        self.check_initialized()?;
+        self.check_region(x, y, width, height)?;
+
+        let stride = self.dimensions.width as usize;
+        let (x, width) = (x as usize, width as usize);
+        let mut pixels = Vec::with_capacity(width * height as usize);
+        for row in y as usize..(y + height) as usize {
+            let start = row * stride + x;
+            pixels.extend_from_slice(&self.pixels[start..start + width]);
+        }
+
+        Ok(ImageBuffer {
+            pixels,
+            dimensions: ImageDimensions { width: width as u32, height },
+            pixel_format: self.pixel_format,
+            icc_profile: self.icc_profile.clone(),
+        })
+    }
+
+    /// 将源缓冲区整体复制到(`dst_x`, `dst_y`)处 - 源必须完全落在本缓冲区内
+    pub fn blit(&mut self, src: &ImageBuffer<P>, dst_x: u32, dst_y: u32) -> Result<()> {
+        self.check_initialized()?;
+        src.check_initialized()?;
+        self.check_region(dst_x, dst_y, src.dimensions.width, src.dimensions.height)?;
+
+        let stride = self.dimensions.width as usize;
+        let width = src.dimensions.width as usize;
+        for (row, src_row) in src.pixels.chunks_exact(width).enumerate() {
+            let start = (dst_y as usize + row) * stride + dst_x as usize;
+            self.pixels[start..start + width].copy_from_slice(src_row);
+        }
+        Ok(())
+    }
+
+    /// 按行遍历 - 每项为长度等于宽度的像素切片
+    pub fn rows(&self) -> impl Iterator<Item = &[P]> {
+        self.pixels.chunks_exact(self.dimensions.width.max(1) as usize)
+    }
+
+    /// 按行并行遍历可变切片 - 供自定义卷积等逐行处理使用
+    #[cfg(feature = "parallel")]
+    pub fn par_rows_mut(&mut self) -> impl rayon::iter::IndexedParallelIterator<Item = &mut [P]> {
+        use rayon::prelude::*;
+        self.pixels.par_chunks_exact_mut(self.dimensions.width.max(1) as usize)
+    }
+
+    /// 校验像素数据与尺寸一致（`new`创建的缓冲区尚未填充）
+    fn check_initialized(&self) -> Result<()> {
+        if self.pixels.len() != self.dimensions.width as usize * self.dimensions.height as usize {
+            return Err(ImageError::InvalidParameters {
+                details: "Buffer not properly initialized".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// 校验矩形区域非空且完全落在缓冲区内
+    fn check_region(&self, x: u32, y: u32, width: u32, height: u32) -> Result<()> {
+        let fits = |origin: u32, extent: u32, limit: u32| {
+            origin.checked_add(extent).is_some_and(|end| end <= limit)
+        };
+        if width == 0 || height == 0
+            || !fits(x, width, self.dimensions.width)
+            || !fits(y, height, self.dimensions.height)
+        {
+            return Err(ImageError::InvalidParameters {
+                details: format!(
+                    "Region {}×{} at ({}, {}) exceeds buffer bounds {}×{}",
+                    width, height, x, y, self.dimensions.width, self.dimensions.height
+                ),
+            });
+        }
+        Ok(())
+    }
+
     // 只读访问器方法
     pub fn dimensions(&self) -> ImageDimensions { self.dimensions }
     pub fn pixel_format(&self) -> PixelFormat { self.pixel_format }
@@ -693,87 +1115,423 @@ impl<P: Pixel> ImageBuffer<P> {
     pub fn as_mut_slice(&mut self) -> &mut [P] {
         &mut self.pixels
     }
-}
-
-// =============================================================================
-// 格式检测器 - 独立的工具模块
-// =============================================================================
 
-/// 格式检测器 - 静态工具类
-pub struct FormatDetector;
+    /// 转换像素格式 - 保留尺寸和色彩配置文件
+    pub fn convert_pixel_format<Q: Pixel>(self) -> ImageBuffer<Q>
+    where
+        P: Into<Q>,
+    {
+        let pixels: Vec<Q> = self.pixels
+            .into_iter()
+            .map(|pixel| pixel.into())
+            .collect();
 
-impl FormatDetector {
-    /// 从数据检测格式 - 主要检测方法
-    pub fn detect(data: &[u8]) -> Result<ImageFormat> {
-        if data.len() < 8 {
-            return Err(ImageError::InvalidFormat {
-                format: "Data too short for format detection".to_string(),
-            });
+        ImageBuffer {
+            pixels,
+            dimensions: self.dimensions,
+            pixel_format: PixelFormat::of::<Q>(),
+            icc_profile: self.icc_profile,
         }
+    }
 
-        // JPEG文件头检测
-        if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
-            return Ok(ImageFormat::Jpeg);
-        }
+    /// 获取嵌入的ICC色彩配置文件
+    pub fn icc_profile(&self) -> Option<&[u8]> {
+        self.icc_profile.as_deref()
+    }
 
-        // PNG文件头检测
-        if data.starts_with(&[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A]) {
-            return Ok(ImageFormat::Png);
-        }
+    /// 设置ICC色彩配置文件 - 编码时按选项决定是否嵌入
+    pub fn set_icc_profile(&mut self, profile: Option<Vec<u8>>) {
+        self.icc_profile = profile;
+    }
+}
 
-        // WebP文件头检测
-        if data.len() >= 12 &&
-           data[0..4] == [0x52, 0x49, 0x46, 0x46] &&
-           data[8..12] == [0x57, 0x45, 0x42, 0x50] {
-            return Ok(ImageFormat::WebP);
+impl ImageBuffer<Rgba8> {
+    /// 转换为灰度缓冲区 - 启用`simd` feature时使用向量化路径
+    ///
+    /// 结果与`convert_pixel_format::<Luma8>()`逐位一致
+    pub fn to_luma(&self) -> ImageBuffer<Luma8> {
+        let (pixels, _) = processing::rgba_to_luma(&self.pixels, true);
+        ImageBuffer {
+            pixels,
+            dimensions: self.dimensions,
+            pixel_format: PixelFormat::of::<Luma8>(),
+            icc_profile: self.icc_profile.clone(),
         }
+    }
 
-        // AVIF文件头检测
+    /// 统计各通道与亮度直方图 - 单遍完成，大图按块并行
+    pub fn histogram(&self) -> Histogram {
+        processing::histogram(&self.pixels)
+    }
+}
+
+impl PixelFormat {
+    /// 推断像素类型对应的格式 - 编译时特化
+    pub fn of<P: Pixel>() -> Self {
+        match (P::CHANNEL_COUNT, P::BITS_PER_CHANNEL, P::HAS_ALPHA) {
+            (3, 8, false) => PixelFormat::Rgb8,
+            (4, 8, true) => PixelFormat::Rgba8,
+            (1, 8, false) => PixelFormat::Gray8,
+            (3, 16, false) => PixelFormat::Rgb16,
+            (4, 16, true) => PixelFormat::Rgba16,
+            (1, 16, false) => PixelFormat::Gray16,
+            _ => PixelFormat::Rgba8, // 默认回退
+        }
+    }
+    
+    /// 是否带透明通道
+    pub fn has_alpha(&self) -> bool {
+        matches!(self, PixelFormat::Rgba8 | PixelFormat::Rgba16)
+    }
+    
+    /// 是否为单通道灰度
+    pub fn is_gray(&self) -> bool {
+        matches!(self, PixelFormat::Gray8 | PixelFormat::Gray16)
+    }
+}
+
+// =============================================================================
+// 格式检测器 - 独立的工具模块
+// =============================================================================
+
+/// 格式检测器 - 静态工具类
+pub struct FormatDetector;
+
+/// 查找偏移签名时扫描的最大字节数
+const MAX_SIGNATURE_SCAN: usize = 32;
+
+/// 详细的格式检测结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatDetection {
+    /// 检测到的格式
+    format: ImageFormat,
+    /// 置信度 [0.0, 1.0]
+    confidence: f32,
+    /// 检测方式
+    method: DetectionMethod,
+    /// 格式子类型（如动画/静态）
+    subtype: Option<FormatSubtype>,
+    /// WebP首个数据块声明的编码方式
+    webp_variant: Option<WebPVariant>,
+    /// 图像数据在输入中的起始偏移
+    offset: usize,
+}
+
+/// 格式检测方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectionMethod {
+    /// 仅匹配文件头魔数
+    MagicBytes,
+    /// 解析了容器或数据块结构
+    StructureParsing,
+    /// 无可靠签名，依据调用方提供的扩展名推断
+    ExtensionHint,
+}
+
+/// 格式子类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatSubtype {
+    /// 静态单帧图像
+    Still,
+    /// 多帧动画
+    Animated,
+}
+
+/// WebP编码方式 - 由`WEBP`标识之后的首个数据块决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebPVariant {
+    /// 简单格式的有损编码（`VP8 `）
+    Lossy,
+    /// 简单格式的无损编码（`VP8L`）
+    Lossless,
+    /// 扩展格式（`VP8X`），可携带透明度、动画、ICC和元数据
+    Extended,
+}
+
+impl FormatDetection {
+    /// 创建检测结果 - 私有构造
+    fn new(format: ImageFormat, confidence: f32, method: DetectionMethod) -> Self {
+        Self {
+            format,
+            confidence,
+            method,
+            subtype: None,
+            webp_variant: None,
+            offset: 0,
+        }
+    }
+
+    /// 附加子类型 - 私有构造
+    fn with_subtype(mut self, subtype: FormatSubtype) -> Self {
+        self.subtype = Some(subtype);
+        self
+    }
+
+    /// 附加WebP编码方式 - 私有构造
+    fn with_webp_variant(mut self, variant: WebPVariant) -> Self {
+        self.webp_variant = Some(variant);
+        self
+    }
+
+    // 只读访问器方法
+    pub fn format(&self) -> ImageFormat { self.format }
+    pub fn confidence(&self) -> f32 { self.confidence }
+    pub fn method(&self) -> DetectionMethod { self.method }
+    pub fn subtype(&self) -> Option<FormatSubtype> { self.subtype }
+    pub fn webp_variant(&self) -> Option<WebPVariant> { self.webp_variant }
+    pub fn offset(&self) -> usize { self.offset }
+
+    /// 是否为动画
+    pub fn is_animated(&self) -> bool {
+        self.subtype == Some(FormatSubtype::Animated)
+    }
+}
+
+impl FormatDetector {
+    /// 从数据检测格式 - 主要检测方法
+    pub fn detect(data: &[u8]) -> Result<ImageFormat> {
+        Self::detect_detailed(data).map(|detection| detection.format())
+    }
+
+    /// 详细检测格式 - 返回置信度、检测方式、子类型和起始偏移
+    ///
+    /// 偏移0处无匹配时，在前`MAX_SIGNATURE_SCAN`字节内查找强签名，
+    /// 以容忍BOM或少量前导垃圾字节
+    pub fn detect_detailed(data: &[u8]) -> Result<FormatDetection> {
+        if data.len() < 8 {
+            return Err(ImageError::InvalidFormat {
+                format: "Data too short for format detection".to_string(),
+            });
+        }
+
+        if let Some(detection) = Self::match_signature(data) {
+            return Ok(detection);
+        }
+
+        // TGA 2.0 的签名位于文件尾部
+        if data.ends_with(TGA_FOOTER_SIGNATURE) {
+            return Ok(FormatDetection::new(ImageFormat::Tga, 0.9, DetectionMethod::MagicBytes));
+        }
+
+        // 偏移处只接受强签名，避免BM等短魔数的误判
+        let scan_end = MAX_SIGNATURE_SCAN.min(data.len() - 1);
+        for offset in 1..=scan_end {
+            if let Some(mut detection) = Self::match_signature(&data[offset..]) {
+                if detection.confidence >= 0.9 {
+                    detection.offset = offset;
+                    detection.confidence *= 0.8;
+                    return Ok(detection);
+                }
+            }
+        }
+
+        Err(ImageError::InvalidFormat {
+            format: "Unknown format - no matching file signature".to_string(),
+        })
+    }
+
+    /// 从文件头读取图像尺寸 - 不解码像素数据，无法识别或解析时返回`None`
+    pub fn read_dimensions(data: &[u8]) -> Option<ImageDimensions> {
+        let (width, height) = image::io::Reader::new(Cursor::new(data))
+            .with_guessed_format()
+            .ok()?
+            .into_dimensions()
+            .ok()?;
+        Some(ImageDimensions { width, height })
+    }
+
+    /// 带扩展名提示的详细检测 - 签名检测失败时回退到扩展名
+    ///
+    /// 只对没有可靠文件头的格式（如不带尾部签名的TGA 1.0）采用提示，
+    /// 且文件头必须结构合理，避免把损坏的数据当成提示的格式
+    pub fn detect_detailed_with_hint(data: &[u8], extension: &str) -> Result<FormatDetection> {
+        let error = match Self::detect_detailed(data) {
+            Ok(detection) => return Ok(detection),
+            Err(error) => error,
+        };
+
+        match ImageFormat::from_extension(extension) {
+            Some(ImageFormat::Tga) if is_plausible_tga_header(data) => {
+                Ok(FormatDetection::new(ImageFormat::Tga, 0.6, DetectionMethod::ExtensionHint))
+            }
+            _ => Err(error),
+        }
+    }
+
+    /// 匹配数据开头的文件签名
+    fn match_signature(data: &[u8]) -> Option<FormatDetection> {
+        // JPEG文件头检测
+        if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            return Some(FormatDetection::new(ImageFormat::Jpeg, 0.9, DetectionMethod::MagicBytes));
+        }
+
+        // PNG文件头检测
+        if data.starts_with(&[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A]) {
+            return Some(FormatDetection::new(ImageFormat::Png, 1.0, DetectionMethod::MagicBytes));
+        }
+
+        // WebP文件头检测 - RIFF容器需确认WEBP标识
         if data.len() >= 12 &&
-           data[4..8] == [0x66, 0x74, 0x79, 0x70] &&
-           data[8..12] == [0x61, 0x76, 0x69, 0x66] {
-            return Ok(ImageFormat::Avif);
+           data[0..4] == [0x52, 0x49, 0x46, 0x46] &&
+           data[8..12] == [0x57, 0x45, 0x42, 0x50] {
+            return Some(Self::inspect_webp(data));
+        }
+
+        // AVIF/HEIC文件头检测 - ISOBMFF容器需解析品牌
+        if data.len() >= 12 && data[4..8] == [0x66, 0x74, 0x79, 0x70] {
+            if let Some(detection) = Self::inspect_isobmff(data) {
+                return Some(detection);
+            }
+        }
+
+        // JPEG XL检测 - 12字节签名盒（容器）或2字节裸码流签名
+        if data.starts_with(JXL_CONTAINER_SIGNATURE) {
+            return Some(FormatDetection::new(ImageFormat::Jxl, 1.0, DetectionMethod::MagicBytes));
+        }
+        if data.starts_with(JXL_CODESTREAM_SIGNATURE) {
+            return Some(FormatDetection::new(ImageFormat::Jxl, 0.8, DetectionMethod::MagicBytes));
         }
 
         // BMP文件头检测
         if data.starts_with(&[0x42, 0x4D]) {
-            return Ok(ImageFormat::Bmp);
+            return Some(FormatDetection::new(ImageFormat::Bmp, 0.6, DetectionMethod::MagicBytes));
         }
 
         // TIFF文件头检测 (小端和大端)
         if data.starts_with(&[0x49, 0x49, 0x2A, 0x00]) ||
            data.starts_with(&[0x4D, 0x4D, 0x00, 0x2A]) {
-            return Ok(ImageFormat::Tiff);
+            return Some(FormatDetection::new(ImageFormat::Tiff, 0.9, DetectionMethod::MagicBytes));
         }
 
         // GIF文件头检测
         if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
-            return Ok(ImageFormat::Gif);
+            return Some(Self::inspect_gif(data));
         }
 
         // ICO文件头检测
         if data.len() >= 4 && data[0..4] == [0x00, 0x00, 0x01, 0x00] {
-            return Ok(ImageFormat::Ico);
+            return Some(FormatDetection::new(ImageFormat::Ico, 0.7, DetectionMethod::MagicBytes));
         }
 
-        Err(ImageError::InvalidFormat {
-            format: "Unknown format - no matching file signature".to_string(),
-        })
+        // PNM文件头检测 - P1到P6后跟空白
+        if is_pnm_header(data) {
+            return Some(FormatDetection::new(ImageFormat::Pnm, 0.8, DetectionMethod::MagicBytes));
+        }
+
+        None
     }
 
     /// 从扩展名猜测格式 - 辅助方法
     pub fn guess_from_extension(extension: &str) -> Option<ImageFormat> {
-        match extension.to_lowercase().as_str() {
-            "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
-            "png" => Some(ImageFormat::Png),
-            "webp" => Some(ImageFormat::WebP),
-            "avif" => Some(ImageFormat::Avif),
-            "bmp" => Some(ImageFormat::Bmp),
-            "tiff" | "tif" => Some(ImageFormat::Tiff),
-            "gif" => Some(ImageFormat::Gif),
-            "ico" => Some(ImageFormat::Ico),
-            _ => None,
+        ImageFormat::from_extension(extension)
+    }
+
+    /// 解析WebP首个数据块 - 识别编码方式，VP8X标志位声明动画
+    ///
+    /// 数据块被截断（VP8X不足10字节的头部）或类型未知时只按魔数识别，不报告子类型
+    fn inspect_webp(data: &[u8]) -> FormatDetection {
+        const VP8X_PAYLOAD_LEN: u32 = 10;
+        let detection = FormatDetection::new(ImageFormat::WebP, 0.9, DetectionMethod::MagicBytes);
+        let chunk_size = data.get(16..20).map(|size| u32::from_le_bytes([size[0], size[1], size[2], size[3]]));
+        let parsed = || FormatDetection::new(ImageFormat::WebP, 1.0, DetectionMethod::StructureParsing);
+
+        match (data.get(12..16), chunk_size) {
+            (Some(b"VP8X"), Some(size)) if size >= VP8X_PAYLOAD_LEN && data.len() >= 20 + VP8X_PAYLOAD_LEN as usize => {
+                let animated = data[20] & WEBP_FLAG_ANIMATION != 0;
+                parsed()
+                    .with_subtype(if animated { FormatSubtype::Animated } else { FormatSubtype::Still })
+                    .with_webp_variant(WebPVariant::Extended)
+            }
+            (Some(b"VP8 "), Some(_)) => parsed().with_subtype(FormatSubtype::Still).with_webp_variant(WebPVariant::Lossy),
+            (Some(b"VP8L"), Some(_)) => parsed().with_subtype(FormatSubtype::Still).with_webp_variant(WebPVariant::Lossless),
+            _ => detection,
+        }
+    }
+
+    /// 解析ISOBMFF的ftyp盒 - 通过主品牌和兼容品牌识别AVIF和HEIC
+    ///
+    /// AVIF文件通常也声明`mif1`兼容品牌，因此先匹配AVIF品牌
+    fn inspect_isobmff(data: &[u8]) -> Option<FormatDetection> {
+        let box_size = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+        let ftyp_end = box_size.clamp(16, data.len().max(16)).min(data.len());
+
+        // 主品牌位于偏移8，兼容品牌从偏移16开始
+        let mut brands = vec![&data[8..12]];
+        brands.extend(data.get(16..ftyp_end).unwrap_or(&[]).chunks_exact(4));
+
+        if brands.contains(&&b"avis"[..]) {
+            return Some(
+                FormatDetection::new(ImageFormat::Avif, 1.0, DetectionMethod::StructureParsing)
+                    .with_subtype(FormatSubtype::Animated),
+            );
+        }
+        if brands.contains(&&b"avif"[..]) {
+            return Some(
+                FormatDetection::new(ImageFormat::Avif, 1.0, DetectionMethod::StructureParsing)
+                    .with_subtype(FormatSubtype::Still),
+            );
+        }
+        if [b"heic", b"heix", b"mif1"].iter().any(|brand| brands.contains(&&brand[..])) {
+            return Some(FormatDetection::new(ImageFormat::Heic, 1.0, DetectionMethod::StructureParsing));
+        }
+
+        None
+    }
+
+    /// 遍历GIF数据块统计图像帧数 - 结构损坏时退回魔数结果
+    fn inspect_gif(data: &[u8]) -> FormatDetection {
+        match Self::count_gif_frames(data, 2) {
+            Some(frames) => {
+                FormatDetection::new(ImageFormat::Gif, 1.0, DetectionMethod::StructureParsing)
+                    .with_subtype(if frames > 1 { FormatSubtype::Animated } else { FormatSubtype::Still })
+            }
+            None => FormatDetection::new(ImageFormat::Gif, 0.9, DetectionMethod::MagicBytes),
+        }
+    }
+
+    /// 统计GIF图像描述符数量，最多数到`limit`
+    fn count_gif_frames(data: &[u8], limit: usize) -> Option<usize> {
+        // 跳过数据子块序列，返回序列结束后的位置
+        fn skip_sub_blocks(data: &[u8], mut pos: usize) -> Option<usize> {
+            loop {
+                let len = *data.get(pos)? as usize;
+                pos += 1;
+                if len == 0 {
+                    return Some(pos);
+                }
+                pos += len;
+            }
         }
+
+        // 逻辑屏幕描述符之后可能紧跟全局颜色表
+        let flags = *data.get(10)?;
+        let mut pos = 13;
+        if flags & 0x80 != 0 {
+            pos += 3 << ((flags & 0x07) + 1);
+        }
+
+        let mut frames = 0;
+        while frames < limit {
+            match *data.get(pos)? {
+                // 扩展块：标签 + 子块
+                0x21 => pos = skip_sub_blocks(data, pos + 2)?,
+                // 图像描述符：局部颜色表 + LZW最小码长 + 子块
+                0x2C => {
+                    let local_flags = *data.get(pos + 9)?;
+                    pos += 10;
+                    if local_flags & 0x80 != 0 {
+                        pos += 3 << ((local_flags & 0x07) + 1);
+                    }
+                    pos = skip_sub_blocks(data, pos + 1)?;
+                    frames += 1;
+                }
+                // 文件结束符
+                0x3B => break,
+                _ => return None,
+            }
+        }
+
+        Some(frames)
     }
 }
 
@@ -813,6 +1571,12 @@ impl CodecConfigBuilder {
         self
     }
 
+    /// 设置解码像素上限 - `None`表示不限制
+    pub fn max_decoded_pixels(mut self, limit: Option<u64>) -> Self {
+        self.config.max_decoded_pixels = limit;
+        self
+    }
+
     /// 设置质量优先级
     pub fn quality_priority(mut self, priority: QualityPriority) -> Self {
         self.config.quality_priority = priority;
@@ -838,6 +1602,7 @@ impl Default for CodecConfig {
             thread_pool_size: None,
             simd_enabled: true,
             memory_limit: None,
+            max_decoded_pixels: Some(DEFAULT_MAX_DECODED_PIXELS),
             quality_priority: QualityPriority::Balanced,
         }
     }
@@ -853,60 +1618,100 @@ impl JpegCodec {
     fn new(_config: &CodecConfig) -> Result<Self> {
         Ok(Self::default())
     }
-}
 
-impl Codec<Rgba8> for JpegCodec {
-    fn decode(&self, data: &[u8]) -> Result<ImageBuffer<Rgba8>> {
-        let img = image::load_from_memory_with_format(data, ImageCrateFormat::Jpeg)
-            .map_err(|e| ImageError::DecodeError {
+    /// 使用jpeg-encoder编码 - image库只支持4:4:4基线JPEG，渐进式和指定色度子采样时改用此路径
+    fn encode_configured(
+        pixel_data: &[u8],
+        dims: ImageDimensions,
+        color_type: image::ColorType,
+        quality: u8,
+        options: &ConversionOptions,
+    ) -> Result<Vec<u8>> {
+        let too_large = || ImageError::InvalidParameters {
+            details: format!("JPEG dimensions {}x{} exceed 65535", dims.width, dims.height),
+        };
+        let width = u16::try_from(dims.width).map_err(|_| too_large())?;
+        let height = u16::try_from(dims.height).map_err(|_| too_large())?;
+        let color_type = match color_type {
+            image::ColorType::L8 => jpeg_encoder::ColorType::Luma,
+            _ => jpeg_encoder::ColorType::Rgb,
+        };
+
+        let mut output = Vec::new();
+        let mut encoder = jpeg_encoder::Encoder::new(&mut output, quality);
+        if options.is_progressive() == Some(true) {
+            // 多扫描并优化哈夫曼表
+            encoder.set_progressive(true);
+            encoder.set_optimized_huffman_tables(true);
+        }
+        if let Some(subsampling) = options.chroma_subsampling() {
+            encoder.set_sampling_factor(match subsampling {
+                Subsampling::S444 => jpeg_encoder::SamplingFactor::R_4_4_4,
+                Subsampling::S422 => jpeg_encoder::SamplingFactor::R_4_2_2,
+                Subsampling::S420 => jpeg_encoder::SamplingFactor::R_4_2_0,
+            });
+        }
+        encoder
+            .encode(pixel_data, width, height, color_type)
+            .map_err(|e| ImageError::EncodeError {
                 format: "JPEG".to_string(),
                 message: e.to_string(),
                 source: Some(Box::new(e)),
             })?;
 
-        let rgba_img = img.to_rgba8();
-        let (width, height) = rgba_img.dimensions();
-
-        let pixels: Vec<Rgba8> = rgba_img
-            .pixels()
-            .map(|p| Rgba8 {
-                r: p.0[0],
-                g: p.0[1],
-                b: p.0[2],
-                a: p.0[3]
-            })
-            .collect();
+        Ok(output)
+    }
+}
 
-        Ok(ImageBuffer::from_raw(
-            width,
-            height,
-            pixels,
-            PixelFormat::Rgba8,
-        )?)
+impl Codec<Rgba8> for JpegCodec {
+    fn decode(&self, data: &[u8]) -> Result<ImageBuffer<Rgba8>> {
+        // CMYK需按Adobe标记决定是否反相，image库一律按反相处理
+        match inspect_jpeg_color(data) {
+            Some(JpegColorLayout { components: 4, adobe }) => decode_cmyk_jpeg(data, adobe),
+            _ => decode_with_image_crate(data, ImageCrateFormat::Jpeg, "JPEG"),
+        }
     }
 
     fn encode(&self, buffer: &ImageBuffer<Rgba8>, options: &ConversionOptions) -> Result<Vec<u8>> {
-        // JPEG 不支持 Alpha 通道，需转为 RGB
+        // JPEG 不支持 Alpha 通道，需转为 RGB；灰度输出只写单通道
         let dims = buffer.dimensions();
-        let rgb_data: Vec<u8> = buffer
-            .as_slice()
-            .iter()
-            .flat_map(|p| [p.r, p.g, p.b])
-            .collect();
+        let gray = match options.target_pixel_format() {
+            Some(target) => target.is_gray(),
+            None => options.is_grayscale(),
+        };
+        let (pixel_data, color_type) = if gray {
+            let gray_data: Vec<u8> = buffer.as_slice().iter().map(|p| p.r).collect();
+            (gray_data, image::ColorType::L8)
+        } else {
+            let rgb_data: Vec<u8> = buffer
+                .as_slice()
+                .iter()
+                .flat_map(|p| [p.r, p.g, p.b])
+                .collect();
+            (rgb_data, image::ColorType::Rgb8)
+        };
 
-        let mut output = Vec::new();
         let quality = (options.quality().unwrap_or(0.8) * 100.0) as u8;
-        let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut output, quality);
-
-        encoder
-            .write_image(&rgb_data, dims.width, dims.height, image::ColorType::Rgb8)
-            .map_err(|e| ImageError::EncodeError {
-                format: "JPEG".to_string(),
-                message: e.to_string(),
-                source: Some(Box::new(e)),
-            })?;
+        let output = if options.is_progressive() == Some(true) || options.chroma_subsampling().is_some() {
+            Self::encode_configured(&pixel_data, dims, color_type, quality, options)?
+        } else {
+            let mut output = Vec::new();
+            let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut output, quality);
+
+            encoder
+                .write_image(&pixel_data, dims.width, dims.height, color_type)
+                .map_err(|e| ImageError::EncodeError {
+                    format: "JPEG".to_string(),
+                    message: e.to_string(),
+                    source: Some(Box::new(e)),
+                })?;
+            output
+        };
 
-        Ok(output)
+        match buffer.icc_profile() {
+            Some(profile) if options.preserves_color_space() => Ok(embed_jpeg_icc_profile(output, profile)),
+            _ => Ok(output),
+        }
     }
 
     fn info(&self) -> CodecInfo {
@@ -931,6 +1736,74 @@ impl Codec<Rgba8> for JpegCodec {
     fn validate_format(&self, data: &[u8]) -> bool {
         data.len() >= 3 && data[0..3] == [0xFF, 0xD8, 0xFF]
     }
+
+    /// 质量0.82的渐进式JPEG - 网页图片体积与画质的常用折中
+    fn default_options(&self) -> ConversionOptions {
+        ConversionOptionsBuilder::new().quality(0.82).progressive(true).build()
+    }
+}
+
+/// JPEG的颜色布局 - 帧头分量数，以及是否带Adobe APP14标记
+struct JpegColorLayout {
+    components: u8,
+    adobe: bool,
+}
+
+/// 扫描JPEG标记段直到扫描开始 - 读取帧头分量数和Adobe APP14标记
+fn inspect_jpeg_color(data: &[u8]) -> Option<JpegColorLayout> {
+    let mut pos = 2;
+    let mut components = None;
+    let mut adobe = false;
+    while pos + 4 <= data.len() && data[pos] == 0xFF {
+        let marker = data[pos + 1];
+        if marker == 0xFF {
+            // 标记前的填充字节
+            pos += 1;
+            continue;
+        }
+        let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let segment = data.get(pos + 4..pos + 2 + length)?;
+        match marker {
+            0xDA => break,
+            0xEE => adobe |= segment.starts_with(b"Adobe"),
+            // SOF0-SOF15，排除DHT、JPG和DAC
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => components = segment.get(5).copied(),
+            _ => {}
+        }
+        pos += 2 + length;
+    }
+    components.map(|components| JpegColorLayout { components, adobe })
+}
+
+/// 解码CMYK/YCCK JPEG并转换为RGB
+///
+/// jpeg-decoder输出的是`255 - 存储值`。Adobe软件写出的CMYK按反相存储，
+/// 这时输出即墨量；没有Adobe标记的文件按常规CMYK存储，需要再反相一次。
+/// CMYK配置文件不适用于RGB输出，因此不保留ICC
+fn decode_cmyk_jpeg(data: &[u8], adobe_inverted: bool) -> Result<ImageBuffer<Rgba8>> {
+    let jpeg_error = |message: String| ImageError::DecodeError {
+        format: "JPEG".to_string(),
+        message,
+        source: None,
+    };
+
+    let mut decoder = jpeg_decoder::Decoder::new(Cursor::new(data));
+    let pixels = decoder.decode().map_err(|e| jpeg_error(e.to_string()))?;
+    let info = decoder.info().ok_or_else(|| jpeg_error("Missing frame header".to_string()))?;
+    if info.pixel_format != jpeg_decoder::PixelFormat::CMYK32 {
+        return decode_with_image_crate(data, ImageCrateFormat::Jpeg, "JPEG");
+    }
+
+    let ink = |value: u8| if adobe_inverted { value as u32 } else { 255 - value as u32 };
+    let rgba: Vec<Rgba8> = pixels
+        .chunks_exact(4)
+        .map(|cmyk| {
+            let white = 255 - ink(cmyk[3]);
+            let channel = |value: u8| (((255 - ink(value)) * white + 127) / 255) as u8;
+            Rgba { r: channel(cmyk[0]), g: channel(cmyk[1]), b: channel(cmyk[2]), a: 255 }
+        })
+        .collect();
+    ImageBuffer::from_raw(info.width as u32, info.height as u32, rgba, PixelFormat::Rgba8)
 }
 
 // 实现PNG编解码器
@@ -942,52 +1815,95 @@ impl PngCodec {
 
 impl Codec<Rgba8> for PngCodec {
     fn decode(&self, data: &[u8]) -> Result<ImageBuffer<Rgba8>> {
-        let img = image::load_from_memory_with_format(data, ImageCrateFormat::Png)
-            .map_err(|e| ImageError::DecodeError {
-                format: "PNG".to_string(),
-                message: e.to_string(),
-                source: Some(Box::new(e)),
-            })?;
-
-        let rgba_img = img.to_rgba8();
-        let (width, height) = rgba_img.dimensions();
-
-        let pixels: Vec<Rgba8> = rgba_img
-            .pixels()
-            .map(|p| Rgba8 {
-                r: p.0[0],
-                g: p.0[1],
-                b: p.0[2],
-                a: p.0[3]
-            })
-            .collect();
+        decode_with_image_crate(data, ImageCrateFormat::Png, "PNG")
+    }
 
-        Ok(ImageBuffer::from_raw(
-            width,
-            height,
-            pixels,
-            PixelFormat::Rgba8,
-        )?)
+    fn decode_with_options(&self, data: &[u8], options: &ConversionOptions) -> Result<ImageBuffer<Rgba8>> {
+        decode_with_bit_reduction(data, ImageCrateFormat::Png, "PNG", options.is_bit_reduction_dither())
     }
 
-    fn encode(&self, buffer: &ImageBuffer<Rgba8>, _options: &ConversionOptions) -> Result<Vec<u8>> {
+    fn encode(&self, buffer: &ImageBuffer<Rgba8>, options: &ConversionOptions) -> Result<Vec<u8>> {
         let dims = buffer.dimensions();
-        let rgba_data: Vec<u8> = buffer
-            .as_slice()
-            .iter()
-            .flat_map(|p| [p.r, p.g, p.b, p.a])
-            .collect();
 
-        let mut output = Vec::new();
-        let mut encoder = image::codecs::png::PngEncoder::new(&mut output);
+        let mut info = png::Info::with_size(dims.width, dims.height);
+        info.bit_depth = png::BitDepth::Eight;
+
+        // 索引色输出调色板索引；指定像素格式时按其通道布局输出；
+        // 灰度输出使用单通道（有透明度时附带Alpha）
+        let (pixel_data, color_type) = if let Some(colors) = options.palette_colors() {
+            let quantized = processing::quantize(buffer.as_slice(), colors as usize);
+            info.bit_depth = palette_bit_depth(quantized.palette.len());
+            info.palette = Some(Cow::Owned(quantized.palette.iter().flat_map(|p| [p.r, p.g, p.b]).collect()));
+            // 透明项排在调色板前部，tRNS只需覆盖到最后一个非不透明项
+            let trns_len = quantized.palette.iter().rposition(|p| p.a != u8::MAX).map_or(0, |i| i + 1);
+            if trns_len > 0 {
+                info.trns = Some(Cow::Owned(quantized.palette[..trns_len].iter().map(|p| p.a).collect()));
+            }
+            (quantized.indices, png::ColorType::Indexed)
+        } else if let Some(target) = options.target_pixel_format() {
+            // 透明度已在处理阶段合成、灰度已在处理阶段转换，这里只挑选通道
+            let pixels = buffer.as_slice().iter();
+            match target {
+                PixelFormat::Gray8 => (pixels.map(|p| p.r).collect(), png::ColorType::Grayscale),
+                PixelFormat::Rgb8 => (pixels.flat_map(|p| [p.r, p.g, p.b]).collect(), png::ColorType::Rgb),
+                _ => (buffer_to_rgba_image(buffer)?.into_raw(), png::ColorType::Rgba),
+            }
+        } else if options.is_grayscale() {
+            if buffer.as_slice().iter().all(|p| p.a == u8::MAX) {
+                let gray_data: Vec<u8> = buffer.as_slice().iter().map(|p| p.r).collect();
+                (gray_data, png::ColorType::Grayscale)
+            } else {
+                let gray_data: Vec<u8> = buffer.as_slice().iter().flat_map(|p| [p.r, p.a]).collect();
+                (gray_data, png::ColorType::GrayscaleAlpha)
+            }
+        } else {
+            (buffer_to_rgba_image(buffer)?.into_raw(), png::ColorType::Rgba)
+        };
+        info.color_type = color_type;
+        let bit_depth = info.bit_depth as u8;
+        if options.preserves_color_space() {
+            info.icc_profile = buffer.icc_profile().map(Cow::Borrowed);
+        }
 
-        encoder
-            .write_image(&rgba_data, dims.width, dims.height, image::ColorType::Rgba8)
-            .map_err(|e| ImageError::EncodeError {
-                format: "PNG".to_string(),
-                message: e.to_string(),
-                source: Some(Box::new(e)),
-            })?;
+        let filter_type = options.png_filter().unwrap_or(self.filter_type);
+        let strategy = options.png_compression_strategy().unwrap_or(self.compression_strategy);
+        let interlaced = options.is_png_interlaced();
+        info.interlaced = interlaced;
+
+        let mut output = Vec::new();
+        {
+            let mut encoder = png::Encoder::with_info(&mut output, info).map_err(png_encode_error)?;
+            let level = options.compression_level().unwrap_or(self.compression_level);
+            if !interlaced && strategy == CompressionStrategy::Default {
+                encoder.set_compression(png_compression(level));
+                let (filter, adaptive) = filter_type.to_png_filter();
+                encoder.set_filter(filter);
+                encoder.set_adaptive_filter(adaptive);
+
+                let pixel_data = if bit_depth < 8 {
+                    pixel_data
+                        .chunks_exact(dims.width as usize)
+                        .flat_map(|row| pack_indices(row, bit_depth))
+                        .collect()
+                } else {
+                    pixel_data
+                };
+                let mut writer = encoder.write_header().map_err(png_encode_error)?;
+                writer.write_image_data(&pixel_data).map_err(png_encode_error)?;
+                writer.finish().map_err(png_encode_error)?;
+            } else {
+                // png库只写非隔行数据且不支持压缩策略，自行滤波和压缩后写入IDAT
+                let bytes_per_pixel = color_type.samples();
+                let scanlines =
+                    png_filtered_scanlines(&pixel_data, dims, bytes_per_pixel, bit_depth, interlaced, filter_type);
+                let compressed = zlib_compress(&scanlines, level, strategy);
+
+                encoder.validate_sequence(false);
+                let mut writer = encoder.write_header().map_err(png_encode_error)?;
+                writer.write_chunk(png::chunk::IDAT, &compressed).map_err(png_encode_error)?;
+                writer.finish().map_err(png_encode_error)?;
+            }
+        }
 
         Ok(output)
     }
@@ -1003,7 +1919,7 @@ impl Codec<Rgba8> for PngCodec {
             quality_features: QualityFeatures {
                 supports_lossless: true,
                 supports_lossy: false,
-                supports_progressive: false,
+                supports_progressive: true,
                 supports_transparency: true,
                 supports_animation: false,
                 max_quality_level: 9,
@@ -1014,63 +1930,1931 @@ impl Codec<Rgba8> for PngCodec {
     fn validate_format(&self, data: &[u8]) -> bool {
         data.len() >= 8 && data[0..8] == [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A]
     }
+
+    /// 最高压缩级别9，逐行自适应滤波 - 无损格式只用编码时间换体积
+    fn default_options(&self) -> ConversionOptions {
+        ConversionOptionsBuilder::new()
+            .compression_level(9)
+            .png_filter(PngFilter::Adaptive)
+            .build()
+    }
 }
 
-// 为其他格式实现存根
-macro_rules! impl_codec_stub {
-    ($codec:ident, $format:expr, $name:expr, $lossy:expr, $transparency:expr, $animation:expr) => {
-        impl $codec {
-            fn new(_config: &CodecConfig) -> Result<Self> {
-                Ok(Self::default())
-            }
+impl PngFilter {
+    /// 映射为png库的滤波器设置
+    fn to_png_filter(self) -> (png::FilterType, png::AdaptiveFilterType) {
+        match self {
+            PngFilter::None => (png::FilterType::NoFilter, png::AdaptiveFilterType::NonAdaptive),
+            PngFilter::Sub => (png::FilterType::Sub, png::AdaptiveFilterType::NonAdaptive),
+            PngFilter::Up => (png::FilterType::Up, png::AdaptiveFilterType::NonAdaptive),
+            PngFilter::Average => (png::FilterType::Avg, png::AdaptiveFilterType::NonAdaptive),
+            PngFilter::Paeth => (png::FilterType::Paeth, png::AdaptiveFilterType::NonAdaptive),
+            PngFilter::Adaptive => (png::FilterType::Sub, png::AdaptiveFilterType::Adaptive),
         }
+    }
+}
 
-        impl Codec<Rgba8> for $codec {
-            fn decode(&self, _data: &[u8]) -> Result<ImageBuffer<Rgba8>> {
-                Err(ImageError::UnsupportedOperation {
-                    operation: format!("{} decode not yet implemented", $name),
-                })
-            }
+/// Adam7隔行扫描的7趟采样 - (起始x, 起始y, x步长, y步长)
+const ADAM7_PASSES: [(u32, u32, u32, u32); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+/// 调色板大小所需的最小PNG位深
+fn palette_bit_depth(palette_len: usize) -> png::BitDepth {
+    match palette_len {
+        0..=2 => png::BitDepth::One,
+        3..=4 => png::BitDepth::Two,
+        5..=16 => png::BitDepth::Four,
+        _ => png::BitDepth::Eight,
+    }
+}
 
-            fn encode(&self, _buffer: &ImageBuffer<Rgba8>, _options: &ConversionOptions) -> Result<Vec<u8>> {
-                Err(ImageError::UnsupportedOperation {
-                    operation: format!("{} encode not yet implemented", $name),
-                })
-            }
+/// 将一行调色板索引按位深打包 - 高位在前，行尾补零到整字节
+fn pack_indices(row: &[u8], bit_depth: u8) -> Vec<u8> {
+    let per_byte = (8 / bit_depth) as usize;
+    row.chunks(per_byte)
+        .map(|indices| {
+            indices
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (i, &index)| byte | index << (8 - bit_depth as usize * (i + 1)))
+        })
+        .collect()
+}
 
-            fn info(&self) -> CodecInfo {
-                CodecInfo {
-                    format: $format,
-                    name: format!("{} Codec", $name),
-                    version: "1.0.0".to_string(),
-                    supports_decode: false, // TODO: 实现后改为true
-                    supports_encode: false, // TODO: 实现后改为true
-                    performance_level: PerformanceLevel::Balanced,
-                    quality_features: QualityFeatures {
-                        supports_lossless: !$lossy,
-                        supports_lossy: $lossy,
-                        supports_progressive: false,
-                        supports_transparency: $transparency,
-                        supports_animation: $animation,
-                        max_quality_level: 100,
-                    },
-                }
+/// 生成PNG的IDAT原始数据 - 每行为滤波类型字节加滤波后的像素
+///
+/// 隔行时按Adam7逐趟输出子图像，每趟的首行以全零行作为上一行；
+/// 位深低于8时`pixel_data`为每像素一字节的调色板索引，逐行打包后再滤波
+fn png_filtered_scanlines(
+    pixel_data: &[u8],
+    dims: ImageDimensions,
+    bytes_per_pixel: usize,
+    bit_depth: u8,
+    interlaced: bool,
+    filter: PngFilter,
+) -> Vec<u8> {
+    let full_pass = [(0, 0, 1, 1)];
+    let passes: &[(u32, u32, u32, u32)] = if interlaced { &ADAM7_PASSES } else { &full_pass };
+    let stride = dims.width as usize * bytes_per_pixel;
+
+    let mut output = Vec::with_capacity(pixel_data.len() + dims.height as usize * 8);
+    for &(x0, y0, dx, dy) in passes {
+        if x0 >= dims.width || y0 >= dims.height {
+            continue;
+        }
+        let pass_width = (dims.width - x0).div_ceil(dx) as usize;
+        let mut row = vec![0u8; pass_width * bytes_per_pixel];
+        let mut previous = Vec::new();
+
+        for y in (y0..dims.height).step_by(dy as usize) {
+            let source = &pixel_data[y as usize * stride..][..stride];
+            for (i, x) in (x0 as usize..dims.width as usize).step_by(dx as usize).enumerate() {
+                row[i * bytes_per_pixel..][..bytes_per_pixel]
+                    .copy_from_slice(&source[x * bytes_per_pixel..][..bytes_per_pixel]);
             }
-
-            fn validate_format(&self, _data: &[u8]) -> bool {
-                false // TODO: 实现具体的格式验证
+            let scanline = if bit_depth < 8 { pack_indices(&row, bit_depth) } else { row.clone() };
+            if previous.is_empty() {
+                previous = vec![0u8; scanline.len()];
             }
-        }
-    };
-}
-
-// Default实现
-impl Default for JpegCodec {
-    fn default() -> Self {
-        Self {
-            quality_tables: None,
-            optimization_level: 6,
-            dct_impl: DctImplementation::Standard,
+            filter_scanline(&scanline, &previous, bytes_per_pixel, filter, &mut output);
+            previous = scanline;
+        }
+    }
+    output
+}
+
+/// 滤波一行扫描线并追加到输出 - 自适应时选择残差绝对值之和最小的滤波器
+fn filter_scanline(row: &[u8], previous: &[u8], bytes_per_pixel: usize, filter: PngFilter, output: &mut Vec<u8>) {
+    let apply = |kind: u8, out: &mut Vec<u8>| {
+        out.push(kind);
+        for i in 0..row.len() {
+            let left = if i >= bytes_per_pixel { row[i - bytes_per_pixel] } else { 0 };
+            let up = previous[i];
+            let up_left = if i >= bytes_per_pixel { previous[i - bytes_per_pixel] } else { 0 };
+            let predictor = match kind {
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                4 => paeth_predictor(left, up, up_left),
+                _ => 0,
+            };
+            out.push(row[i].wrapping_sub(predictor));
+        }
+    };
+
+    let kind = match filter {
+        PngFilter::None => 0,
+        PngFilter::Sub => 1,
+        PngFilter::Up => 2,
+        PngFilter::Average => 3,
+        PngFilter::Paeth => 4,
+        PngFilter::Adaptive => {
+            let mut candidate = Vec::with_capacity(row.len() + 1);
+            let cost = |kind: u8, candidate: &mut Vec<u8>| -> u64 {
+                candidate.clear();
+                apply(kind, candidate);
+                candidate[1..].iter().map(|&b| (b as i8).unsigned_abs() as u64).sum()
+            };
+            (0..=4).min_by_key(|&kind| cost(kind, &mut candidate)).unwrap_or(0)
+        }
+    };
+    apply(kind, output);
+}
+
+/// Paeth预测器 - 选择left、up、up_left中最接近 left + up - up_left 的值
+fn paeth_predictor(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let distance_left = (estimate - left as i16).abs();
+    let distance_up = (estimate - up as i16).abs();
+    let distance_up_left = (estimate - up_left as i16).abs();
+    if distance_left <= distance_up && distance_left <= distance_up_left {
+        left
+    } else if distance_up <= distance_up_left {
+        up
+    } else {
+        up_left
+    }
+}
+
+/// 压缩级别映射为png库的压缩档位 - 0-1最快，2-7默认，8-9最高
+fn png_compression(level: u8) -> png::Compression {
+    match level {
+        0..=1 => png::Compression::Fast,
+        2..=7 => png::Compression::Default,
+        _ => png::Compression::Best,
+    }
+}
+
+/// 按指定级别和匹配策略压缩为zlib数据流
+fn zlib_compress(data: &[u8], level: u8, strategy: CompressionStrategy) -> Vec<u8> {
+    use miniz_oxide::deflate::core::{self as deflate, CompressorOxide, TDEFLFlush, TDEFLStatus};
+
+    let strategy = match strategy {
+        CompressionStrategy::Default => deflate::CompressionStrategy::Default,
+        CompressionStrategy::Filtered => deflate::CompressionStrategy::Filtered,
+        CompressionStrategy::HuffmanOnly => deflate::CompressionStrategy::HuffmanOnly,
+        CompressionStrategy::Rle => deflate::CompressionStrategy::RLE,
+        CompressionStrategy::Fixed => deflate::CompressionStrategy::Fixed,
+    };
+    // window_bits为正时带zlib头和校验和
+    let flags = deflate::create_comp_flags_from_zip_params(level.min(9) as i32, 15, strategy as i32);
+    let mut compressor = CompressorOxide::new(flags);
+
+    let mut output = Vec::with_capacity(data.len() / 2);
+    let (status, _) = deflate::compress_to_output(&mut compressor, data, TDEFLFlush::Finish, |chunk| {
+        output.extend_from_slice(chunk);
+        true
+    });
+    debug_assert_eq!(status, TDEFLStatus::Done);
+    output
+}
+
+/// 包装png库的编码错误
+fn png_encode_error(e: png::EncodingError) -> ImageError {
+    ImageError::EncodeError {
+        format: "PNG".to_string(),
+        message: e.to_string(),
+        source: Some(Box::new(e)),
+    }
+}
+
+/// JPEG APP2段中ICC配置文件的标识
+const JPEG_ICC_MARKER: &[u8] = b"ICC_PROFILE\0";
+/// 单个APP2段可容纳的ICC数据上限
+const JPEG_ICC_CHUNK_SIZE: usize = 65535 - 2 - 14;
+
+/// 将ICC配置文件以APP2段写入JPEG - 放在SOI和JFIF头之后
+fn embed_jpeg_icc_profile(jpeg: Vec<u8>, profile: &[u8]) -> Vec<u8> {
+    let mut insert_at = 2;
+    if jpeg.len() >= 6 && jpeg[2..4] == [0xFF, 0xE0] {
+        insert_at = 4 + u16::from_be_bytes([jpeg[4], jpeg[5]]) as usize;
+    }
+
+    let chunks: Vec<&[u8]> = profile.chunks(JPEG_ICC_CHUNK_SIZE).collect();
+    let mut segments = Vec::with_capacity(profile.len() + chunks.len() * 18);
+    for (index, chunk) in chunks.iter().enumerate() {
+        segments.extend_from_slice(&[0xFF, 0xE2]);
+        segments.extend_from_slice(&((chunk.len() + 2 + JPEG_ICC_MARKER.len() + 2) as u16).to_be_bytes());
+        segments.extend_from_slice(JPEG_ICC_MARKER);
+        segments.push(index as u8 + 1);
+        segments.push(chunks.len() as u8);
+        segments.extend_from_slice(chunk);
+    }
+
+    let mut output = Vec::with_capacity(jpeg.len() + segments.len());
+    output.extend_from_slice(&jpeg[..insert_at]);
+    output.extend_from_slice(&segments);
+    output.extend_from_slice(&jpeg[insert_at..]);
+    output
+}
+
+// 实现GIF编解码器
+impl GifCodec {
+    fn new(_config: &CodecConfig) -> Result<Self> {
+        Ok(Self::default())
+    }
+
+    /// 调色板量化速度 - 启用调色板优化时使用更精细的量化
+    fn quantization_speed(&self) -> i32 {
+        if self.palette_optimization { 10 } else { 30 }
+    }
+
+    /// 转换为待编码的帧图像 - 指定调色板大小时先量化
+    ///
+    /// 不超过256色的帧由GIF编码器直接使用精确调色板，不再经过NeuQuant
+    fn frame_image(buffer: &ImageBuffer<Rgba8>, options: &ConversionOptions) -> Result<image::RgbaImage> {
+        let mut image = buffer_to_rgba_image(buffer)?;
+        if let Some(colors) = options.palette_colors() {
+            let quantized = processing::quantize(buffer.as_slice(), colors as usize);
+            for (pixel, &index) in image.pixels_mut().zip(&quantized.indices) {
+                let color = quantized.palette[index as usize];
+                *pixel = image::Rgba([color.r, color.g, color.b, color.a]);
+            }
+        }
+        Ok(image)
+    }
+
+    /// 写出GIF数据流 - 单帧和多帧共用
+    fn write_gif(&self, frames: Vec<image::Frame>, loop_count: Option<u16>) -> Result<Vec<u8>> {
+        let repeat = match loop_count {
+            Some(count) if count > 0 => image::codecs::gif::Repeat::Finite(count),
+            _ => image::codecs::gif::Repeat::Infinite,
+        };
+
+        let mut output = Vec::new();
+        {
+            let mut encoder = image::codecs::gif::GifEncoder::new_with_speed(
+                &mut output,
+                self.quantization_speed(),
+            );
+            encoder
+                .set_repeat(repeat)
+                .map_err(|e| encode_error("GIF", e))?;
+            encoder
+                .encode_frames(frames)
+                .map_err(|e| encode_error("GIF", e))?;
+        }
+
+        Ok(output)
+    }
+}
+
+impl Codec<Rgba8> for GifCodec {
+    fn decode(&self, data: &[u8]) -> Result<ImageBuffer<Rgba8>> {
+        decode_with_image_crate(data, ImageCrateFormat::Gif, "GIF")
+    }
+
+    fn encode(&self, buffer: &ImageBuffer<Rgba8>, options: &ConversionOptions) -> Result<Vec<u8>> {
+        let frame = image::Frame::new(Self::frame_image(buffer, options)?);
+        self.write_gif(vec![frame], options.loop_count())
+    }
+
+    fn decode_frames(&self, data: &[u8]) -> Result<Vec<AnimationFrame<Rgba8>>> {
+        self.decode_frames_cancellable(data, &AtomicBool::new(false))
+    }
+
+    fn encode_frames(&self, frames: &[AnimationFrame<Rgba8>], options: &ConversionOptions) -> Result<Vec<u8>> {
+        self.encode_frames_cancellable(frames, options, &AtomicBool::new(false))
+    }
+
+    /// 逐帧解码，每帧开始前检查取消信号
+    fn decode_frames_cancellable(&self, data: &[u8], cancel: &dyn Cancellation) -> Result<Vec<AnimationFrame<Rgba8>>> {
+        if !self.animation_support {
+            return Ok(vec![(self.decode(data)?, Duration::ZERO)]);
+        }
+
+        let decoder = image::codecs::gif::GifDecoder::new(Cursor::new(data))
+            .map_err(|e| decode_error("GIF", e))?;
+
+        collect_frames(decoder.into_frames(), "GIF", cancel)
+    }
+
+    /// 逐帧量化，每帧开始前检查取消信号
+    fn encode_frames_cancellable(
+        &self,
+        frames: &[AnimationFrame<Rgba8>],
+        options: &ConversionOptions,
+        cancel: &dyn Cancellation,
+    ) -> Result<Vec<u8>> {
+        let total = frames.len();
+        let frames = frames
+            .iter()
+            .enumerate()
+            .map(|(index, (buffer, delay))| {
+                if cancel.is_cancelled() {
+                    return Err(ImageError::cancelled("GIF frame encode", index, total));
+                }
+                Ok(image::Frame::from_parts(
+                    Self::frame_image(buffer, options)?,
+                    0,
+                    0,
+                    image::Delay::from_saturating_duration(*delay),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.write_gif(frames, options.loop_count())
+    }
+
+    fn info(&self) -> CodecInfo {
+        CodecInfo {
+            format: ImageFormat::Gif,
+            name: "GIF Codec".to_string(),
+            version: "1.0.0".to_string(),
+            supports_decode: true,
+            supports_encode: true,
+            performance_level: PerformanceLevel::Balanced,
+            quality_features: QualityFeatures {
+                supports_lossless: true,
+                supports_lossy: false,
+                supports_progressive: false,
+                supports_transparency: true,
+                supports_animation: self.animation_support,
+                max_quality_level: 100,
+            },
+        }
+    }
+
+    fn validate_format(&self, data: &[u8]) -> bool {
+        data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a")
+    }
+}
+
+// 实现PNM编解码器
+impl PnmCodec {
+    fn new(_config: &CodecConfig) -> Result<Self> {
+        Ok(Self)
+    }
+
+    /// 解析`pnm_subtype`自定义参数 - 默认二进制编码
+    fn sample_encoding(options: &ConversionOptions) -> Result<image::codecs::pnm::SampleEncoding> {
+        use image::codecs::pnm::SampleEncoding;
+
+        match options.custom_param("pnm_subtype") {
+            None | Some("binary") => Ok(SampleEncoding::Binary),
+            Some("ascii") => Ok(SampleEncoding::Ascii),
+            Some(other) => Err(ImageError::InvalidParameters {
+                details: format!("Unknown pnm_subtype '{}', expected 'binary' or 'ascii'", other),
+            }),
+        }
+    }
+}
+
+impl Codec<Rgba8> for PnmCodec {
+    fn decode(&self, data: &[u8]) -> Result<ImageBuffer<Rgba8>> {
+        decode_with_image_crate(data, ImageCrateFormat::Pnm, "PNM")
+    }
+
+    fn encode(&self, buffer: &ImageBuffer<Rgba8>, options: &ConversionOptions) -> Result<Vec<u8>> {
+        use image::codecs::pnm::{PnmEncoder, PnmSubtype};
+
+        let encoding = Self::sample_encoding(options)?;
+        let dims = buffer.dimensions();
+        let rgba_image = buffer_to_rgba_image(buffer)?;
+
+        // 灰度输出写PGM，其余写PPM；PNM不支持透明度，alpha通道被丢弃
+        let mut output = Vec::new();
+        let gray = match options.target_pixel_format() {
+            Some(target) => target.is_gray(),
+            None => options.is_grayscale(),
+        };
+        if gray {
+            let gray = image::DynamicImage::ImageRgba8(rgba_image).into_luma8();
+            PnmEncoder::new(&mut output)
+                .with_subtype(PnmSubtype::Graymap(encoding))
+                .write_image(gray.as_raw(), dims.width, dims.height, image::ColorType::L8)
+                .map_err(|e| encode_error("PNM", e))?;
+        } else {
+            let rgb = image::DynamicImage::ImageRgba8(rgba_image).into_rgb8();
+            PnmEncoder::new(&mut output)
+                .with_subtype(PnmSubtype::Pixmap(encoding))
+                .write_image(rgb.as_raw(), dims.width, dims.height, image::ColorType::Rgb8)
+                .map_err(|e| encode_error("PNM", e))?;
+        }
+
+        Ok(output)
+    }
+
+    fn info(&self) -> CodecInfo {
+        CodecInfo {
+            format: ImageFormat::Pnm,
+            name: "PNM Codec".to_string(),
+            version: "1.0.0".to_string(),
+            supports_decode: true,
+            supports_encode: true,
+            performance_level: PerformanceLevel::Fast,
+            quality_features: QualityFeatures {
+                supports_lossless: true,
+                supports_lossy: false,
+                supports_progressive: false,
+                supports_transparency: false,
+                supports_animation: false,
+                max_quality_level: 100,
+            },
+        }
+    }
+
+    fn validate_format(&self, data: &[u8]) -> bool {
+        is_pnm_header(data)
+    }
+}
+
+/// PNM魔数检查 - `P1`到`P6`后必须紧跟空白字符
+fn is_pnm_header(data: &[u8]) -> bool {
+    matches!(data, [b'P', b'1'..=b'6', separator, ..] if separator.is_ascii_whitespace())
+}
+
+// 实现AVIF编解码器
+impl AvifCodec {
+    fn new(_config: &CodecConfig) -> Result<Self> {
+        Ok(Self::default())
+    }
+
+    /// 合并转换选项与默认编码设置
+    ///
+    /// `quality` 映射到颜色质量，自定义参数 `avif_speed` (0-10) 和
+    /// `avif_quality_alpha` (0-100) 分别覆盖编码速度和Alpha通道质量
+    fn resolve_settings(&self, options: &ConversionOptions) -> Result<AvifEncoderSettings> {
+        let mut settings = self.encoder_settings.clone();
+
+        if let Some(quality) = options.quality() {
+            settings.quality = (quality * 100.0).round() as u8;
+        }
+        if let Some(speed) = custom_u8_param(options, "avif_speed", 10)? {
+            settings.speed = speed;
+        }
+        if let Some(quality_alpha) = custom_u8_param(options, "avif_quality_alpha", 100)? {
+            settings.quality_alpha = quality_alpha;
+        }
+
+        Ok(settings)
+    }
+
+    /// 编码线程数 - 禁用分块或要求可复现输出时单线程编码，自定义分块按块数分配线程
+    #[cfg(feature = "avif")]
+    fn encoder_threads(&self, options: &ConversionOptions) -> Option<usize> {
+        if options.is_deterministic() {
+            return Some(1);
+        }
+        match self.tiling_mode {
+            TilingMode::Disabled => Some(1),
+            TilingMode::Auto => None,
+            TilingMode::Custom { cols, rows } => Some((cols as usize * rows as usize).max(1)),
+        }
+    }
+
+    #[cfg(feature = "avif")]
+    fn encode_with_ravif(
+        &self,
+        buffer: &ImageBuffer<Rgba8>,
+        settings: &AvifEncoderSettings,
+        options: &ConversionOptions,
+    ) -> Result<Vec<u8>> {
+        let dims = buffer.dimensions();
+        let pixels: Vec<ravif::RGBA8> = buffer
+            .as_slice()
+            .iter()
+            .map(|p| ravif::RGBA8::new(p.r, p.g, p.b, p.a))
+            .collect();
+
+        // ravif的速度范围为1-10
+        let encoded = ravif::Encoder::new()
+            .with_quality(settings.quality as f32)
+            .with_alpha_quality(settings.quality_alpha as f32)
+            .with_speed(settings.speed.clamp(1, 10))
+            .with_num_threads(self.encoder_threads(options))
+            .encode_rgba(ravif::Img::new(&pixels[..], dims.width as usize, dims.height as usize))
+            .map_err(|e| ImageError::EncodeError {
+                format: "AVIF".to_string(),
+                message: e.to_string(),
+                source: Some(Box::new(e)),
+            })?;
+
+        Ok(encoded.avif_file)
+    }
+}
+
+impl Codec<Rgba8> for AvifCodec {
+    fn decode(&self, _data: &[u8]) -> Result<ImageBuffer<Rgba8>> {
+        Err(ImageError::UnsupportedOperation {
+            operation: "AVIF decode not yet implemented".to_string(),
+        })
+    }
+
+    fn encode(&self, buffer: &ImageBuffer<Rgba8>, options: &ConversionOptions) -> Result<Vec<u8>> {
+        // 参数错误优先于功能缺失报告
+        let settings = self.resolve_settings(options)?;
+
+        #[cfg(feature = "avif")]
+        {
+            self.encode_with_ravif(buffer, &settings, options)
+        }
+
+        #[cfg(not(feature = "avif"))]
+        {
+            let _ = (buffer, settings);
+            Err(ImageError::UnsupportedOperation {
+                operation: "AVIF encode requires the `avif` feature".to_string(),
+            })
+        }
+    }
+
+    fn info(&self) -> CodecInfo {
+        CodecInfo {
+            format: ImageFormat::Avif,
+            name: "AVIF Codec".to_string(),
+            version: "1.0.0".to_string(),
+            supports_decode: false,
+            supports_encode: cfg!(feature = "avif"),
+            performance_level: PerformanceLevel::HighQuality,
+            quality_features: QualityFeatures {
+                supports_lossless: false,
+                supports_lossy: true,
+                supports_progressive: false,
+                supports_transparency: true,
+                supports_animation: false,
+                max_quality_level: 100,
+            },
+        }
+    }
+
+    fn validate_format(&self, data: &[u8]) -> bool {
+        data.len() >= 12
+            && data[4..8] == *b"ftyp"
+            && FormatDetector::inspect_isobmff(data).is_some_and(|d| d.format == ImageFormat::Avif)
+    }
+
+    /// 质量0.7、编码速度6 - AVIF在较低质量下仍保持较好的主观画质
+    fn default_options(&self) -> ConversionOptions {
+        ConversionOptionsBuilder::new().quality(0.7).custom_param("avif_speed", "6").build()
+    }
+}
+
+/// 读取数值型自定义参数 - 超出 `0..=max` 或无法解析时报错
+fn custom_u8_param(options: &ConversionOptions, key: &str, max: u8) -> Result<Option<u8>> {
+    let Some(value) = options.custom_param(key) else {
+        return Ok(None);
+    };
+
+    match value.trim().parse::<u8>() {
+        Ok(parsed) if parsed <= max => Ok(Some(parsed)),
+        _ => Err(ImageError::InvalidParameters {
+            details: format!("{} must be an integer in 0..={}, got '{}'", key, max, value),
+        }),
+    }
+}
+
+// 实现ICO编解码器
+impl IcoCodec {
+    fn new(_config: &CodecConfig) -> Result<Self> {
+        Ok(Self::default())
+    }
+
+    /// 单尺寸编码的目标边长 - 超出上限时取不超过256的最大标准尺寸
+    fn target_size(&self, dims: ImageDimensions) -> Option<u32> {
+        let longest = dims.width.max(dims.height);
+        if longest <= ICO_MAX_SIZE {
+            return None;
+        }
+        self.supported_sizes.iter().copied().filter(|&size| size <= ICO_MAX_SIZE).max()
+    }
+
+    /// 写出ICO目录和全部条目 - 每个条目以PNG存储
+    fn write_ico(images: &[image::RgbaImage]) -> Result<Vec<u8>> {
+        use image::codecs::ico::{IcoEncoder, IcoFrame};
+
+        let frames = images
+            .iter()
+            .map(|img| IcoFrame::as_png(img.as_raw(), img.width(), img.height(), image::ColorType::Rgba8))
+            .collect::<image::ImageResult<Vec<_>>>()
+            .map_err(|e| encode_error("ICO", e))?;
+
+        let mut output = Vec::new();
+        IcoEncoder::new(&mut output)
+            .encode_images(&frames)
+            .map_err(|e| encode_error("ICO", e))?;
+        Ok(output)
+    }
+}
+
+impl Codec<Rgba8> for IcoCodec {
+    fn decode(&self, data: &[u8]) -> Result<ImageBuffer<Rgba8>> {
+        decode_with_image_crate(data, ImageCrateFormat::Ico, "ICO")
+    }
+
+    fn encode(&self, buffer: &ImageBuffer<Rgba8>, _options: &ConversionOptions) -> Result<Vec<u8>> {
+        let rgba_image = buffer_to_rgba_image(buffer)?;
+        let image = match self.target_size(buffer.dimensions()) {
+            Some(size) => fit_to_square(&rgba_image, size),
+            None => rgba_image,
+        };
+        Self::write_ico(&[image])
+    }
+
+    fn encode_multisize(&self, buffer: &ImageBuffer<Rgba8>, sizes: &[u32]) -> Result<Vec<u8>> {
+        for (index, &size) in sizes.iter().enumerate() {
+            if size == 0 || size > ICO_MAX_SIZE {
+                return Err(ImageError::InvalidParameters {
+                    details: format!("ICO size {} out of range 1..={}", size, ICO_MAX_SIZE),
+                });
+            }
+            if sizes[..index].contains(&size) {
+                return Err(ImageError::InvalidParameters {
+                    details: format!("Duplicate ICO size {}", size),
+                });
+            }
+        }
+
+        let rgba_image = buffer_to_rgba_image(buffer)?;
+        let images: Vec<_> = sizes.iter().map(|&size| fit_to_square(&rgba_image, size)).collect();
+        Self::write_ico(&images)
+    }
+
+    fn info(&self) -> CodecInfo {
+        CodecInfo {
+            format: ImageFormat::Ico,
+            name: "ICO Codec".to_string(),
+            version: "1.0.0".to_string(),
+            supports_decode: true,
+            supports_encode: true,
+            performance_level: PerformanceLevel::Balanced,
+            quality_features: QualityFeatures {
+                supports_lossless: true,
+                supports_lossy: false,
+                supports_progressive: false,
+                supports_transparency: true,
+                supports_animation: false,
+                max_quality_level: 100,
+            },
+        }
+    }
+
+    fn validate_format(&self, data: &[u8]) -> bool {
+        data.starts_with(&[0x00, 0x00, 0x01, 0x00])
+    }
+}
+
+/// ICO条目的最大边长
+const ICO_MAX_SIZE: u32 = 256;
+
+/// 等比缩放到正方形画布内并居中，空白处保持透明
+fn fit_to_square(image: &image::RgbaImage, size: u32) -> image::RgbaImage {
+    use image::imageops::{self, FilterType};
+
+    let (width, height) = image.dimensions();
+    let scale = size as f64 / width.max(height) as f64;
+    let scaled_width = ((width as f64 * scale).round() as u32).clamp(1, size);
+    let scaled_height = ((height as f64 * scale).round() as u32).clamp(1, size);
+
+    let scaled = imageops::resize(image, scaled_width, scaled_height, FilterType::Lanczos3);
+    if scaled_width == size && scaled_height == size {
+        return scaled;
+    }
+
+    let mut canvas = image::RgbaImage::new(size, size);
+    let x = (size - scaled_width) / 2;
+    let y = (size - scaled_height) / 2;
+    imageops::overlay(&mut canvas, &scaled, x as i64, y as i64);
+    canvas
+}
+
+// 实现BMP编解码器
+impl BmpCodec {
+    fn new(_config: &CodecConfig) -> Result<Self> {
+        Ok(Self)
+    }
+}
+
+impl Codec<Rgba8> for BmpCodec {
+    fn decode(&self, data: &[u8]) -> Result<ImageBuffer<Rgba8>> {
+        decode_with_image_crate(data, ImageCrateFormat::Bmp, "BMP")
+    }
+
+    fn encode(&self, buffer: &ImageBuffer<Rgba8>, _options: &ConversionOptions) -> Result<Vec<u8>> {
+        // BMP不支持透明度：管线已与背景合成，这里写出24位无压缩RGB
+        let dims = buffer.dimensions();
+        let rgb_image = image::DynamicImage::ImageRgba8(buffer_to_rgba_image(buffer)?).to_rgb8();
+
+        let mut output = Vec::new();
+        image::codecs::bmp::BmpEncoder::new(&mut output)
+            .encode(rgb_image.as_raw(), dims.width, dims.height, image::ColorType::Rgb8)
+            .map_err(|e| encode_error("BMP", e))?;
+        Ok(output)
+    }
+
+    fn info(&self) -> CodecInfo {
+        CodecInfo {
+            format: ImageFormat::Bmp,
+            name: "BMP Codec".to_string(),
+            version: "1.0.0".to_string(),
+            supports_decode: true,
+            supports_encode: true,
+            performance_level: PerformanceLevel::Fast,
+            quality_features: QualityFeatures {
+                supports_lossless: true,
+                supports_lossy: false,
+                supports_progressive: false,
+                supports_transparency: false,
+                supports_animation: false,
+                max_quality_level: 100,
+            },
+        }
+    }
+
+    fn validate_format(&self, data: &[u8]) -> bool {
+        // 14字节文件头以"BM"开头，随后是DIB信息头
+        data.len() >= 26 && data.starts_with(b"BM")
+    }
+}
+
+// 实现TGA编解码器
+impl TgaCodec {
+    fn new(_config: &CodecConfig) -> Result<Self> {
+        Ok(Self::default())
+    }
+}
+
+impl Codec<Rgba8> for TgaCodec {
+    fn decode(&self, data: &[u8]) -> Result<ImageBuffer<Rgba8>> {
+        decode_with_image_crate(data, ImageCrateFormat::Tga, "TGA")
+    }
+
+    fn encode(&self, buffer: &ImageBuffer<Rgba8>, _options: &ConversionOptions) -> Result<Vec<u8>> {
+        let dims = buffer.dimensions();
+        let rgba_image = buffer_to_rgba_image(buffer)?;
+
+        let mut output = Vec::new();
+        let encoder = image::codecs::tga::TgaEncoder::new(&mut output);
+        let encoder = if self.rle_compression { encoder } else { encoder.disable_rle() };
+        encoder
+            .write_image(rgba_image.as_raw(), dims.width, dims.height, image::ColorType::Rgba8)
+            .map_err(|e| encode_error("TGA", e))?;
+
+        // 写入TGA 2.0尾部（无扩展区和开发者区），使输出可被签名检测识别
+        output.extend_from_slice(&[0; 8]);
+        output.extend_from_slice(TGA_FOOTER_SIGNATURE);
+        Ok(output)
+    }
+
+    fn info(&self) -> CodecInfo {
+        CodecInfo {
+            format: ImageFormat::Tga,
+            name: "TGA Codec".to_string(),
+            version: "1.0.0".to_string(),
+            supports_decode: true,
+            supports_encode: true,
+            performance_level: PerformanceLevel::Fast,
+            quality_features: QualityFeatures {
+                supports_lossless: true,
+                supports_lossy: false,
+                supports_progressive: false,
+                supports_transparency: true,
+                supports_animation: false,
+                max_quality_level: 100,
+            },
+        }
+    }
+
+    fn validate_format(&self, data: &[u8]) -> bool {
+        data.ends_with(TGA_FOOTER_SIGNATURE) || is_plausible_tga_header(data)
+    }
+}
+
+// 实现HEIC编解码器
+#[cfg(feature = "heif")]
+impl HeicCodec {
+    fn new(_config: &CodecConfig) -> Result<Self> {
+        Ok(Self)
+    }
+}
+
+#[cfg(feature = "heif")]
+impl Codec<Rgba8> for HeicCodec {
+    fn decode(&self, data: &[u8]) -> Result<ImageBuffer<Rgba8>> {
+        use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+        let context = HeifContext::read_from_bytes(data).map_err(heif_decode_error)?;
+        let handle = context.primary_image_handle().map_err(heif_decode_error)?;
+        let image = LibHeif::new()
+            .decode(&handle, ColorSpace::Rgb(RgbChroma::Rgba), None)
+            .map_err(heif_decode_error)?;
+
+        let plane = image.planes().interleaved.ok_or_else(|| ImageError::DecodeError {
+            format: "HEIC".to_string(),
+            message: "Decoded image has no interleaved RGBA plane".to_string(),
+            source: None,
+        })?;
+
+        // 行之间可能有填充，按stride逐行读取
+        let row_len = plane.width as usize * 4;
+        let pixels = plane
+            .data
+            .chunks(plane.stride)
+            .take(plane.height as usize)
+            .flat_map(|row| row[..row_len].chunks_exact(4))
+            .map(|p| Rgba8 { r: p[0], g: p[1], b: p[2], a: p[3] })
+            .collect();
+
+        ImageBuffer::from_raw(plane.width, plane.height, pixels, PixelFormat::Rgba8)
+    }
+
+    fn encode(&self, _buffer: &ImageBuffer<Rgba8>, _options: &ConversionOptions) -> Result<Vec<u8>> {
+        Err(ImageError::UnsupportedOperation {
+            operation: "HEIC encode is not supported".to_string(),
+        })
+    }
+
+    fn info(&self) -> CodecInfo {
+        CodecInfo {
+            format: ImageFormat::Heic,
+            name: "HEIC Codec".to_string(),
+            version: "1.0.0".to_string(),
+            supports_decode: true,
+            supports_encode: false,
+            performance_level: PerformanceLevel::HighQuality,
+            quality_features: QualityFeatures {
+                supports_lossless: false,
+                supports_lossy: true,
+                supports_progressive: false,
+                supports_transparency: true,
+                supports_animation: false,
+                max_quality_level: 100,
+            },
+        }
+    }
+
+    fn validate_format(&self, data: &[u8]) -> bool {
+        data.len() >= 12
+            && data[4..8] == *b"ftyp"
+            && FormatDetector::inspect_isobmff(data).is_some_and(|d| d.format == ImageFormat::Heic)
+    }
+}
+
+/// 包装libheif的解码错误
+#[cfg(feature = "heif")]
+fn heif_decode_error(e: libheif_rs::HeifError) -> ImageError {
+    ImageError::DecodeError {
+        format: "HEIC".to_string(),
+        message: e.to_string(),
+        source: Some(Box::new(e)),
+    }
+}
+
+// 实现JPEG XL编解码器
+#[cfg(feature = "jxl")]
+impl JxlCodec {
+    fn new(_config: &CodecConfig) -> Result<Self> {
+        Ok(Self)
+    }
+}
+
+#[cfg(feature = "jxl")]
+impl Codec<Rgba8> for JxlCodec {
+    fn decode(&self, data: &[u8]) -> Result<ImageBuffer<Rgba8>> {
+        let to_error = |e: Box<dyn std::error::Error + Send + Sync>| ImageError::DecodeError {
+            format: "JPEG XL".to_string(),
+            message: e.to_string(),
+            source: Some(e),
+        };
+
+        let image = jxl_oxide::JxlImage::builder().read(Cursor::new(data)).map_err(to_error)?;
+        let render = image.render_frame(0).map_err(to_error)?;
+        let mut stream = render.stream();
+        let (width, height) = (stream.width(), stream.height());
+        let channels = stream.channels() as usize;
+
+        let mut samples = vec![0u8; width as usize * height as usize * channels];
+        stream.write_to_buffer(&mut samples);
+
+        let pixels = samples
+            .chunks_exact(channels)
+            .map(|p| match *p {
+                [l] => Rgba8 { r: l, g: l, b: l, a: u8::MAX },
+                [l, a] => Rgba8 { r: l, g: l, b: l, a },
+                [r, g, b] => Rgba8 { r, g, b, a: u8::MAX },
+                [r, g, b, a, ..] => Rgba8 { r, g, b, a },
+                [] => unreachable!("chunks_exact never yields empty chunks"),
+            })
+            .collect();
+
+        ImageBuffer::from_raw(width, height, pixels, PixelFormat::Rgba8)
+    }
+
+    /// 无损编码 - 编码器只支持模块化无损模式，`quality`不生效
+    fn encode(&self, buffer: &ImageBuffer<Rgba8>, options: &ConversionOptions) -> Result<Vec<u8>> {
+        use zune_core::{bit_depth::BitDepth, colorspace::ColorSpace, options::EncoderOptions};
+
+        let dims = buffer.dimensions();
+        let raw: Vec<u8> = buffer.as_slice().iter().flat_map(|p| [p.r, p.g, p.b, p.a]).collect();
+        let mut encoder_options = EncoderOptions::new(
+            dims.width as usize,
+            dims.height as usize,
+            ColorSpace::RGBA,
+            BitDepth::Eight,
+        );
+        if options.is_deterministic() {
+            encoder_options = encoder_options.set_num_threads(1);
+        }
+
+        zune_jpegxl::JxlSimpleEncoder::new(&raw, encoder_options)
+            .encode()
+            .map_err(|e| ImageError::EncodeError {
+                format: "JPEG XL".to_string(),
+                message: format!("{:?}", e),
+                source: None,
+            })
+    }
+
+    fn info(&self) -> CodecInfo {
+        CodecInfo {
+            format: ImageFormat::Jxl,
+            name: "JPEG XL Codec".to_string(),
+            version: "1.0.0".to_string(),
+            supports_decode: true,
+            supports_encode: true,
+            performance_level: PerformanceLevel::HighQuality,
+            quality_features: QualityFeatures {
+                supports_lossless: true,
+                supports_lossy: false,
+                supports_progressive: false,
+                supports_transparency: true,
+                supports_animation: false,
+                max_quality_level: 100,
+            },
+        }
+    }
+
+    fn validate_format(&self, data: &[u8]) -> bool {
+        data.starts_with(JXL_CODESTREAM_SIGNATURE) || data.starts_with(JXL_CONTAINER_SIGNATURE)
+    }
+}
+
+/// JPEG XL裸码流签名
+const JXL_CODESTREAM_SIGNATURE: &[u8] = &[0xFF, 0x0A];
+/// JPEG XL容器签名 - 12字节的`JXL `签名盒
+const JXL_CONTAINER_SIGNATURE: &[u8] = &[0x00, 0x00, 0x00, 0x0C, b'J', b'X', b'L', b' ', 0x0D, 0x0A, 0x87, 0x0A];
+
+/// 格式依赖的可选特性 - 当前构建未启用时返回特性名
+fn missing_feature(format: ImageFormat) -> Option<&'static str> {
+    match format {
+        ImageFormat::Heic if !cfg!(feature = "heif") => Some("heif"),
+        ImageFormat::Jxl if !cfg!(feature = "jxl") => Some("jxl"),
+        _ => None,
+    }
+}
+
+/// 当前构建启用的格式 - 依赖可选特性的格式在特性关闭时排除
+fn enabled_formats() -> Vec<ImageFormat> {
+    ImageFormat::ALL
+        .iter()
+        .copied()
+        .filter(|format| missing_feature(*format).is_none())
+        .collect()
+}
+
+/// 格式所需特性未启用 - 错误中列出当前可用的格式
+#[cfg_attr(all(feature = "heif", feature = "jxl"), allow(dead_code))]
+fn format_not_enabled(format: ImageFormat) -> ImageError {
+    ImageError::UnsupportedFormat {
+        format: format!("{} (requires the `{}` feature)", format, missing_feature(format).unwrap_or_default()),
+        supported: enabled_formats().iter().map(ToString::to_string).collect(),
+    }
+}
+
+/// PNG文件签名
+const PNG_SIGNATURE: &[u8] = &[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// 文件头声明的尺寸 - PNG直接读取IHDR（image库读取尺寸时要求数据完整到首个IDAT），
+/// 其余格式交给`FormatDetector::read_dimensions`
+fn declared_dimensions(data: &[u8]) -> Option<ImageDimensions> {
+    if data.starts_with(PNG_SIGNATURE) && data.get(12..16) == Some(b"IHDR".as_slice()) {
+        let field = |offset: usize| data.get(offset..offset + 4)?.try_into().ok().map(u32::from_be_bytes);
+        return Some(ImageDimensions { width: field(16)?, height: field(20)? });
+    }
+    FormatDetector::read_dimensions(data)
+}
+
+/// 解码结果的宽高都必须大于零 - 损坏文件可能解出0×0或零高度的图像
+fn ensure_nonzero_dimensions<P: Pixel>(buffer: &ImageBuffer<P>) -> Result<()> {
+    let dims = buffer.dimensions();
+    if dims.width == 0 || dims.height == 0 {
+        return Err(ImageError::InvalidDimensions {
+            width: dims.width,
+            height: dims.height,
+            reason: "zero dimension".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// TGA 2.0 尾部签名 `TRUEVISION-XFILE.\0`
+const TGA_FOOTER_SIGNATURE: &[u8] = b"TRUEVISION-XFILE.\0";
+/// TGA文件头长度
+const TGA_HEADER_LEN: usize = 18;
+
+/// TGA文件头合理性检查 - 色表类型、图像类型和像素深度必须是规范取值
+fn is_plausible_tga_header(data: &[u8]) -> bool {
+    if data.len() < TGA_HEADER_LEN {
+        return false;
+    }
+
+    let color_map_type = data[1];
+    let image_type = data[2];
+    let width = u16::from_le_bytes([data[12], data[13]]);
+    let height = u16::from_le_bytes([data[14], data[15]]);
+    let pixel_depth = data[16];
+
+    matches!(color_map_type, 0 | 1)
+        && matches!(image_type, 1 | 2 | 3 | 9 | 10 | 11)
+        && matches!(pixel_depth, 8 | 15 | 16 | 24 | 32)
+        && width > 0
+        && height > 0
+}
+
+// 实现WebP编解码器
+impl WebPCodec {
+    fn new(_config: &CodecConfig) -> Result<Self> {
+        Ok(Self::default())
+    }
+
+    /// 选择编码模式 - 显式设置优先，否则混合模式下指定了质量即走有损
+    fn resolve_mode(&self, options: &ConversionOptions) -> WebPMode {
+        match (options.webp_lossless(), self.encoding_mode) {
+            (Some(true), _) => WebPMode::Lossless,
+            (Some(false), _) => WebPMode::Lossy,
+            (None, WebPMode::Mixed) if options.quality().is_some() => WebPMode::Lossy,
+            (None, WebPMode::Mixed) => WebPMode::Lossless,
+            (None, mode) => mode,
+        }
+    }
+
+    /// 有损编码单帧 - 基于libwebp，预处理设置映射到编码配置
+    ///
+    /// 自定义参数 `webp_method` (0-6) 覆盖编码力度，越大越慢、压缩越好
+    #[cfg(feature = "webp-lossy")]
+    fn encode_lossy(&self, buffer: &ImageBuffer<Rgba8>, options: &ConversionOptions) -> Result<Vec<u8>> {
+        let dims = buffer.dimensions();
+        let rgba_image = buffer_to_rgba_image(buffer)?;
+        let lossy_error = |message: String| ImageError::EncodeError {
+            format: "WebP".to_string(),
+            message,
+            source: None,
+        };
+
+        let mut config = webp::WebPConfig::new()
+            .map_err(|_| lossy_error("Failed to initialize libwebp config".to_string()))?;
+        config.lossless = 0;
+        config.quality = options.quality().unwrap_or(0.8) * 100.0;
+        // libwebp有损编码固定为4:2:0，请求4:4:4时以sharp YUV减轻边缘渗色
+        let sharp_yuv = self.preprocessing.sharp_yuv
+            || options.chroma_subsampling() == Some(Subsampling::S444);
+        config.use_sharp_yuv = sharp_yuv as i32;
+        config.autofilter = self.preprocessing.auto_filter as i32;
+        config.alpha_compression = self.preprocessing.alpha_compression as i32;
+        if let Some(method) = custom_u8_param(options, "webp_method", 6)? {
+            config.method = method as i32;
+        }
+        if options.is_deterministic() {
+            config.thread_level = 0;
+        }
+
+        let encoded = webp::Encoder::from_rgba(rgba_image.as_raw(), dims.width, dims.height)
+            .encode_advanced(&config)
+            .map_err(|e| lossy_error(format!("{:?}", e)))?;
+
+        Ok(encoded.to_vec())
+    }
+
+    /// 按模式编码单帧 - 未启用`webp-lossy`特性时，自动选择的有损模式回退为无损
+    fn encode_still(&self, buffer: &ImageBuffer<Rgba8>, options: &ConversionOptions) -> Result<Vec<u8>> {
+        match self.resolve_mode(options) {
+            #[cfg(feature = "webp-lossy")]
+            WebPMode::Lossy => self.encode_lossy(buffer, options),
+            #[cfg(not(feature = "webp-lossy"))]
+            WebPMode::Lossy if options.webp_lossless() == Some(false) => Err(ImageError::UnsupportedOperation {
+                operation: "Lossy WebP encode requires the `webp-lossy` feature".to_string(),
+            }),
+            _ => self.encode_lossless(buffer),
+        }
+    }
+
+    /// 无损编码单帧 - 输出完整的RIFF容器
+    fn encode_lossless(&self, buffer: &ImageBuffer<Rgba8>) -> Result<Vec<u8>> {
+        let dims = buffer.dimensions();
+        let rgba_image = buffer_to_rgba_image(buffer)?;
+
+        let mut output = Vec::new();
+        image::codecs::webp::WebPEncoder::new_lossless(&mut output)
+            .write_image(rgba_image.as_raw(), dims.width, dims.height, image::ColorType::Rgba8)
+            .map_err(|e| encode_error("WebP", e))?;
+
+        Ok(output)
+    }
+}
+
+impl Codec<Rgba8> for WebPCodec {
+    fn decode(&self, data: &[u8]) -> Result<ImageBuffer<Rgba8>> {
+        decode_with_image_crate(data, ImageCrateFormat::WebP, "WebP")
+    }
+
+    fn encode(&self, buffer: &ImageBuffer<Rgba8>, options: &ConversionOptions) -> Result<Vec<u8>> {
+        let encoded = self.encode_still(buffer, options)?;
+
+        let profile = match buffer.icc_profile() {
+            Some(profile) if options.preserves_color_space() => profile,
+            _ => return Ok(encoded),
+        };
+
+        // 嵌入ICC需要扩展格式：VP8X + ICCP + 图像数据
+        let dims = buffer.dimensions();
+        let has_alpha = buffer.as_slice().iter().any(|p| p.a != u8::MAX);
+        let mut chunks = Vec::new();
+        write_vp8x_chunk(
+            &mut chunks,
+            WEBP_FLAG_ICC | if has_alpha { WEBP_FLAG_ALPHA } else { 0 },
+            dims,
+        );
+        write_riff_chunk(&mut chunks, b"ICCP", profile);
+        chunks.extend_from_slice(skip_vp8x_chunk(&encoded[WEBP_HEADER_LEN..]));
+
+        Ok(wrap_webp_container(chunks))
+    }
+
+    fn decode_frames(&self, data: &[u8]) -> Result<Vec<AnimationFrame<Rgba8>>> {
+        self.decode_frames_cancellable(data, &AtomicBool::new(false))
+    }
+
+    fn encode_frames(&self, frames: &[AnimationFrame<Rgba8>], options: &ConversionOptions) -> Result<Vec<u8>> {
+        self.encode_frames_cancellable(frames, options, &AtomicBool::new(false))
+    }
+
+    /// 逐帧解码，每帧开始前检查取消信号
+    fn decode_frames_cancellable(&self, data: &[u8], cancel: &dyn Cancellation) -> Result<Vec<AnimationFrame<Rgba8>>> {
+        let decoder = image::codecs::webp::WebPDecoder::new(Cursor::new(data))
+            .map_err(|e| decode_error("WebP", e))?;
+
+        // 静态WebP不产生动画帧，按单帧处理
+        if !decoder.has_animation() {
+            return Ok(vec![(self.decode(data)?, Duration::ZERO)]);
+        }
+
+        collect_frames(decoder.into_frames(), "WebP", cancel)
+    }
+
+    /// 逐帧编码ANMF块，每帧开始前检查取消信号
+    fn encode_frames_cancellable(
+        &self,
+        frames: &[AnimationFrame<Rgba8>],
+        options: &ConversionOptions,
+        cancel: &dyn Cancellation,
+    ) -> Result<Vec<u8>> {
+        let canvas_width = frames.iter().map(|(b, _)| b.dimensions().width).max().unwrap_or(0);
+        let canvas_height = frames.iter().map(|(b, _)| b.dimensions().height).max().unwrap_or(0);
+        let has_alpha = frames
+            .iter()
+            .any(|(b, _)| b.as_slice().iter().any(|p| p.a != u8::MAX));
+
+        let profile = frames[0].0.icc_profile().filter(|_| options.preserves_color_space());
+
+        let mut chunks = Vec::new();
+
+        // VP8X: 扩展头，声明动画、透明度和色彩配置
+        let mut flags = WEBP_FLAG_ANIMATION;
+        if has_alpha {
+            flags |= WEBP_FLAG_ALPHA;
+        }
+        if profile.is_some() {
+            flags |= WEBP_FLAG_ICC;
+        }
+        write_vp8x_chunk(
+            &mut chunks,
+            flags,
+            ImageDimensions { width: canvas_width, height: canvas_height },
+        );
+        if let Some(profile) = profile {
+            write_riff_chunk(&mut chunks, b"ICCP", profile);
+        }
+
+        // ANIM: 背景色（BGRA）和循环次数，0表示无限循环
+        let mut anim = vec![0, 0, 0, 0];
+        anim.extend_from_slice(&options.loop_count().unwrap_or(0).to_le_bytes());
+        write_riff_chunk(&mut chunks, b"ANIM", &anim);
+
+        // ANMF: 每帧都是完整画布，覆盖而不混合
+        for (index, (buffer, delay)) in frames.iter().enumerate() {
+            if cancel.is_cancelled() {
+                return Err(ImageError::cancelled("WebP frame encode", index, frames.len()));
+            }
+            let dims = buffer.dimensions();
+            let encoded = self.encode_lossless(buffer)?;
+
+            let mut anmf = Vec::with_capacity(16 + encoded.len());
+            push_u24(&mut anmf, 0);
+            push_u24(&mut anmf, 0);
+            push_u24(&mut anmf, dims.width.saturating_sub(1));
+            push_u24(&mut anmf, dims.height.saturating_sub(1));
+            push_u24(&mut anmf, (delay.as_millis() as u32).min(0xFF_FFFF));
+            anmf.push(WEBP_FRAME_NO_BLEND);
+            anmf.extend_from_slice(&encoded[WEBP_HEADER_LEN..]);
+            write_riff_chunk(&mut chunks, b"ANMF", &anmf);
+        }
+
+        Ok(wrap_webp_container(chunks))
+    }
+
+    fn info(&self) -> CodecInfo {
+        CodecInfo {
+            format: ImageFormat::WebP,
+            name: "WebP Codec".to_string(),
+            version: "1.0.0".to_string(),
+            supports_decode: true,
+            supports_encode: true,
+            performance_level: PerformanceLevel::Balanced,
+            quality_features: QualityFeatures {
+                supports_lossless: true,
+                supports_lossy: false,
+                supports_progressive: false,
+                supports_transparency: true,
+                supports_animation: true,
+                max_quality_level: 100,
+            },
+        }
+    }
+
+    fn validate_format(&self, data: &[u8]) -> bool {
+        data.len() >= WEBP_HEADER_LEN && data[0..4] == *b"RIFF" && data[8..12] == *b"WEBP"
+    }
+
+    /// 质量0.8、最高编码力度的有损WebP - 需要`webp-lossy`特性，否则回退为无损
+    fn default_options(&self) -> ConversionOptions {
+        ConversionOptionsBuilder::new().quality(0.8).custom_param("webp_method", "6").build()
+    }
+}
+
+// 实现TIFF编解码器
+impl TiffCodec {
+    fn new(_config: &CodecConfig) -> Result<Self> {
+        Ok(Self::default())
+    }
+
+    /// 解析`tiff_compression`自定义参数 - 未指定时使用编解码器默认压缩
+    fn compression(&self, options: &ConversionOptions) -> Result<TiffCompression> {
+        let Some(value) = options.custom_param("tiff_compression") else {
+            return Ok(self.compression_type);
+        };
+
+        match value.trim().to_ascii_lowercase().as_str() {
+            "none" | "uncompressed" => Ok(TiffCompression::None),
+            "lzw" => Ok(TiffCompression::Lzw),
+            "deflate" | "zip" => Ok(TiffCompression::Deflate),
+            "packbits" => Ok(TiffCompression::PackBits),
+            "jpeg" => Ok(TiffCompression::Jpeg),
+            _ => Err(ImageError::InvalidParameters {
+                details: format!(
+                    "Unknown tiff_compression '{}', expected none, lzw, deflate, packbits or jpeg",
+                    value
+                ),
+            }),
+        }
+    }
+
+    /// 解析`tiff_byte_order`自定义参数 - `II`小端（默认）或`MM`大端，返回是否大端
+    fn big_endian(options: &ConversionOptions) -> Result<bool> {
+        let Some(value) = options.custom_param("tiff_byte_order") else {
+            return Ok(false);
+        };
+
+        match value.trim().to_ascii_uppercase().as_str() {
+            "II" => Ok(false),
+            "MM" => Ok(true),
+            _ => Err(ImageError::InvalidParameters {
+                details: format!("Unknown tiff_byte_order '{}', expected II or MM", value),
+            }),
+        }
+    }
+
+    /// 按选项中的压缩算法和字节序写出TIFF - 每个缓冲区一页
+    fn encode_tiff(&self, pages: &[ImageBuffer<Rgba8>], options: &ConversionOptions) -> Result<Vec<u8>> {
+        use tiff::encoder::compression::{Deflate, Lzw, Packbits, Uncompressed};
+
+        let big_endian = Self::big_endian(options)?;
+        let preserve_icc = options.preserves_color_space();
+        let mut output = match self.compression(options)? {
+            TiffCompression::None => Self::write_tiff(pages, Uncompressed, preserve_icc),
+            TiffCompression::Lzw => Self::write_tiff(pages, Lzw, preserve_icc),
+            TiffCompression::Deflate => Self::write_tiff(pages, Deflate::default(), preserve_icc),
+            TiffCompression::PackBits => Self::write_tiff(pages, Packbits, preserve_icc),
+            TiffCompression::Jpeg => Err(ImageError::UnsupportedOperation {
+                operation: "TIFF JPEG compression".to_string(),
+            }),
+        }?;
+
+        // tiff库只按本机字节序写出，需要时整体转换
+        convert_tiff_byte_order(&mut output, big_endian)?;
+        Ok(output)
+    }
+
+    /// 按指定压缩算法写出TIFF - 每页一个IFD，各自携带ICC配置文件
+    fn write_tiff<D: tiff::encoder::compression::Compression + Copy>(
+        pages: &[ImageBuffer<Rgba8>],
+        compression: D,
+        preserve_icc: bool,
+    ) -> Result<Vec<u8>> {
+        let mut output = Cursor::new(Vec::new());
+        {
+            let mut encoder = tiff::encoder::TiffEncoder::new(&mut output).map_err(tiff_encode_error)?;
+            for buffer in pages {
+                let dims = buffer.dimensions();
+                let rgba_image = buffer_to_rgba_image(buffer)?;
+                let mut image = encoder
+                    .new_image_with_compression::<tiff::encoder::colortype::RGBA8, D>(
+                        dims.width,
+                        dims.height,
+                        compression,
+                    )
+                    .map_err(tiff_encode_error)?;
+
+                if let Some(profile) = buffer.icc_profile().filter(|_| preserve_icc) {
+                    image
+                        .encoder()
+                        .write_tag(tiff::tags::Tag::Unknown(TIFF_TAG_ICC_PROFILE), TiffUndefined(profile))
+                        .map_err(tiff_encode_error)?;
+                }
+
+                image.write_data(rgba_image.as_raw()).map_err(tiff_encode_error)?;
+            }
+        }
+
+        Ok(output.into_inner())
+    }
+
+    /// 统计TIFF的页数 - 只遍历IFD链，不解码像素
+    fn page_count(data: &[u8]) -> Result<usize> {
+        let mut decoder = tiff::decoder::Decoder::new(Cursor::new(data)).map_err(tiff_decode_error)?;
+        let mut count = 1;
+        while decoder.more_images() {
+            decoder.next_image().map_err(tiff_decode_error)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// 解码指定页 - 支持8/16位的灰度、灰度+Alpha、RGB和RGBA，16位取高8位
+    fn decode_page(data: &[u8], index: usize) -> Result<ImageBuffer<Rgba8>> {
+        use tiff::decoder::DecodingResult;
+        use tiff::ColorType;
+
+        let mut decoder = tiff::decoder::Decoder::new(Cursor::new(data)).map_err(tiff_decode_error)?;
+        decoder.seek_to_image(index).map_err(tiff_decode_error)?;
+        let (width, height) = decoder.dimensions().map_err(tiff_decode_error)?;
+        let color_type = decoder.colortype().map_err(tiff_decode_error)?;
+        let unsupported = || ImageError::UnsupportedOperation {
+            operation: format!("TIFF page {} with color type {:?}", index, color_type),
+        };
+
+        let samples = match decoder.read_image().map_err(tiff_decode_error)? {
+            DecodingResult::U8(samples) => samples,
+            DecodingResult::U16(samples) => samples.iter().map(|&s| (s >> 8) as u8).collect(),
+            _ => return Err(unsupported()),
+        };
+        let channels = match color_type {
+            ColorType::Gray(8 | 16) => 1,
+            ColorType::GrayA(8 | 16) => 2,
+            ColorType::RGB(8 | 16) => 3,
+            ColorType::RGBA(8 | 16) => 4,
+            _ => return Err(unsupported()),
+        };
+        if samples.len() != width as usize * height as usize * channels {
+            return Err(unsupported());
+        }
+
+        let rgba: Vec<u8> = samples
+            .chunks_exact(channels)
+            .flat_map(|s| match *s {
+                [g] => [g, g, g, u8::MAX],
+                [g, a] => [g, g, g, a],
+                [r, g, b] => [r, g, b, u8::MAX],
+                [r, g, b, a] => [r, g, b, a],
+                _ => unreachable!("chunks_exact yields {} samples", channels),
+            })
+            .collect();
+        let image = image::RgbaImage::from_raw(width, height, rgba).ok_or_else(unsupported)?;
+        rgba_image_to_buffer(image)
+    }
+}
+
+impl Codec<Rgba8> for TiffCodec {
+    fn decode(&self, data: &[u8]) -> Result<ImageBuffer<Rgba8>> {
+        decode_with_image_crate(data, ImageCrateFormat::Tiff, "TIFF")
+    }
+
+    fn decode_with_options(&self, data: &[u8], options: &ConversionOptions) -> Result<ImageBuffer<Rgba8>> {
+        decode_with_bit_reduction(data, ImageCrateFormat::Tiff, "TIFF", options.is_bit_reduction_dither())
+    }
+
+    fn encode(&self, buffer: &ImageBuffer<Rgba8>, options: &ConversionOptions) -> Result<Vec<u8>> {
+        self.encode_tiff(std::slice::from_ref(buffer), options)
+    }
+
+    fn decode_pages(&self, data: &[u8]) -> Result<Vec<ImageBuffer<Rgba8>>> {
+        self.decode_pages_cancellable(data, &AtomicBool::new(false))
+    }
+
+    /// 解码全部页面 - 启用`parallel` feature时各页在独立的解码器中并行解码，
+    /// 每页开始前检查取消信号
+    fn decode_pages_cancellable(&self, data: &[u8], cancel: &dyn Cancellation) -> Result<Vec<ImageBuffer<Rgba8>>> {
+        let count = Self::page_count(data)?;
+        let decode = |index: usize| (!cancel.is_cancelled()).then(|| Self::decode_page(data, index));
+
+        #[cfg(feature = "parallel")]
+        let pages: Vec<Option<Result<ImageBuffer<Rgba8>>>> = {
+            use rayon::prelude::*;
+            (0..count).into_par_iter().map(decode).collect()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let pages: Vec<Option<Result<ImageBuffer<Rgba8>>>> = (0..count).map(decode).collect();
+
+        let completed = pages.iter().filter(|page| page.is_some()).count();
+        if completed < count {
+            return Err(ImageError::cancelled("TIFF page decode", completed, count));
+        }
+        pages.into_iter().flatten().collect()
+    }
+
+    fn encode_pages(&self, pages: &[ImageBuffer<Rgba8>], options: &ConversionOptions) -> Result<Vec<u8>> {
+        self.encode_tiff(pages, options)
+    }
+
+    fn info(&self) -> CodecInfo {
+        CodecInfo {
+            format: ImageFormat::Tiff,
+            name: "TIFF Codec".to_string(),
+            version: "1.0.0".to_string(),
+            supports_decode: true,
+            supports_encode: true,
+            performance_level: PerformanceLevel::Balanced,
+            quality_features: QualityFeatures {
+                supports_lossless: true,
+                supports_lossy: false,
+                supports_progressive: false,
+                supports_transparency: true,
+                supports_animation: false,
+                max_quality_level: 9,
+            },
+        }
+    }
+
+    fn validate_format(&self, data: &[u8]) -> bool {
+        data.starts_with(&[0x49, 0x49, 0x2A, 0x00]) || data.starts_with(&[0x4D, 0x4D, 0x00, 0x2A])
+    }
+}
+
+/// TIFF的ICC配置文件标签（InterColorProfile）
+const TIFF_TAG_ICC_PROFILE: u16 = 34675;
+
+/// 原地转换TIFF的字节序 - 遍历IFD链，交换文件头、目录项及其多字节取值
+///
+/// 只交换结构化字段；像素数据按8位样本写出（压缩数据也是字节流），无需处理
+fn convert_tiff_byte_order(data: &mut [u8], big_endian: bool) -> Result<()> {
+    let corrupt = || ImageError::EncodeError {
+        format: "TIFF".to_string(),
+        message: "Malformed TIFF structure while converting byte order".to_string(),
+        source: None,
+    };
+
+    let source_big_endian = data.starts_with(b"MM");
+    if source_big_endian == big_endian {
+        return Ok(());
+    }
+
+    let read = |data: &[u8], pos: usize, len: usize| -> Result<u32> {
+        let bytes = data.get(pos..pos + len).ok_or_else(corrupt)?;
+        let fold = |value: u32, &byte: &u8| value << 8 | byte as u32;
+        Ok(if source_big_endian { bytes.iter().fold(0, fold) } else { bytes.iter().rev().fold(0, fold) })
+    };
+    let swap = |data: &mut [u8], pos: usize, len: usize| -> Result<()> {
+        data.get_mut(pos..pos + len).ok_or_else(corrupt)?.reverse();
+        Ok(())
+    };
+
+    data[..2].copy_from_slice(if big_endian { b"MM" } else { b"II" });
+    swap(data, 2, 2)?;
+    let mut ifd = read(data, 4, 4)? as usize;
+    swap(data, 4, 4)?;
+
+    let mut visited = HashSet::new();
+    while ifd != 0 && visited.insert(ifd) {
+        let count = read(data, ifd, 2)? as usize;
+        swap(data, ifd, 2)?;
+        for entry in (0..count).map(|i| ifd + 2 + 12 * i) {
+            swap(data, entry, 2)?;
+            let field_type = read(data, entry + 2, 2)?;
+            swap(data, entry + 2, 2)?;
+            let values = read(data, entry + 4, 4)? as usize;
+            swap(data, entry + 4, 4)?;
+
+            // 每个取值按多少字节交换，以及每个取值含几个这样的单元
+            let (unit, units_per_value) = match field_type {
+                3 | 8 => (2, 1),
+                4 | 9 | 11 | 13 => (4, 1),
+                5 | 10 => (4, 2),
+                12 | 16 | 17 | 18 => (8, 1),
+                _ => (1, 1),
+            };
+            let units = values.checked_mul(units_per_value).ok_or_else(corrupt)?;
+            let position = if units.checked_mul(unit).ok_or_else(corrupt)? <= 4 {
+                entry + 8
+            } else {
+                let offset = read(data, entry + 8, 4)? as usize;
+                swap(data, entry + 8, 4)?;
+                offset
+            };
+            if unit > 1 {
+                for index in 0..units {
+                    swap(data, position + index * unit, unit)?;
+                }
+            }
+        }
+
+        let next = ifd + 2 + 12 * count;
+        let next_ifd = read(data, next, 4)? as usize;
+        swap(data, next, 4)?;
+        ifd = next_ifd;
+    }
+
+    Ok(())
+}
+
+/// TIFF的UNDEFINED类型字段 - ICC配置文件按规范以此类型存储
+struct TiffUndefined<'a>(&'a [u8]);
+
+impl tiff::encoder::TiffValue for TiffUndefined<'_> {
+    const BYTE_LEN: u8 = 1;
+    const FIELD_TYPE: tiff::tags::Type = tiff::tags::Type::UNDEFINED;
+
+    fn count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn data(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.0)
+    }
+}
+
+/// 包装tiff库的解码错误
+fn tiff_decode_error(e: tiff::TiffError) -> ImageError {
+    ImageError::DecodeError {
+        format: "TIFF".to_string(),
+        message: e.to_string(),
+        source: Some(Box::new(e)),
+    }
+}
+
+/// 包装tiff库的编码错误
+fn tiff_encode_error(e: tiff::TiffError) -> ImageError {
+    ImageError::EncodeError {
+        format: "TIFF".to_string(),
+        message: e.to_string(),
+        source: Some(Box::new(e)),
+    }
+}
+
+/// WebP文件头长度（RIFF + 大小 + WEBP）
+const WEBP_HEADER_LEN: usize = 12;
+/// VP8X标志位：包含动画
+const WEBP_FLAG_ANIMATION: u8 = 0x02;
+/// VP8X标志位：包含XMP
+const WEBP_FLAG_XMP: u8 = 0x04;
+/// VP8X标志位：包含EXIF
+const WEBP_FLAG_EXIF: u8 = 0x08;
+/// VP8X标志位：包含透明度
+const WEBP_FLAG_ALPHA: u8 = 0x10;
+/// VP8X标志位：包含ICC色彩配置
+const WEBP_FLAG_ICC: u8 = 0x20;
+/// ANMF标志位：不与前一帧混合
+const WEBP_FRAME_NO_BLEND: u8 = 0x02;
+
+/// 用RIFF头包装WebP数据块
+fn wrap_webp_container(chunks: Vec<u8>) -> Vec<u8> {
+    let mut output = Vec::with_capacity(WEBP_HEADER_LEN + chunks.len());
+    output.extend_from_slice(b"RIFF");
+    output.extend_from_slice(&(chunks.len() as u32 + 4).to_le_bytes());
+    output.extend_from_slice(b"WEBP");
+    output.extend_from_slice(&chunks);
+    output
+}
+
+/// 写入VP8X扩展头 - 标志位和画布尺寸
+fn write_vp8x_chunk(output: &mut Vec<u8>, flags: u8, canvas: ImageDimensions) {
+    let mut vp8x = Vec::with_capacity(10);
+    vp8x.push(flags);
+    vp8x.extend_from_slice(&[0, 0, 0]);
+    push_u24(&mut vp8x, canvas.width.saturating_sub(1));
+    push_u24(&mut vp8x, canvas.height.saturating_sub(1));
+    write_riff_chunk(output, b"VP8X", &vp8x);
+}
+
+/// 跳过已有的VP8X扩展头 - 有损带透明度的输出自带VP8X，重新封装时需替换
+fn skip_vp8x_chunk(chunks: &[u8]) -> &[u8] {
+    const VP8X_CHUNK_LEN: usize = 8 + 10;
+    match chunks.get(..4) {
+        Some(b"VP8X") if chunks.len() >= VP8X_CHUNK_LEN => &chunks[VP8X_CHUNK_LEN..],
+        _ => chunks,
+    }
+}
+
+/// 写入RIFF数据块 - 奇数长度需要补齐
+fn write_riff_chunk(output: &mut Vec<u8>, fourcc: &[u8; 4], payload: &[u8]) {
+    output.extend_from_slice(fourcc);
+    output.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    output.extend_from_slice(payload);
+    if payload.len() % 2 == 1 {
+        output.push(0);
+    }
+}
+
+/// 写入24位小端整数
+fn push_u24(output: &mut Vec<u8>, value: u32) {
+    output.extend_from_slice(&value.to_le_bytes()[..3]);
+}
+
+// =============================================================================
+// 元数据清除 - 保证EXIF/XMP/GPS等信息不随输出泄露
+// =============================================================================
+
+/// PNG中保留的辅助数据块 - 只描述像素与颜色，不携带文本或拍摄信息
+const PNG_KEPT_ANCILLARY_CHUNKS: [&[u8; 4]; 12] = [
+    b"tRNS", b"gAMA", b"cHRM", b"sRGB", b"iCCP", b"sBIT",
+    b"bKGD", b"pHYs", b"cICP", b"acTL", b"fcTL", b"fdAT",
+];
+
+/// 是否能在不重新编码的情况下清除该格式的元数据
+pub(crate) fn can_strip_metadata(format: ImageFormat) -> bool {
+    matches!(format, ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP)
+}
+
+/// 清除容器中的元数据 - 像素数据和ICC配置文件原样保留
+///
+/// 格式不支持或数据结构无法解析时返回`None`，调用方应改为重新编码
+pub(crate) fn strip_metadata(data: &[u8], format: ImageFormat) -> Option<Vec<u8>> {
+    match format {
+        ImageFormat::Jpeg => strip_jpeg_metadata(data),
+        ImageFormat::Png => strip_png_metadata(data),
+        ImageFormat::WebP => strip_webp_metadata(data),
+        _ => None,
+    }
+}
+
+/// 扫描开始前只保留JFIF、ICC、Adobe颜色标记和解码必需的标记段
+fn strip_jpeg_metadata(data: &[u8]) -> Option<Vec<u8>> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(&data[..2]);
+    let mut pos = 2;
+    loop {
+        if data.get(pos) != Some(&0xFF) {
+            return None;
+        }
+        let marker = *data.get(pos + 1)?;
+        if marker == 0xFF {
+            // 标记前的填充字节
+            pos += 1;
+            continue;
+        }
+        let length = u16::from_be_bytes([*data.get(pos + 2)?, *data.get(pos + 3)?]) as usize;
+        let end = pos + 2 + length;
+        let segment = data.get(pos + 4..end)?;
+        if marker == 0xDA {
+            // 扫描数据之后不再有可识别的元数据段
+            output.extend_from_slice(&data[pos..]);
+            return Some(output);
+        }
+        let keep = match marker {
+            0xE0 => segment.starts_with(b"JFIF\0"),
+            0xE2 => segment.starts_with(JPEG_ICC_MARKER),
+            0xEE => segment.starts_with(b"Adobe"),
+            // APP1（EXIF/XMP）、APP13（IPTC）等应用段与COM注释
+            0xE0..=0xEF | 0xFE => false,
+            _ => true,
+        };
+        if keep {
+            output.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+    }
+}
+
+/// 保留关键数据块和描述像素的辅助数据块，去掉文本、EXIF、时间戳等
+fn strip_png_metadata(data: &[u8]) -> Option<Vec<u8>> {
+    if !data.starts_with(PNG_SIGNATURE) {
+        return None;
+    }
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(PNG_SIGNATURE);
+    let mut pos = PNG_SIGNATURE.len();
+    while pos < data.len() {
+        let length = u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?) as usize;
+        let chunk_type = data.get(pos + 4..pos + 8)?;
+        // 长度 + 类型 + 数据 + CRC
+        let end = pos + 12 + length;
+        let chunk = data.get(pos..end)?;
+        let critical = chunk_type[0].is_ascii_uppercase();
+        if critical || PNG_KEPT_ANCILLARY_CHUNKS.iter().any(|kept| kept.as_slice() == chunk_type) {
+            output.extend_from_slice(chunk);
+        }
+        if chunk_type == b"IEND" {
+            return Some(output);
+        }
+        pos = end;
+    }
+    None
+}
+
+/// 去掉EXIF和XMP数据块，同时清除VP8X中对应的标志位
+fn strip_webp_metadata(data: &[u8]) -> Option<Vec<u8>> {
+    if data.len() < WEBP_HEADER_LEN || &data[..4] != b"RIFF" || &data[8..12] != b"WEBP" {
+        return None;
+    }
+    let mut chunks = Vec::with_capacity(data.len());
+    let mut pos = WEBP_HEADER_LEN;
+    while pos < data.len() {
+        let fourcc = data.get(pos..pos + 4)?;
+        let size = u32::from_le_bytes(data.get(pos + 4..pos + 8)?.try_into().ok()?) as usize;
+        // 奇数长度的数据块带一个填充字节，最后一块可能省略
+        let end = (pos + 8 + size + size % 2).min(data.len());
+        let chunk = data.get(pos..pos + 8 + size)?;
+        if fourcc != b"EXIF" && fourcc != b"XMP " {
+            let start = chunks.len();
+            chunks.extend_from_slice(chunk);
+            if size % 2 == 1 {
+                chunks.push(0);
+            }
+            if fourcc == b"VP8X" && size > 0 {
+                chunks[start + 8] &= !(WEBP_FLAG_EXIF | WEBP_FLAG_XMP);
+            }
+        }
+        pos = end;
+    }
+    Some(wrap_webp_container(chunks))
+}
+
+// =============================================================================
+// image库桥接 - 私有辅助函数
+// =============================================================================
+
+/// 使用image库解码为RGBA缓冲区 - 同时提取嵌入的ICC配置文件
+fn decode_with_image_crate(data: &[u8], format: ImageCrateFormat, name: &str) -> Result<ImageBuffer<Rgba8>> {
+    decode_with_bit_reduction(data, format, name, false)
+}
+
+/// 使用image库解码 - `dither`时16位来源以有序抖动降到8位，否则四舍五入
+fn decode_with_bit_reduction(
+    data: &[u8],
+    format: ImageCrateFormat,
+    name: &str,
+    dither: bool,
+) -> Result<ImageBuffer<Rgba8>> {
+    let cursor = Cursor::new(data);
+    let to_error = |e| decode_error(name, e);
+
+    match format {
+        ImageCrateFormat::Jpeg => decode_with_decoder(
+            image::codecs::jpeg::JpegDecoder::new(cursor).map_err(to_error)?,
+            name,
+            dither,
+        ),
+        ImageCrateFormat::Png => decode_with_decoder(
+            image::codecs::png::PngDecoder::new(cursor).map_err(to_error)?,
+            name,
+            dither,
+        ),
+        ImageCrateFormat::WebP => decode_with_decoder(
+            image::codecs::webp::WebPDecoder::new(cursor).map_err(to_error)?,
+            name,
+            dither,
+        ),
+        ImageCrateFormat::Tiff => decode_with_decoder(
+            image::codecs::tiff::TiffDecoder::new(cursor).map_err(to_error)?,
+            name,
+            dither,
+        ),
+        _ => {
+            let img = image::load_from_memory_with_format(data, format).map_err(to_error)?;
+            rgba_image_to_buffer(img.to_rgba8())
+        }
+    }
+}
+
+/// 通过具体解码器解码 - 保留ICC配置文件
+fn decode_with_decoder<'a, D: image::ImageDecoder<'a>>(
+    mut decoder: D,
+    name: &str,
+    dither: bool,
+) -> Result<ImageBuffer<Rgba8>> {
+    let icc_profile = decoder.icc_profile();
+    let img = image::DynamicImage::from_decoder(decoder).map_err(|e| decode_error(name, e))?;
+
+    let sixteen_bit = matches!(
+        img.color(),
+        image::ColorType::L16 | image::ColorType::La16 | image::ColorType::Rgb16 | image::ColorType::Rgba16
+    );
+    let mut buffer = if dither && sixteen_bit {
+        processing::dither_to_rgba8(&img.to_rgba16())?
+    } else {
+        rgba_image_to_buffer(img.to_rgba8())?
+    };
+    buffer.set_icc_profile(icc_profile);
+    Ok(buffer)
+}
+
+/// 收集image库的动画帧及其时长 - 每帧开始前检查取消信号
+///
+/// 流式解码无法预知总帧数，取消时`total`为已解码帧数加上被跳过的下一帧
+fn collect_frames(
+    frames: image::Frames<'_>,
+    name: &str,
+    cancel: &dyn Cancellation,
+) -> Result<Vec<AnimationFrame<Rgba8>>> {
+    let mut collected = Vec::new();
+    for frame in frames {
+        if cancel.is_cancelled() {
+            let completed = collected.len();
+            return Err(ImageError::cancelled(format!("{} frame decode", name), completed, completed + 1));
+        }
+        let frame = frame.map_err(|e| decode_error(name, e))?;
+        let delay = Duration::from(frame.delay());
+        collected.push((rgba_image_to_buffer(frame.into_buffer())?, delay));
+    }
+    Ok(collected)
+}
+
+/// image库的RGBA图像转换为内部缓冲区
+fn rgba_image_to_buffer(rgba_img: image::RgbaImage) -> Result<ImageBuffer<Rgba8>> {
+    let (width, height) = rgba_img.dimensions();
+
+    let pixels: Vec<Rgba8> = rgba_img
+        .pixels()
+        .map(|p| Rgba8 {
+            r: p.0[0],
+            g: p.0[1],
+            b: p.0[2],
+            a: p.0[3]
+        })
+        .collect();
+
+    ImageBuffer::from_raw(width, height, pixels, PixelFormat::Rgba8)
+}
+
+/// 内部缓冲区转换为image库的RGBA图像
+fn buffer_to_rgba_image(buffer: &ImageBuffer<Rgba8>) -> Result<image::RgbaImage> {
+    let dims = buffer.dimensions();
+    let rgba_data: Vec<u8> = buffer
+        .as_slice()
+        .iter()
+        .flat_map(|p| [p.r, p.g, p.b, p.a])
+        .collect();
+
+    image::RgbaImage::from_raw(dims.width, dims.height, rgba_data).ok_or_else(|| {
+        ImageError::InvalidParameters {
+            details: format!("Buffer does not fill {}×{} image", dims.width, dims.height),
+        }
+    })
+}
+
+/// 包装image库的解码错误
+fn decode_error(format: &str, e: image::ImageError) -> ImageError {
+    ImageError::DecodeError {
+        format: format.to_string(),
+        message: e.to_string(),
+        source: Some(Box::new(e)),
+    }
+}
+
+/// 包装image库的编码错误
+fn encode_error(format: &str, e: image::ImageError) -> ImageError {
+    ImageError::EncodeError {
+        format: format.to_string(),
+        message: e.to_string(),
+        source: Some(Box::new(e)),
+    }
+}
+
+// Default实现
+impl Default for JpegCodec {
+    fn default() -> Self {
+        Self {
+            quality_tables: None,
+            optimization_level: 6,
+            dct_impl: DctImplementation::Standard,
         }
     }
 }
@@ -1114,27 +3898,27 @@ impl Default for AvifCodec {
     }
 }
 
-impl Default for BmpCodec {
+impl Default for TiffCodec {
     fn default() -> Self {
         Self {
-            support_compression: false,
+            compression_type: TiffCompression::Deflate,
         }
     }
 }
 
-impl Default for TiffCodec {
+impl Default for GifCodec {
     fn default() -> Self {
         Self {
-            compression_type: TiffCompression::Deflate,
+            animation_support: true,
+            palette_optimization: true,
         }
     }
 }
 
-impl Default for GifCodec {
+impl Default for TgaCodec {
     fn default() -> Self {
         Self {
-            animation_support: true,
-            palette_optimization: true,
+            rle_compression: true,
         }
     }
 }
@@ -1147,13 +3931,3 @@ impl Default for IcoCodec {
     }
 }
 
-// 应用存根实现
-// JPEG and PNG codecs are already implemented above
-// impl_codec_stub!(JpegCodec, ImageFormat::Jpeg, "JPEG", true, false, false);
-// impl_codec_stub!(PngCodec, ImageFormat::Png, "PNG", false, true, false);
-impl_codec_stub!(WebPCodec, ImageFormat::WebP, "WebP", true, true, true);
-impl_codec_stub!(AvifCodec, ImageFormat::Avif, "AVIF", true, true, true);
-impl_codec_stub!(BmpCodec, ImageFormat::Bmp, "BMP", false, false, false);
-impl_codec_stub!(TiffCodec, ImageFormat::Tiff, "TIFF", false, true, false);
-impl_codec_stub!(GifCodec, ImageFormat::Gif, "GIF", false, true, true);
-impl_codec_stub!(IcoCodec, ImageFormat::Ico, "ICO", false, true, false);
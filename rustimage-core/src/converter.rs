@@ -7,18 +7,82 @@
 //! - **零成本抽象**：编译时优化和类型特化
 
 use crate::{
-    error::{ImageError, Result},
+    error::{ErrorCollector, ErrorStatistics, ImageError, Result, RetryPolicy},
     types::*,
-    codecs::{CodecEngine, CodecConfigBuilder},
+    codecs::{self, AnimationFrame, Codec, CodecEngine, CodecInfo, CodecConfigBuilder, FormatDetector, ImageBuffer, PixelFormat, DEFAULT_MAX_DECODED_PIXELS},
+    metadata,
     performance::PerformanceMonitor,
+    processing,
+    quality::QualityAssessor,
 };
 use rayon::prelude::*;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
 #[cfg(target_arch = "wasm32")]
 use web_time::Instant;
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::Instant;
 
+/// 估算输出大小时编码样本的最大边长
+const ESTIMATE_SAMPLE_EDGE: u32 = 256;
+/// 提取颜色摘要时缩小副本的最大边长
+const COLOR_SUMMARY_EDGE: u32 = 64;
+/// 颜色摘要中主色调的最大数量
+const DOMINANT_COLORS: usize = 5;
+/// 生成BlurHash时缩小副本的最大边长
+const BLURHASH_EDGE: u32 = 32;
+/// 超时后仍在后台运行的工作线程默认上限
+const DEFAULT_MAX_DETACHED_WORKERS: usize = 4;
+
+/// 超时工作线程的状态 - 运行中
+#[cfg(not(target_arch = "wasm32"))]
+const WORKER_RUNNING: u8 = 0;
+/// 超时工作线程的状态 - 已结束
+#[cfg(not(target_arch = "wasm32"))]
+const WORKER_FINISHED: u8 = 1;
+/// 超时工作线程的状态 - 调用方已超时返回，线程在后台继续运行
+#[cfg(not(target_arch = "wasm32"))]
+const WORKER_DETACHED: u8 = 2;
+
+/// 自某时刻起经过的毫秒数
+fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+/// 时间预算下的编码力度阶梯 - 从最快到最慢
+///
+/// 只调整调用方未显式设置的旋钮：AVIF `avif_speed`、有损WebP `webp_method`；
+/// PNG压缩级别以已配置的级别为上限逐级提高。没有力度旋钮的格式只有一级
+fn effort_ladder(to: ImageFormat, options: &ConversionOptions) -> Vec<ConversionOptions> {
+    match to {
+        ImageFormat::Avif if options.custom_param("avif_speed").is_none() => [10u8, 8, 6]
+            .into_iter()
+            .map(|speed| options.with_custom_param("avif_speed", speed.to_string()))
+            .collect(),
+        ImageFormat::WebP
+            if options.custom_param("webp_method").is_none()
+                && FormatConverter::is_encoding_lossy(to, options) =>
+        {
+            [0u8, 2, 4, 6]
+                .into_iter()
+                .map(|method| options.with_custom_param("webp_method", method.to_string()))
+                .collect()
+        }
+        ImageFormat::Png => {
+            let ceiling = options.compression_level().unwrap_or(6);
+            let mut levels: Vec<u8> = [1, 6, 9].into_iter().filter(|&level| level < ceiling).collect();
+            levels.push(ceiling);
+            levels.into_iter().map(|level| options.with_compression_level(level)).collect()
+        }
+        _ => vec![options.clone()],
+    }
+}
+
 // 外部依赖的简化实现
 fn num_cpus_get() -> usize {
     // 简化实现，实际项目中应该使用 num_cpus crate
@@ -37,10 +101,40 @@ fn num_cpus_get() -> usize {
 /// 内部管理复杂的编解码、性能监控、质量评估等逻辑
 pub struct FormatConverter {
     // 私有字段：完全隐藏实现细节
-    codec_engine: CodecEngine,                    // 编解码引擎
+    codec_engine: Arc<CodecEngine>,               // 编解码引擎（可在工作线程间共享）
     performance_monitor: PerformanceMonitor,      // 性能监控器
+    quality_assessor: QualityAssessor,            // 质量评估器
     config: ConverterConfig,                      // 转换器配置
     conversion_stats: Arc<Mutex<ConversionStats>>, // 转换统计
+    shared: SharedResources,                      // 与批处理工作转换器共享的资源
+    observers: Vec<Box<dyn ConversionObserver>>,  // 转换前后的观察者
+}
+
+/// 共享资源 - 私有：批处理的工作转换器复用所属转换器的线程池和错误收集器
+#[derive(Clone, Default)]
+struct SharedResources {
+    /// 专用线程池
+    thread_pool: Option<Arc<rayon::ThreadPool>>,
+    /// 错误收集器
+    error_collector: Option<Arc<ErrorCollector>>,
+    /// 超时后仍在后台运行的工作线程数
+    detached_workers: Arc<AtomicUsize>,
+}
+
+/// 超时工作线程的退出标记 - 线程正常结束或panic时都会执行，已脱离的线程在此释放名额
+#[cfg(not(target_arch = "wasm32"))]
+struct WorkerExit {
+    state: Arc<AtomicU8>,
+    detached_workers: Arc<AtomicUsize>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for WorkerExit {
+    fn drop(&mut self) {
+        if self.state.swap(WORKER_FINISHED, Ordering::AcqRel) == WORKER_DETACHED {
+            self.detached_workers.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
 }
 
 /// 转换器配置 - 使用构建器模式简化复杂配置
@@ -55,6 +149,8 @@ pub struct ConverterConfig {
     pub enable_simd: bool,
     /// 内存限制（字节）
     pub memory_limit: Option<u64>,
+    /// 解码的最大像素数 - 按文件头声明的尺寸在解码前检查，与内存限制无关
+    pub max_decoded_pixels: Option<u64>,
     
     // 监控和质量
     /// 是否启用性能监控
@@ -71,6 +167,12 @@ pub struct ConverterConfig {
     pub batch_size: usize,
     /// 是否自动优化格式选择
     pub auto_format_optimization: bool,
+    /// 解码前检测数据格式，与声明的源格式不符时立即报错
+    pub strict_input_validation: bool,
+    /// 是否收集失败转换的错误统计
+    pub enable_error_collection: bool,
+    /// 超时后仍在后台运行的工作线程上限 - 达到上限时拒绝新的带超时转换
+    pub max_detached_workers: usize,
 }
 
 /// 质量策略
@@ -115,7 +217,22 @@ struct ConversionStats {
     format_usage: std::collections::HashMap<(ImageFormat, ImageFormat), u64>,
 }
 
+/// 转换管线 - 私有：单次转换的解码、处理、编码步骤
+/// 
+/// 只持有共享状态，可以整体移动到工作线程执行
+struct ConversionPipeline {
+    /// 共享的编解码引擎
+    codec_engine: Arc<CodecEngine>,
+    /// 质量评估器
+    quality_assessor: QualityAssessor,
+    /// 是否计算质量指标
+    assess_quality: bool,
+    /// 是否允许SIMD路径
+    simd_enabled: bool,
+}
+
 /// 转换上下文 - 私有：单次转换的内部状态
+#[derive(Clone)]
 struct ConversionContext { 
     /// 开始时间
     start_time: Instant,
@@ -123,6 +240,10 @@ struct ConversionContext {
     from_format: ImageFormat,
     /// 输出格式  
     to_format: ImageFormat,
+    /// 自定义源格式 - 设置时经已注册的编解码器解码，`from_format`为其声明的内置格式
+    from_custom: Option<CustomEndpoint>,
+    /// 自定义目标格式 - 设置时经已注册的编解码器编码，`to_format`为其声明的内置格式
+    to_custom: Option<CustomEndpoint>,
     /// 输入大小
     input_size: u64,
     /// 转换选项
@@ -131,6 +252,50 @@ struct ConversionContext {
     enable_monitoring: bool,
 }
 
+/// 自定义格式端点 - 私有：已注册编解码器的键及其声明的能力
+#[derive(Clone)]
+struct CustomEndpoint {
+    /// 注册时使用的格式键
+    key: String,
+    /// 编解码器是否声明支持透明度
+    supports_transparency: bool,
+}
+
+impl CustomEndpoint {
+    fn new(key: &str, info: &CodecInfo) -> Self {
+        Self {
+            key: key.to_string(),
+            supports_transparency: info.quality_features.supports_transparency,
+        }
+    }
+}
+
+impl ConversionContext {
+    /// 源数据能否携带透明度 - 自定义格式按其编解码器的声明
+    fn source_supports_transparency(&self) -> bool {
+        self.from_custom
+            .as_ref()
+            .map_or(self.from_format.supports_transparency(), |custom| custom.supports_transparency)
+    }
+    
+    /// 输出能否保留透明度 - 自定义格式按其编解码器的声明
+    fn target_supports_transparency(&self) -> bool {
+        self.to_custom
+            .as_ref()
+            .map_or(self.to_format.supports_transparency(), |custom| custom.supports_transparency)
+    }
+    
+    /// 是否可以直通 - 同格式、选项允许且不需要像素变换；强制清除元数据时还要求能就地剔除
+    fn is_passthrough(&self) -> bool {
+        self.from_format == self.to_format
+            && self.from_custom.is_none()
+            && self.to_custom.is_none()
+            && self.options.allows_passthrough()
+            && !self.options.transforms_pixels()
+            && (!self.options.strips_all_metadata() || codecs::can_strip_metadata(self.from_format))
+    }
+}
+
 /// 批处理上下文 - 私有：批量转换的协调器
 struct BatchContext {
     /// 总任务数
@@ -150,6 +315,18 @@ struct BatchContext {
 impl FormatConverter {
     /// 创建新的格式转换器 - 主要构造函数
     pub fn new(config: ConverterConfig) -> Result<Self> {
+        let shared = SharedResources {
+            thread_pool: Self::build_thread_pool(&config)?,
+            error_collector: config
+                .enable_error_collection
+                .then(|| Arc::new(ErrorCollector::new(true))),
+            detached_workers: Arc::new(AtomicUsize::new(0)),
+        };
+        Self::with_shared(config, shared)
+    }
+    
+    /// 使用已有共享资源创建转换器 - 批处理工作转换器复用所属转换器的资源
+    fn with_shared(config: ConverterConfig, shared: SharedResources) -> Result<Self> {
         // 1. 创建编解码引擎配置
         let mut codec_config_builder = CodecConfigBuilder::new()
             .parallel(config.enable_parallel)
@@ -163,7 +340,9 @@ impl FormatConverter {
             codec_config_builder = codec_config_builder.memory_limit(limit);
         }
         
-        let codec_config = codec_config_builder.build();
+        let codec_config = codec_config_builder
+            .max_decoded_pixels(config.max_decoded_pixels)
+            .build();
         
         // 2. 创建编解码引擎
         let codec_engine = CodecEngine::new(codec_config)?;
@@ -177,13 +356,61 @@ impl FormatConverter {
         let conversion_stats = Arc::new(Mutex::new(ConversionStats::default()));
         
         Ok(Self {
-            codec_engine,
+            codec_engine: Arc::new(codec_engine),
             performance_monitor,
+            quality_assessor: QualityAssessor::new(),
             config,
             conversion_stats,
+            shared,
+            observers: Vec::new(),
         })
     }
     
+    /// 创建专用线程池 - 按`thread_pool_size`限定并发，未设置时使用rayon默认线程数
+    ///
+    /// 未启用并行时不创建；wasm32不支持创建线程，批处理沿用全局线程池
+    fn build_thread_pool(config: &ConverterConfig) -> Result<Option<Arc<rayon::ThreadPool>>> {
+        if !config.enable_parallel || cfg!(target_arch = "wasm32") {
+            return Ok(None);
+        }
+        
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(config.thread_pool_size.unwrap_or(0))
+            .thread_name(|index| format!("rustimage-worker-{}", index))
+            .build()
+            .map(|pool| Some(Arc::new(pool)))
+            .map_err(|e| ImageError::ConfigurationError {
+                setting: "thread_pool_size".to_string(),
+                value: format!("{:?}", config.thread_pool_size),
+                reason: e.to_string(),
+            })
+    }
+    
+    /// 批处理使用的并行线程数
+    pub fn current_num_threads(&self) -> usize {
+        self.shared
+            .thread_pool
+            .as_ref()
+            .map_or_else(rayon::current_num_threads, |pool| pool.current_num_threads())
+    }
+    
+    /// 超时后仍在后台运行的工作线程数 - 线程到达下一个取消检查点后退出
+    pub fn detached_workers(&self) -> usize {
+        self.shared.detached_workers.load(Ordering::Acquire)
+    }
+    
+    /// 在专用线程池中执行并行操作 - 没有专用线程池时使用全局线程池
+    fn install<R, OP>(thread_pool: Option<&rayon::ThreadPool>, op: OP) -> R
+    where
+        OP: FnOnce() -> R + Send,
+        R: Send,
+    {
+        match thread_pool {
+            Some(pool) => pool.install(op),
+            None => op(),
+        }
+    }
+    
     /// 使用默认配置创建转换器
     pub fn with_defaults() -> Result<Self> {
         Self::new(ConverterConfig::default())
@@ -215,6 +442,34 @@ impl FormatConverter {
         Self::new(config)
     }
     
+    /// 预热 - 提前启动线程池、初始化查找表并调用一次每个编解码器
+    ///
+    /// 对每个可编码的格式编码一张8×8图像（可解码时再解码回来），
+    /// 使首次真实转换不再承担初始化开销；预热不计入转换统计
+    pub fn warmup(&self) -> Result<()> {
+        // 启动全部工作线程 - 未配置专用线程池时初始化rayon全局线程池
+        if self.config.enable_parallel {
+            Self::install(self.shared.thread_pool.as_deref(), || rayon::broadcast(|_| ()));
+        }
+        processing::warm_tables();
+        
+        let pixels = (0..64u32)
+            .map(|i| Rgba8 { r: (i * 4) as u8, g: 128, b: (255 - i * 4) as u8, a: u8::MAX })
+            .collect();
+        let buffer = ImageBuffer::from_raw(8, 8, pixels, PixelFormat::Rgba8)?;
+        for format in self.get_supported_formats() {
+            let info = self.codec_engine.get_codec_info(format)?;
+            if !info.supports_encode {
+                continue;
+            }
+            let encoded = self.codec_engine.encode(&buffer, format, &self.get_default_options(format, format))?;
+            if info.supports_decode {
+                self.codec_engine.decode::<Rgba8>(&encoded, format)?;
+            }
+        }
+        Ok(())
+    }
+    
     /// 转换图像格式 - 深模块的主要接口
     pub fn convert_format(
         &mut self,
@@ -228,67 +483,1043 @@ impl FormatConverter {
             start_time: Instant::now(),
             from_format,
             to_format,
+            from_custom: None,
+            to_custom: None,
             input_size: image_data.len() as u64,
             options: options.unwrap_or_else(|| self.get_default_options(from_format, to_format)),
             enable_monitoring: self.config.enable_performance_monitoring,
         };
         
-        // 2. 验证转换请求
-        self.validate_conversion_request(&context)?;
+        self.observe_and_execute(image_data, &context)
+    }
+    
+    /// 带重试的格式转换 - 超时、内存、I/O等可重试错误按指数退避重试
+    ///
+    /// 最多尝试`max_attempts`次，两次尝试之间依次等待`backoff`、`2×backoff`……；
+    /// 不可重试的错误（如`InvalidFormat`）立即返回
+    pub fn convert_with_retry(
+        &mut self,
+        image_data: &[u8],
+        from_format: ImageFormat,
+        to_format: ImageFormat,
+        options: Option<ConversionOptions>,
+        max_attempts: u32,
+        backoff: Duration,
+    ) -> Result<ConvertedImage> {
+        RetryPolicy::new(max_attempts, backoff)
+            .run(|| self.convert_format(image_data, from_format, to_format, options.clone()))
+    }
+    
+    /// 自动检测源格式并转换 - 调用方无需声明`from_format`
+    ///
+    /// 签名无法识别时返回`FormatDetectionFailed`；已知源格式时应使用`convert_format`
+    pub fn convert_auto(
+        &mut self,
+        image_data: &[u8],
+        to_format: ImageFormat,
+        options: Option<ConversionOptions>,
+    ) -> Result<ConvertedImage> {
+        let detection = match FormatDetector::detect_detailed(image_data) {
+            Ok(detection) => detection,
+            Err(e) => {
+                let error = ImageError::FormatDetectionFailed { reason: e.to_string() };
+                self.record_error(&error);
+                return Err(error);
+            }
+        };
+        // 跳过图像之前的BOM或杂散字节
+        self.convert_format(&image_data[detection.offset()..], detection.format(), to_format, options)
+    }
+    
+    /// 批量转换图像格式
+    pub fn batch_convert(
+        &mut self,
+        images: Vec<ImageInput>,
+        conversion_tasks: Vec<ConversionTask>,
+    ) -> Result<Vec<ConvertedImage>> {
+        let results = self.run_tasks(images, conversion_tasks, Self::convert_task)?;
         
-        // 3. 执行转换
-        let result = self.execute_conversion(image_data, &context);
+        // 聚合结果
+        self.aggregate_batch_results(results)
+    }
+    
+    /// 逐项报告的批量转换 - 每项结果带回任务标识，单项失败不影响其他项
+    ///
+    /// 任务未设置`id`时使用输入的文件名；结果顺序与输入一致。
+    /// 只有图像与任务数量不一致时整体返回错误
+    pub fn batch_convert_items(
+        &mut self,
+        images: Vec<ImageInput>,
+        conversion_tasks: Vec<ConversionTask>,
+    ) -> Result<Vec<BatchItemResult>> {
+        let ids: Vec<Option<String>> = conversion_tasks
+            .iter()
+            .zip(&images)
+            .map(|(task, image)| task.id.clone().or_else(|| image.filename().map(str::to_string)))
+            .collect();
+        let results = self.run_tasks(images, conversion_tasks, Self::convert_task)?;
         
-        // 4. 更新统计
-        self.update_conversion_stats(&context, &result);
+        Ok(ids
+            .into_iter()
+            .zip(results)
+            .map(|(id, result)| BatchItemResult { id, result })
+            .collect())
+    }
+    
+    /// 自动检测源格式的批量转换 - 每项为（数据，目标格式，选项）
+    ///
+    /// 逐项独立检测与转换，单项失败不影响其余项，结果与输入一一对应
+    pub fn batch_convert_auto(
+        &mut self,
+        inputs: Vec<(Vec<u8>, ImageFormat, Option<ConversionOptions>)>,
+    ) -> Vec<Result<ConvertedImage>> {
+        self.run_batch(inputs, |converter, (data, to_format, options)| {
+            converter.convert_auto(&data, to_format, options)
+        })
+    }
+    
+    /// 转换目录中的所有图像 - 输出写入`output_dir`，文件名为原文件名加目标扩展名
+    ///
+    /// 按文件签名（必要时结合扩展名）识别图像，无法识别的文件直接跳过、不出现在结果中；
+    /// 结果按文件路径排序，每项为写出的路径或该文件的转换错误。同名输出追加`_2`、`_3`等后缀。
+    /// 只读取目录的第一层，不递归子目录
+    pub fn convert_directory(
+        &mut self,
+        dir: &Path,
+        to_format: ImageFormat,
+        output_dir: &Path,
+        options: Option<ConversionOptions>,
+    ) -> Result<Vec<Result<PathBuf>>> {
+        let io_error = |operation: String| move |source| ImageError::IoError { operation, source };
         
-        result
+        let mut paths = std::fs::read_dir(dir)
+            .and_then(|entries| entries.map(|entry| entry.map(|entry| entry.path())).collect::<std::io::Result<Vec<_>>>())
+            .map_err(io_error(format!("read directory {}", dir.display())))?;
+        paths.retain(|path| path.is_file());
+        paths.sort();
+        std::fs::create_dir_all(output_dir)
+            .map_err(io_error(format!("create directory {}", output_dir.display())))?;
+        
+        let mut used_names = HashSet::new();
+        let mut results = Vec::new();
+        for path in paths {
+            let data = match std::fs::read(&path) {
+                Ok(data) => data,
+                Err(source) => {
+                    results.push(Err(io_error(format!("read {}", path.display()))(source)));
+                    continue;
+                }
+            };
+            
+            // 非图像文件跳过
+            let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+            let Ok(detection) = FormatDetector::detect_detailed_with_hint(&data, extension) else {
+                continue;
+            };
+            
+            let output_path = Self::unique_output_path(&path, to_format, output_dir, &mut used_names);
+            let result = self
+                .convert_format(&data, detection.format(), to_format, options.clone())
+                .and_then(|converted| {
+                    std::fs::write(&output_path, converted.data())
+                        .map_err(io_error(format!("write {}", output_path.display())))?;
+                    Ok(output_path)
+                });
+            results.push(result);
+        }
+        
+        Ok(results)
     }
     
-    /// 批量转换图像格式
-    pub fn batch_convert(
+    /// 带进度回调的批量转换 - 每个任务完成后调用`on_progress(completed, total)`
+    ///
+    /// 转换在工作线程上执行，完成事件经由通道汇总，回调始终在调用线程上串行执行
+    pub fn batch_convert_with_progress<F>(
         &mut self,
         images: Vec<ImageInput>,
         conversion_tasks: Vec<ConversionTask>,
+        mut on_progress: F,
+    ) -> Result<Vec<ConvertedImage>>
+    where
+        F: FnMut(usize, usize),
+    {
+        let total = images.len();
+        let (sender, receiver) = mpsc::channel::<()>();
+        let converter = &mut *self;
+        let results = thread::scope(|scope| {
+            let worker = scope.spawn(move || {
+                converter.run_tasks(images, conversion_tasks, |converter, image, task| {
+                    let result = converter.convert_task(image, task);
+                    let _ = sender.send(());
+                    result
+                })
+            });
+            
+            // 发送端随工作线程结束而释放，循环随之退出
+            for completed in receiver.iter().enumerate().map(|(index, _)| index + 1) {
+                on_progress(completed, total);
+            }
+            
+            worker.join().map_err(|_| ImageError::ProcessingFailed {
+                stage: "batch".to_string(),
+                reason: "worker thread panicked".to_string(),
+            })
+        })??;
+        
+        self.aggregate_batch_results(results)
+    }
+    
+    /// 可取消的批量转换 - 每个任务开始前检查取消信号
+    ///
+    /// 已经开始的单次转换会执行完毕，之后尚未开始的任务被跳过，
+    /// 返回带有已完成数量的`OperationCancelled`错误
+    pub fn batch_convert_cancellable<C: Cancellation + ?Sized>(
+        &mut self,
+        images: Vec<ImageInput>,
+        conversion_tasks: Vec<ConversionTask>,
+        cancel: &C,
     ) -> Result<Vec<ConvertedImage>> {
-        if images.len() != conversion_tasks.len() {
+        let total = images.len();
+        let results = self.run_tasks(images, conversion_tasks, |converter, image, task| {
+            if cancel.is_cancelled() {
+                return Ok(None);
+            }
+            converter.convert_task(image, task).map(Some)
+        })?;
+        
+        // 被跳过的任务为`Ok(None)`
+        let completed = results.iter().filter(|result| !matches!(result, Ok(None))).count();
+        if completed < total {
+            return Err(ImageError::OperationCancelled {
+                operation: "batch conversion".to_string(),
+                reason: "cancellation requested".to_string(),
+                completed,
+                total,
+            });
+        }
+        
+        self.aggregate_batch_results(results.into_iter().filter_map(Result::transpose).collect())
+    }
+    
+    /// 完整解码校验 - 用于上传前确认数据可以被完整解码，而不只是文件头正确
+    ///
+    /// 未指定`expected_format`时按文件签名检测格式；指定时检测结果必须一致，
+    /// 无法识别签名的数据（如不带页脚的TGA）按指定格式解码。
+    /// 成功时返回图像尺寸，截断或损坏的数据返回解码器的`DecodeError`
+    pub fn validate_decodable(
+        &self,
+        image_data: &[u8],
+        expected_format: Option<ImageFormat>,
+    ) -> Result<ImageDimensions> {
+        let (format, offset) = match (FormatDetector::detect_detailed(image_data), expected_format) {
+            (Ok(detected), Some(expected)) if detected.format() != expected => {
+                return Err(ImageError::InvalidFormat {
+                    format: format!("expected {:?} but data is {:?}", expected, detected.format()),
+                });
+            }
+            (Ok(detected), _) => (detected.format(), detected.offset()),
+            (Err(_), Some(expected)) => (expected, 0),
+            (Err(error), None) => return Err(error),
+        };
+        
+        // 跳过图像之前的BOM或杂散字节
+        Ok(self.decode_to_buffer(&image_data[offset..], format)?.dimensions())
+    }
+    
+    /// 解码为可复用的图像句柄 - 一次解码，多次编码
+    pub fn decode_to_buffer(&self, image_data: &[u8], from_format: ImageFormat) -> Result<DecodedImage> {
+        if image_data.is_empty() {
+            return Err(ImageError::InvalidParameters {
+                details: "Empty input data".to_string(),
+            });
+        }
+        
+        let buffer = self.codec_engine.decode::<Rgba8>(image_data, from_format)?;
+        Ok(DecodedImage {
+            buffer,
+            source_format: Some(from_format),
+            source_size: image_data.len() as u64,
+        })
+    }
+    
+    /// 将已解码的图像编码为目标格式
+    ///
+    /// 选项中的像素变换作用于句柄的副本，句柄本身保持不变；
+    /// 解码已完成，因此不应用`timeout`
+    pub fn encode_buffer(
+        &mut self,
+        image: &DecodedImage,
+        to_format: ImageFormat,
+        options: Option<ConversionOptions>,
+    ) -> Result<ConvertedImage> {
+        let context = ConversionContext {
+            start_time: Instant::now(),
+            from_format: image.context_format(),
+            to_format,
+            from_custom: None,
+            to_custom: None,
+            input_size: image.source_size,
+            options: options.unwrap_or_else(|| self.get_default_options(image.context_format(), to_format)),
+            enable_monitoring: self.config.enable_performance_monitoring,
+        };
+        
+        self.validate_conversion_request(&context)?;
+        
+        if context.enable_monitoring {
+            self.performance_monitor.start_conversion(&context.from_format, &context.to_format);
+        }
+        
+        let mut metrics = PerformanceMetrics::default();
+        let result = self.pipeline().process_and_encode(
+            image.buffer.clone(),
+            &context,
+            &AtomicBool::new(false),
+            &mut metrics,
+        );
+        
+        if context.enable_monitoring {
+            self.performance_monitor.record_stage_timing(&metrics.timing);
+            self.performance_monitor.record_simd_usage(metrics.system.simd_utilized);
+            self.performance_monitor.end_conversion(context.start_time.elapsed(), result.is_ok());
+        }
+        self.update_conversion_stats(&context, result.as_ref());
+
+        result
+    }
+
+    /// 从无容器的原始RGBA字节构造图像句柄（如GPU缓冲区的回读）
+    ///
+    /// 按`R, G, B, A`逐字节解释，与平台字节序无关；`data`必须恰好为`width × height × 4`字节。
+    /// 原始数据没有容器格式，句柄的`source_format()`为`None`
+    pub fn from_raw_rgba(&self, data: &[u8], width: u32, height: u32) -> Result<DecodedImage> {
+        DecodedImage::from_rgba_bytes(width, height, data, None, data.len() as u64)
+    }
+
+    /// 将图像句柄导出为原始RGBA字节 - 行优先，每像素按`R, G, B, A`逐字节排列
+    pub fn encode_raw_rgba(&self, decoded: &DecodedImage) -> Vec<u8> {
+        decoded
+            .buffer()
+            .as_slice()
+            .iter()
+            .flat_map(|pixel| [pixel.r, pixel.g, pixel.b, pixel.a])
+            .collect()
+    }
+
+    /// 转换并保留像素 - 同时返回编码结果和编码前的图像句柄，免去再次解码
+    ///
+    /// 句柄中是经过选项中像素变换（缩放、灰度等）之后的像素，尺寸与编码结果一致；
+    /// 需要像素，因此不走同格式直通，也不应用`timeout`
+    pub fn convert_and_keep_buffer(
+        &mut self,
+        image_data: &[u8],
+        from_format: ImageFormat,
+        to_format: ImageFormat,
+        options: Option<ConversionOptions>,
+    ) -> Result<(ConvertedImage, DecodedImage)> {
+        let context = ConversionContext {
+            start_time: Instant::now(),
+            from_format,
+            to_format,
+            from_custom: None,
+            to_custom: None,
+            input_size: image_data.len() as u64,
+            options: options.unwrap_or_else(|| self.get_default_options(from_format, to_format)),
+            enable_monitoring: self.config.enable_performance_monitoring,
+        };
+        
+        self.validate_conversion_request(&context)?;
+        if self.config.strict_input_validation {
+            Self::validate_declared_format(image_data, from_format)?;
+        }
+        self.codec_engine.validate_format_data(image_data, from_format)?;
+        
+        if context.enable_monitoring {
+            self.performance_monitor.start_conversion(&context.from_format, &context.to_format);
+        }
+        
+        let mut metrics = PerformanceMetrics::default();
+        let pipeline = self.pipeline();
+        let result = pipeline.decode(image_data, &context, &mut metrics).and_then(|buffer| {
+            pipeline.process_and_encode_keeping_buffer(buffer, &context, &AtomicBool::new(false), &mut metrics)
+        });
+        
+        if context.enable_monitoring {
+            self.performance_monitor.record_stage_timing(&metrics.timing);
+            self.performance_monitor.record_simd_usage(metrics.system.simd_utilized);
+            self.performance_monitor.end_conversion(context.start_time.elapsed(), result.is_ok());
+        }
+        self.update_conversion_stats(&context, result.as_ref().map(|(converted, _)| converted));
+        if let Err(error) = &result {
+            self.record_error(error);
+        }
+        
+        let (converted, buffer) = result?;
+        let decoded = DecodedImage {
+            buffer,
+            source_format: Some(from_format),
+            source_size: context.input_size,
+        };
+        Ok((converted, decoded))
+    }
+    
+    /// 格式对比基准 - 同一源图像编码为各目标格式，按输出大小升序返回
+    ///
+    /// 源数据只解码一次，各目标共享同一解码结果以保证公平；
+    /// 任一目标编码失败时返回该错误，可先用`conversion_matrix`筛选可编码的格式
+    pub fn benchmark_formats(
+        &mut self,
+        image_data: &[u8],
+        from_format: ImageFormat,
+        targets: &[ImageFormat],
+        options: Option<ConversionOptions>,
+    ) -> Result<Vec<FormatBenchmark>> {
+        let decoded = self.decode_to_buffer(image_data, from_format)?;
+        
+        let mut benchmarks = targets
+            .iter()
+            .map(|&format| {
+                let start = Instant::now();
+                let converted = self.encode_buffer(&decoded, format, options.clone())?;
+                let encode_time_ms = elapsed_ms(start);
+                
+                let quality = self
+                    .codec_engine
+                    .decode::<Rgba8>(converted.data(), format)
+                    .and_then(|reconstructed| self.quality_assessor.assess_quality(decoded.buffer(), &reconstructed))
+                    .ok();
+                
+                Ok(FormatBenchmark {
+                    format,
+                    encode_time_ms,
+                    output_size: converted.data().len() as u64,
+                    quality,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        
+        benchmarks.sort_by_key(|benchmark| benchmark.output_size);
+        Ok(benchmarks)
+    }
+    
+    /// 释放转换器保留的缓存内存
+    ///
+    /// 编解码缓冲区按次分配、转换结束即释放，长期保留的只有性能监控的历史快照；
+    /// 调用后下一次转换需重新分配，以此换取更低的常驻内存。
+    /// 注意WASM线性内存只增不减，释放的空间由分配器复用而不会归还给浏览器
+    pub fn clear_caches(&mut self) {
+        self.performance_monitor.release_history();
+    }
+    
+    /// 当前缓存占用的字节数（估算）
+    pub fn cached_bytes(&self) -> usize {
+        self.performance_monitor.retained_bytes()
+    }
+    
+    /// 生成缩略图 - 等比缩放到不超过`max_width`×`max_height`
+    ///
+    /// 缩放在选项中的像素变换之前完成。默认不放大，源图像已在范围内时原尺寸编码；
+    /// 选项允许放大时等比放大到恰好放进范围
+    pub fn make_thumbnail(
+        &mut self,
+        image_data: &[u8],
+        from_format: ImageFormat,
+        to_format: ImageFormat,
+        max_width: u32,
+        max_height: u32,
+        options: Option<ConversionOptions>,
+    ) -> Result<ConvertedImage> {
+        if max_width == 0 || max_height == 0 {
+            return Err(ImageError::InvalidParameters {
+                details: format!("Thumbnail bounds must be non-zero, got {}×{}", max_width, max_height),
+            });
+        }
+        
+        let mut image = self.decode_to_buffer(image_data, from_format)?;
+        let bounds = FormatLimits {
+            max_width: Some(max_width),
+            max_height: Some(max_height),
+            max_file_size: None,
+            supported_bit_depths: &[],
+        };
+        let target = if options.as_ref().is_some_and(ConversionOptions::allows_upscale) {
+            let max = ImageDimensions { width: max_width, height: max_height };
+            processing::scale_to_fit(image.dimensions(), max)
+        } else {
+            processing::fit_within(image.dimensions(), &bounds)
+        };
+        if let Some(target) = target {
+            let filter = options.as_ref().map_or(ResizeFilter::Lanczos3, |options| options.resize_filter(image.dimensions(), target));
+            let linear = options.as_ref().is_some_and(ConversionOptions::is_linear_resize);
+            image.buffer = processing::resize(&image.buffer, target, filter, linear)?;
+        }
+        
+        self.encode_buffer(&image, to_format, options)
+    }
+    
+    /// 从同一解码结果编码多个变体 - 每个变体可指定目标格式、尺寸上限与选项
+    ///
+    /// 启用并行时各变体并行编码；结果顺序与`variants`一致，单个变体失败不影响其他变体
+    pub fn encode_variants(&mut self, decoded: &DecodedImage, variants: &[Variant]) -> Vec<Result<ConvertedImage>> {
+        self.run_batch(variants.iter().collect(), |converter, variant| {
+            converter.encode_variant(decoded, variant)
+        })
+    }
+    
+    /// 编码单个变体 - 超出尺寸上限时先在副本上等比缩小
+    fn encode_variant(&mut self, decoded: &DecodedImage, variant: &Variant) -> Result<ConvertedImage> {
+        let Some((max_width, max_height)) = variant.max_size else {
+            return self.encode_buffer(decoded, variant.format, variant.options.clone());
+        };
+        if max_width == 0 || max_height == 0 {
+            return Err(ImageError::InvalidParameters {
+                details: format!("Variant bounds must be non-zero, got {}×{}", max_width, max_height),
+            });
+        }
+        
+        let bounds = FormatLimits {
+            max_width: Some(max_width),
+            max_height: Some(max_height),
+            max_file_size: None,
+            supported_bit_depths: &[],
+        };
+        let Some(target) = processing::fit_within(decoded.dimensions(), &bounds) else {
+            return self.encode_buffer(decoded, variant.format, variant.options.clone());
+        };
+        
+        let options = variant.options.as_ref();
+        let filter = options.map_or(ResizeFilter::Lanczos3, ConversionOptions::downscale_filter);
+        let linear = options.is_some_and(ConversionOptions::is_linear_resize);
+        let resized = DecodedImage {
+            buffer: processing::resize(&decoded.buffer, target, filter, linear)?,
+            source_format: decoded.source_format,
+            source_size: decoded.source_size,
+        };
+        self.encode_buffer(&resized, variant.format, variant.options.clone())
+    }
+    
+    /// 读取EXIF元数据 - 方向、拍摄时间、GPS坐标和相机信息
+    ///
+    /// 支持JPEG、PNG、WebP和TIFF；没有EXIF或格式不携带EXIF时返回空结构，
+    /// 只有数据与声明的格式不符时才返回错误。不解码像素
+    pub fn read_metadata(&self, image_data: &[u8], from_format: ImageFormat) -> Result<ImageMetadata> {
+        Self::validate_declared_format(image_data, from_format)?;
+        Ok(metadata::read_metadata(image_data, from_format))
+    }
+    
+    /// 提取颜色摘要 - 平均色与最多5种主色调
+    ///
+    /// 统计在等比缩小到64×64以内的副本上进行，不计入转换统计
+    pub fn color_summary(&self, image_data: &[u8], from_format: ImageFormat) -> Result<ColorSummary> {
+        let mut image = self.decode_to_buffer(image_data, from_format)?;
+        let bounds = FormatLimits {
+            max_width: Some(COLOR_SUMMARY_EDGE),
+            max_height: Some(COLOR_SUMMARY_EDGE),
+            max_file_size: None,
+            supported_bit_depths: &[],
+        };
+        if let Some(target) = processing::fit_within(image.dimensions(), &bounds) {
+            image.buffer = processing::resize(&image.buffer, target, ResizeFilter::Triangle, false)?;
+        }
+        
+        Ok(processing::color_summary(&image.buffer, DOMINANT_COLORS))
+    }
+    
+    /// 生成BlurHash占位字符串 - 客户端可据此展开为低分辨率的模糊预览
+    ///
+    /// `components_x`和`components_y`为横纵方向的DCT分量数，取值1-9；
+    /// 编码在等比缩小到32×32以内的副本上进行，不计入转换统计
+    pub fn blurhash(
+        &self,
+        image_data: &[u8],
+        from_format: ImageFormat,
+        components_x: u32,
+        components_y: u32,
+    ) -> Result<String> {
+        if !(1..=9).contains(&components_x) || !(1..=9).contains(&components_y) {
             return Err(ImageError::InvalidParameters {
                 details: format!(
-                    "Images count ({}) does not match tasks count ({})",
-                    images.len(),
-                    conversion_tasks.len()
+                    "BlurHash components must be in 1-9, got {}×{}",
+                    components_x, components_y
                 ),
             });
         }
         
-        if images.is_empty() {
-            return Ok(Vec::new());
+        let mut image = self.decode_to_buffer(image_data, from_format)?;
+        let bounds = FormatLimits {
+            max_width: Some(BLURHASH_EDGE),
+            max_height: Some(BLURHASH_EDGE),
+            max_file_size: None,
+            supported_bit_depths: &[],
+        };
+        if let Some(target) = processing::fit_within(image.dimensions(), &bounds) {
+            image.buffer = processing::resize(&image.buffer, target, ResizeFilter::Triangle, false)?;
         }
         
-        // 执行批量转换
-        let results = if self.config.enable_parallel {
-            self.execute_parallel_batch(images, conversion_tasks)?
-        } else {
-            self.execute_sequential_batch(images, conversion_tasks)?
+        Ok(processing::blurhash(&image.buffer, components_x, components_y))
+    }
+    
+    /// 估算输出大小 - 不产生转换结果，也不计入转换统计
+    ///
+    /// 注意：该方法会完整解码输入并执行选项中的像素变换。处理后的图像不超过
+    /// 256×256时直接编码得到精确大小；否则编码等比缩小的样本，按像素数外推
+    pub fn estimate_output_size(
+        &self,
+        image_data: &[u8],
+        from_format: ImageFormat,
+        to_format: ImageFormat,
+        options: Option<ConversionOptions>,
+    ) -> Result<SizeEstimate> {
+        let context = ConversionContext {
+            start_time: Instant::now(),
+            from_format,
+            to_format,
+            from_custom: None,
+            to_custom: None,
+            input_size: image_data.len() as u64,
+            options: options.unwrap_or_else(|| self.get_default_options(from_format, to_format)),
+            enable_monitoring: false,
         };
+        self.validate_conversion_request(&context)?;
         
-        // 聚合结果
-        self.aggregate_batch_results(results)
+        // 直通时输出即输入
+        if context.is_passthrough() && self.codec_engine.check_header(image_data)?.is_some() {
+            let size = image_data.len() as u64;
+            return Ok(SizeEstimate { min: size, likely: size, max: size });
+        }
+        
+        let image = self.decode_to_buffer(image_data, from_format)?;
+        let processed = self.pipeline().process_buffer(
+            image.buffer,
+            &context,
+            &AtomicBool::new(false),
+            &mut PerformanceMetrics::default(),
+            &mut Vec::new(),
+        )?;
+        
+        let dims = processed.dimensions();
+        let sample_bounds = FormatLimits {
+            max_width: Some(ESTIMATE_SAMPLE_EDGE),
+            max_height: Some(ESTIMATE_SAMPLE_EDGE),
+            max_file_size: None,
+            supported_bit_depths: &[],
+        };
+        let Some(sample_dims) = processing::fit_within(dims, &sample_bounds) else {
+            let size = self.codec_engine.encode(&processed, to_format, &context.options)?.len() as u64;
+            return Ok(SizeEstimate { min: size, likely: size, max: size });
+        };
+        
+        let sample = processing::resize(&processed, sample_dims, ResizeFilter::Triangle, false)?;
+        let sample_size = self.codec_engine.encode(&sample, to_format, &context.options)?.len() as f64;
+        let pixels = |d: ImageDimensions| d.width as f64 * d.height as f64;
+        let pixel_ratio = pixels(dims) / pixels(sample_dims);
+        let likely = sample_size * pixel_ratio;
+        
+        // 缩小既会让细节更密集，也会平滑掉噪声，外推误差方向取决于图像内容
+        Ok(SizeEstimate {
+            min: (likely * 0.5) as u64,
+            likely: likely as u64,
+            max: (likely * 2.0) as u64,
+        })
+    }
+    
+    /// 生成转换计划 - 列出转换将依次执行的步骤和结果尺寸，不解码也不编码
+    ///
+    /// 与实际转换进行相同的请求校验；源尺寸取自文件头，无法读取时返回`DecodeError`。
+    /// 透明度合成按格式能力列出，实际转换中图像完全不透明时该步骤不改变像素
+    pub fn plan(
+        &self,
+        image_data: &[u8],
+        from_format: ImageFormat,
+        to_format: ImageFormat,
+        options: Option<ConversionOptions>,
+    ) -> Result<ConversionPlan> {
+        let context = ConversionContext {
+            start_time: Instant::now(),
+            from_format,
+            to_format,
+            from_custom: None,
+            to_custom: None,
+            input_size: image_data.len() as u64,
+            options: options.unwrap_or_else(|| self.get_default_options(from_format, to_format)),
+            enable_monitoring: false,
+        };
+        self.validate_conversion_request(&context)?;
+        if self.config.strict_input_validation {
+            Self::validate_declared_format(image_data, from_format)?;
+        }
+        self.codec_engine.validate_format_data(image_data, from_format)?;
+        
+        let source_dimensions = FormatDetector::read_dimensions(image_data).ok_or_else(|| ImageError::DecodeError {
+            format: from_format.to_string(),
+            message: "Unable to read image dimensions from header".to_string(),
+            source: None,
+        })?;
+        let source_format = FormatDetector::detect(image_data).unwrap_or(from_format);
+        
+        if context.is_passthrough() {
+            return Ok(ConversionPlan {
+                source_format,
+                source_dimensions,
+                operations: vec![PlannedOperation::Passthrough],
+                output_dimensions: source_dimensions,
+                lossy: false,
+            });
+        }
+        
+        // 与process_buffer的处理顺序保持一致
+        let options = &context.options;
+        let mut operations = vec![PlannedOperation::Decode { format: from_format }];
+        let mut dims = source_dimensions;
+        if let Some(target) = options.resize() {
+            if target.width == 0 || target.height == 0 {
+                return Err(ImageError::InvalidParameters {
+                    details: format!("Resize target {} must be non-empty", target),
+                });
+            }
+            let target = if options.allows_upscale() { target } else { processing::cap_upscale(target, dims) };
+            if target != dims {
+                let filter = options.resize_filter(dims, target);
+                operations.push(PlannedOperation::Resize { from: dims, to: target, filter });
+                dims = target;
+            }
+        }
+        let downscale = processing::fit_within(dims, &to_format.info().limits);
+        if let Some(to) = downscale {
+            operations.push(PlannedOperation::Downscale { from: dims, to });
+            dims = to;
+        }
+        let target_pixel_format = options.target_pixel_format();
+        let drops_alpha = !to_format.supports_transparency() || target_pixel_format.is_some_and(|target| !target.has_alpha());
+        if from_format.supports_transparency() && drops_alpha {
+            operations.push(PlannedOperation::FlattenAlpha { background: options.background() });
+        }
+        if options.is_grayscale() || target_pixel_format.is_some_and(|target| target.is_gray()) {
+            operations.push(PlannedOperation::Grayscale);
+        }
+        if let Some(clip_percent) = options.auto_contrast() {
+            operations.push(PlannedOperation::AutoContrast { clip_percent });
+        }
+        if let Some(colors) = options.palette_colors() {
+            operations.push(PlannedOperation::Quantize { colors });
+        }
+        operations.push(PlannedOperation::Encode { format: to_format });
+        
+        Ok(ConversionPlan {
+            source_format,
+            source_dimensions,
+            operations,
+            output_dimensions: dims,
+            lossy: downscale.is_some() || self.is_conversion_lossy(from_format, to_format, options),
+        })
+    }
+    
+    /// 添加转换观察者 - 按添加顺序在每次`convert_format`前后调用
+    ///
+    /// 批处理的并行工作转换器不继承观察者
+    pub fn add_observer(&mut self, observer: Box<dyn ConversionObserver>) {
+        self.observers.push(observer);
+    }
+    
+    /// 注册自定义编解码器 - 之后可在`convert_custom`中以`format_key`引用
+    ///
+    /// 引擎仍被未结束的转换（如超时后仍在运行的工作线程）共享时返回错误
+    pub fn register_codec(&mut self, format_key: impl Into<String>, codec: Box<dyn Codec<Rgba8>>) -> Result<()> {
+        let format_key = format_key.into();
+        match Arc::get_mut(&mut self.codec_engine) {
+            Some(engine) => engine.register_codec(format_key, codec),
+            None => Err(ImageError::ConfigurationError {
+                setting: "custom_codec".to_string(),
+                value: format_key,
+                reason: "codec engine is shared with a running conversion".to_string(),
+            }),
+        }
+    }
+    
+    /// 按格式键转换 - 键可以是已注册的自定义格式，也可以是内置格式名（如`"png"`）
+    ///
+    /// 两端都是内置格式时等同于`convert_format`。自定义格式经已注册的编解码器解码或编码，
+    /// 其余步骤（像素变换、警告、统计、观察者与错误收集）与内置格式相同；观察者、统计和
+    /// 结果的`format()`中，自定义格式以其编解码器`info().format`声明的内置格式表示。
+    /// 透明度按编解码器的声明处理，输出不受该内置格式的尺寸上限约束
+    pub fn convert_custom(
+        &mut self,
+        image_data: &[u8],
+        from_key: &str,
+        to_key: &str,
+        options: Option<ConversionOptions>,
+    ) -> Result<ConvertedImage> {
+        let from_builtin = from_key.parse::<ImageFormat>().ok();
+        let to_builtin = to_key.parse::<ImageFormat>().ok();
+        if let (Some(from), Some(to)) = (from_builtin, to_builtin) {
+            return self.convert_format(image_data, from, to, options);
+        }
+        
+        let resolve = |builtin: Option<ImageFormat>, key: &str| match builtin {
+            Some(format) => Ok((format, None)),
+            None => self
+                .codec_engine
+                .custom_codec_info(key)
+                .map(|info| (info.format, Some(CustomEndpoint::new(key, &info)))),
+        };
+        let ((from_format, from_custom), (to_format, to_custom)) = match resolve(from_builtin, from_key)
+            .and_then(|from| Ok((from, resolve(to_builtin, to_key)?)))
+        {
+            Ok(endpoints) => endpoints,
+            Err(error) => {
+                self.record_error(&error);
+                return Err(error);
+            }
+        };
+        
+        let context = ConversionContext {
+            start_time: Instant::now(),
+            from_format,
+            to_format,
+            from_custom,
+            to_custom,
+            input_size: image_data.len() as u64,
+            options: options.unwrap_or_else(|| self.get_default_options(from_format, to_format)),
+            enable_monitoring: self.config.enable_performance_monitoring,
+        };
+        
+        self.observe_and_execute(image_data, &context)
+    }
+    
+    /// 解码多页TIFF的全部页面 - 按文件中的顺序返回
+    ///
+    /// 启用`parallel` feature时各页并行解码
+    pub fn decode_tiff_pages(&self, image_data: &[u8]) -> Result<Vec<ImageBuffer<Rgba8>>> {
+        self.codec_engine.decode_pages(image_data, ImageFormat::Tiff)
+    }
+    
+    /// 可取消地解码多页TIFF - 每页开始前检查取消信号，已开始的页面会解码完毕
+    pub fn decode_tiff_pages_cancellable<C: Cancellation>(
+        &self,
+        image_data: &[u8],
+        cancel: &C,
+    ) -> Result<Vec<ImageBuffer<Rgba8>>> {
+        self.codec_engine.decode_pages_cancellable(image_data, ImageFormat::Tiff, cancel)
+    }
+    
+    /// 将多个缓冲区按顺序编码为多页TIFF - 压缩方式同单页TIFF的`tiff_compression`参数
+    pub fn encode_tiff_pages(
+        &self,
+        pages: &[ImageBuffer<Rgba8>],
+        options: Option<ConversionOptions>,
+    ) -> Result<Vec<u8>> {
+        let options = options.unwrap_or_else(|| self.get_default_options(ImageFormat::Tiff, ImageFormat::Tiff));
+        self.codec_engine.encode_pages(pages, ImageFormat::Tiff, &options)
+    }
+    
+    /// 解码动画的全部帧 - 返回每一帧及其显示时长
+    ///
+    /// 静态图像返回单帧，时长为零
+    pub fn decode_frames(
+        &self,
+        image_data: &[u8],
+        format: ImageFormat,
+    ) -> Result<Vec<AnimationFrame<Rgba8>>> {
+        self.codec_engine.decode_frames(image_data, format)
+    }
+
+    /// 可取消地解码动画 - 每帧开始前检查取消信号，已开始的帧会解码完毕
+    pub fn decode_frames_cancellable<C: Cancellation>(
+        &self,
+        image_data: &[u8],
+        format: ImageFormat,
+        cancel: &C,
+    ) -> Result<Vec<AnimationFrame<Rgba8>>> {
+        self.codec_engine.decode_frames_cancellable(image_data, format, cancel)
+    }
+
+    /// 将帧序列编码为目标格式的动画
+    pub fn encode_frames(
+        &self,
+        frames: &[AnimationFrame<Rgba8>],
+        to_format: ImageFormat,
+        options: Option<ConversionOptions>,
+    ) -> Result<Vec<u8>> {
+        self.encode_frames_cancellable(frames, to_format, options, &AtomicBool::new(false))
+    }
+
+    /// 可取消地编码动画 - 每帧编码前检查取消信号
+    pub fn encode_frames_cancellable<C: Cancellation>(
+        &self,
+        frames: &[AnimationFrame<Rgba8>],
+        to_format: ImageFormat,
+        options: Option<ConversionOptions>,
+        cancel: &C,
+    ) -> Result<Vec<u8>> {
+        let options = options.unwrap_or_else(|| self.get_default_options(to_format, to_format));
+        self.codec_engine.encode_frames_cancellable(frames, to_format, &options, cancel)
+    }
+
+    /// 将单个源图像生成多尺寸ICO - 如包含16/32/48的favicon
+    ///
+    /// 源图像等比缩放到每个边长（上限256），非正方形时居中并以透明填充
+    pub fn encode_ico_multisize(
+        &self,
+        buffer: &ImageBuffer<Rgba8>,
+        sizes: &[u32],
+    ) -> Result<ConvertedImage> {
+        let start_time = Instant::now();
+        let output_data = self.codec_engine.encode_multisize(buffer, ImageFormat::Ico, sizes)?;
+
+        // 结果尺寸取最大的条目
+        let largest = sizes.iter().copied().max().unwrap_or(0);
+        Ok(ConvertedImage::new(
+            output_data,
+            ImageDimensions { width: largest, height: largest },
+            ImageFormat::Ico,
+            start_time.elapsed().as_secs_f64() * 1000.0,
+            buffer.len() as u64 * Rgba8::CHANNEL_COUNT as u64,
+        ))
+    }
+
+    /// 将帧序列编码为动画图像 - 返回完整的转换结果
+    ///
+    /// 仅支持具备动画能力的格式，循环次数由选项中的`loop_count`控制
+    pub fn encode_animation(
+        &self,
+        frames: Vec<AnimationFrame<Rgba8>>,
+        to_format: ImageFormat,
+        options: Option<ConversionOptions>,
+    ) -> Result<ConvertedImage> {
+        self.encode_animation_cancellable(frames, to_format, options, &AtomicBool::new(false))
+    }
+
+    /// 可取消地编码动画图像 - 取消时返回带已编码帧数的`OperationCancelled`
+    pub fn encode_animation_cancellable<C: Cancellation>(
+        &self,
+        frames: Vec<AnimationFrame<Rgba8>>,
+        to_format: ImageFormat,
+        options: Option<ConversionOptions>,
+        cancel: &C,
+    ) -> Result<ConvertedImage> {
+        let start_time = Instant::now();
+
+        if !to_format.info().capabilities.supports_animation() {
+            return Err(ImageError::UnsupportedOperation {
+                operation: format!("Animation encoding to {:?}", to_format),
+            });
+        }
+
+        let dimensions = frames
+            .first()
+            .map(|(buffer, _)| buffer.dimensions())
+            .ok_or_else(|| ImageError::InvalidParameters {
+                details: "Animation requires at least one frame".to_string(),
+            })?;
+        let raw_size: u64 = frames
+            .iter()
+            .map(|(buffer, _)| buffer.len() as u64 * Rgba8::CHANNEL_COUNT as u64)
+            .sum();
+
+        let output_data = self.encode_frames_cancellable(&frames, to_format, options, cancel)?;
+
+        Ok(ConvertedImage::new(
+            output_data,
+            dimensions,
+            to_format,
+            start_time.elapsed().as_secs_f64() * 1000.0,
+            raw_size,
+        ))
+    }
+
+    /// 检测图像格式
+    pub fn detect_format(&self, image_data: &[u8]) -> Result<ImageFormat> {
+        self.codec_engine.detect_format(image_data)
+    }
+    
+    /// 获取格式信息
+    pub fn get_format_info(&self, format: ImageFormat) -> FormatInfo {
+        format.info()
+    }
+    
+    /// 目标格式的推荐编码选项 - 可直接传给转换方法，或在此基础上调整
+    ///
+    /// - JPEG：质量0.82，渐进式
+    /// - PNG：压缩级别9，自适应滤波
+    /// - WebP：质量0.8，`webp_method`为6（最高力度）
+    /// - AVIF：质量0.7，`avif_speed`为6
+    ///
+    /// 其余格式及当前构建未启用的格式返回默认选项
+    pub fn recommended_options(&self, to_format: ImageFormat) -> ConversionOptions {
+        self.codec_engine.default_options(to_format).unwrap_or_default()
+    }
+    
+    /// 获取支持的格式转换
+    pub fn get_supported_formats(&self) -> Vec<ImageFormat> {
+        self.codec_engine.supported_formats()
+    }
+    
+    /// 检查格式转换是否支持
+    pub fn is_conversion_supported(&self, from: ImageFormat, to: ImageFormat) -> bool {
+        self.codec_engine.supports_conversion(from, to)
     }
     
-    /// 检测图像格式
-    pub fn detect_format(&self, image_data: &[u8]) -> Result<ImageFormat> {
-        self.codec_engine.detect_format(image_data)
+    /// 列出源格式可转换到的所有目标格式 - 顺序与`get_supported_formats`一致
+    ///
+    /// 只包含当前构建启用的格式（如HEIC需要`heif`特性）；源格式不可解码时返回空列表
+    pub fn targets_for(&self, from: ImageFormat) -> Vec<ImageFormat> {
+        self.conversion_matrix()
+            .into_iter()
+            .filter(|capability| capability.from == from && capability.supported)
+            .map(|capability| capability.to)
+            .collect()
     }
     
-    /// 获取格式信息
-    pub fn get_format_info(&self, format: ImageFormat) -> FormatInfo {
-        format.info()
+    /// 判断转换是否会丢失信息 - 用于在执行前提示用户
+    ///
+    /// 目标编码有损（JPEG、AVIF、有损WebP、GIF调色板量化），或请求了缩放、
+    /// 灰度、自动对比度、透明度合成等像素变换时返回`true`，即使两端格式都是无损的
+    pub fn is_conversion_lossy(&self, from: ImageFormat, to: ImageFormat, options: &ConversionOptions) -> bool {
+        let lossy_encoding = Self::is_encoding_lossy(to, options);
+        let target_pixel_format = options.target_pixel_format();
+        let drops_alpha = !to.supports_transparency() || target_pixel_format.is_some_and(|target| !target.has_alpha());
+        let flattens_alpha = from.supports_transparency() && drops_alpha;
+        
+        lossy_encoding
+            || flattens_alpha
+            || options.is_grayscale()
+            || target_pixel_format.is_some_and(|target| target.is_gray())
+            || options.auto_contrast().is_some()
+            || options.resize().is_some()
     }
     
-    /// 获取支持的格式转换
-    pub fn get_supported_formats(&self) -> Vec<ImageFormat> {
-        self.codec_engine.supported_formats()
+    /// 转换能力矩阵 - 列出所有源→目标格式组合
+    ///
+    /// 由已注册编解码器的编解码能力和格式特性推导，用于界面中禁用不可用的目标格式
+    pub fn conversion_matrix(&self) -> Vec<ConversionCapability> {
+        let formats = self.get_supported_formats();
+        let lossless_options = ConversionOptionsBuilder::new().webp_lossless(true).build();
+        
+        formats
+            .iter()
+            .flat_map(|&from| formats.iter().map(move |&to| (from, to)))
+            .map(|(from, to)| {
+                let decodable = self
+                    .codec_engine
+                    .get_codec_info(from)
+                    .is_ok_and(|info| info.supports_decode);
+                let encoder = self.codec_engine.get_codec_info(to).ok().filter(|info| info.supports_encode);
+                let supported = decodable && encoder.is_some();
+                let lossless_capable = supported
+                    && encoder.is_some_and(|info| info.quality_features.supports_lossless)
+                    && !self.is_conversion_lossy(from, to, &lossless_options);
+                
+                ConversionCapability { from, to, supported, lossless_capable }
+            })
+            .collect()
+    }
+    
+    /// 获取错误统计 - 包含批处理中各项的失败，未启用错误收集时为空
+    pub fn error_statistics(&self) -> ErrorStatistics {
+        self.shared
+            .error_collector
+            .as_ref()
+            .map(|collector| collector.get_statistics())
+            .unwrap_or_default()
     }
     
     /// 获取转换统计
@@ -328,6 +1559,11 @@ impl FormatConverter {
 impl FormatConverter {
     /// 验证转换请求 - 私有方法
     fn validate_conversion_request(&self, context: &ConversionContext) -> Result<()> {
+        // 格式所需特性未启用时报告UnsupportedFormat及可用格式
+        for format in [context.from_format, context.to_format] {
+            self.codec_engine.get_codec_info(format)?;
+        }
+        
         if !self.codec_engine.supports_conversion(context.from_format, context.to_format) {
             return Err(ImageError::UnsupportedOperation {
                 operation: format!(
@@ -343,9 +1579,139 @@ impl FormatConverter {
             });
         }
         
+        if let Some(colors) = context.options.palette_colors() {
+            if !matches!(context.to_format, ImageFormat::Png | ImageFormat::Gif) {
+                return Err(ImageError::UnsupportedOperation {
+                    operation: format!("Indexed color output is not supported for {:?}", context.to_format),
+                });
+            }
+            if !(2..=256).contains(&colors) {
+                return Err(ImageError::InvalidParameters {
+                    details: format!("Palette size {} must be between 2 and 256", colors),
+                });
+            }
+        }
+        
+        if let Some(pixel_format) = context.options.target_pixel_format() {
+            let supported = match context.to_format {
+                ImageFormat::Png => matches!(pixel_format, PixelFormat::Gray8 | PixelFormat::Rgb8 | PixelFormat::Rgba8),
+                ImageFormat::Jpeg | ImageFormat::Pnm => matches!(pixel_format, PixelFormat::Gray8 | PixelFormat::Rgb8),
+                _ => false,
+            };
+            if !supported {
+                return Err(ImageError::UnsupportedOperation {
+                    operation: format!("Pixel format {:?} cannot be written as {:?}", pixel_format, context.to_format),
+                });
+            }
+            if context.options.palette_colors().is_some() {
+                return Err(ImageError::InvalidParameters {
+                    details: "target_pixel_format cannot be combined with palette_colors".to_string(),
+                });
+            }
+        }
+        
+        if context.options.requires_lossless() && Self::is_encoding_lossy(context.to_format, &context.options) {
+            return Err(ImageError::UnsupportedOperation {
+                operation: format!(
+                    "Lossless output required but {:?} encoding with these options is lossy",
+                    context.to_format
+                ),
+            });
+        }
+        
         Ok(())
     }
     
+    /// 目标编码是否有损 - JPEG、AVIF、有损WebP，以及需要调色板量化的GIF和索引色PNG
+    fn is_encoding_lossy(to: ImageFormat, options: &ConversionOptions) -> bool {
+        match to {
+            ImageFormat::Png => options.palette_colors().is_some(),
+            // 与WebP编码器的模式选择一致：显式设置优先，否则指定质量即为有损
+            ImageFormat::WebP => options.webp_lossless().map_or(options.quality().is_some(), |lossless| !lossless),
+            ImageFormat::Gif => true,
+            _ => to.supports_lossy(),
+        }
+    }
+    
+    /// 验证转换请求后执行转换 - 仅实际执行的转换计入转换统计
+    fn validate_and_execute(&mut self, image_data: &[u8], context: &ConversionContext) -> Result<ConvertedImage> {
+        self.validate_conversion_request(context)?;
+        // 自定义源格式的签名由其编解码器在解码时检查
+        if context.from_custom.is_none() {
+            if self.config.strict_input_validation {
+                Self::validate_declared_format(image_data, context.from_format)?;
+            }
+            // 解码前用声明格式的编解码器检查签名，直通时也不会把不符的数据原样返回
+            self.codec_engine.validate_format_data(image_data, context.from_format)?;
+        }
+        
+        let result = self.execute_conversion(image_data, context);
+        self.update_conversion_stats(context, result.as_ref());
+        result
+    }
+    
+    /// 通知观察者并执行转换 - 任一观察者拒绝时不执行转换，失败时记录错误
+    fn observe_and_execute(&mut self, image_data: &[u8], context: &ConversionContext) -> Result<ConvertedImage> {
+        // 1. 通知观察者
+        let mut approval = Ok(());
+        for observer in &self.observers {
+            let verdict = observer.before(context.from_format, context.to_format, image_data.len());
+            approval = approval.and(verdict);
+        }
+        
+        // 2. 验证并执行转换
+        let result = approval.and_then(|()| self.validate_and_execute(image_data, context));
+        
+        // 3. 记录失败
+        if let Err(error) = &result {
+            self.record_error(error);
+        }
+        
+        for observer in &self.observers {
+            observer.after(&result);
+        }
+        result
+    }
+    
+    /// 记录失败的转换 - 未启用错误收集时忽略
+    fn record_error(&self, error: &ImageError) {
+        if let Some(collector) = &self.shared.error_collector {
+            collector.record_error(error);
+        }
+    }
+    
+    /// 生成不与本次已写出文件重名的输出路径
+    fn unique_output_path(
+        source: &Path,
+        to_format: ImageFormat,
+        output_dir: &Path,
+        used_names: &mut HashSet<String>,
+    ) -> PathBuf {
+        let stem = source
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("converted_image");
+        let mut name = format!("{}.{}", stem, to_format.extension());
+        let mut suffix = 2;
+        while !used_names.insert(name.clone()) {
+            name = format!("{}_{}.{}", stem, suffix, to_format.extension());
+            suffix += 1;
+        }
+        output_dir.join(name)
+    }
+    
+    /// 校验声明的源格式 - 检测到的格式与声明不符时返回`InvalidFormat`
+    ///
+    /// 无法识别签名的数据（如不带页脚的TGA）交由解码器判断
+    fn validate_declared_format(image_data: &[u8], declared: ImageFormat) -> Result<()> {
+        match FormatDetector::detect(image_data) {
+            Ok(detected) if detected != declared => Err(ImageError::InvalidFormat {
+                format: format!("declared {:?} but data is {:?}", declared, detected),
+            }),
+            _ => Ok(()),
+        }
+    }
+    
     /// 执行单次转换 - 核心转换逻辑
     fn execute_conversion(
         &mut self,
@@ -359,82 +1725,186 @@ impl FormatConverter {
             self.performance_monitor.start_conversion(&context.from_format, &context.to_format);
         }
         
-        let result = (|| -> Result<ConvertedImage> {
-            // 1. 解码输入图像
-            let image_buffer = self.codec_engine.decode::<Rgba8>(image_data, context.from_format)?;
-            
-            // 2. 编码为目标格式
-            let output_data = self.codec_engine.encode(
-                &image_buffer,
-                context.to_format,
-                &context.options,
-            )?;
-            
-            // 3. 计算指标
-            let conversion_time_ms = context.start_time.elapsed().as_secs_f64() * 1000.0;
-            
-            // 4. 构建结果
-            Ok(ConvertedImage::new(
-                output_data,
-                image_buffer.dimensions(),
-                context.to_format,
-                conversion_time_ms,
-                context.input_size,
-            ))
-        })();
+        let mut metrics = PerformanceMetrics::default();
+        let result = match context.options.timeout() {
+            Some(timeout) => self.execute_with_timeout(image_data, context, timeout, &mut metrics),
+            None => self.pipeline().run(image_data, context, &AtomicBool::new(false), &mut metrics),
+        };
         
         // 结束性能监控
         if context.enable_monitoring {
             let duration = start_time.elapsed();
             let success = result.is_ok();
+            self.performance_monitor.record_stage_timing(&metrics.timing);
+            self.performance_monitor.record_simd_usage(metrics.system.simd_utilized);
             self.performance_monitor.end_conversion(duration, success);
         }
         
         result
     }
     
-    /// 执行并行批处理
-    fn execute_parallel_batch(
-        &mut self,
-        images: Vec<ImageInput>,
-        tasks: Vec<ConversionTask>,
-    ) -> Result<Vec<Result<ConvertedImage>>> {
-        let results: Vec<Result<ConvertedImage>> = images
-            .into_par_iter()
-            .zip(tasks.into_par_iter())
-            .map(|(image, task)| {
-                let mut local_converter = FormatConverter::new(self.config.clone())?;
-                local_converter.convert_format(
-                    image.data(),
-                    task.from_format,
-                    task.to_format,
-                    task.options,
-                )
+    /// 在工作线程中执行转换 - 超时后放弃等待
+    ///
+    /// 线程无法被强制终止：超时后设置取消标志，
+    /// 工作线程在当前阶段（解码/处理/编码）结束后自行退出
+    #[cfg(not(target_arch = "wasm32"))]
+    fn execute_with_timeout(
+        &self,
+        image_data: &[u8],
+        context: &ConversionContext,
+        timeout: Duration,
+        metrics: &mut PerformanceMetrics,
+    ) -> Result<ConvertedImage> {
+        let detached_workers = &self.shared.detached_workers;
+        let running = detached_workers.load(Ordering::Acquire);
+        if running >= self.config.max_detached_workers {
+            return Err(ImageError::ResourceExhausted {
+                resource: format!(
+                    "conversion worker threads: {} timed-out conversions still running (limit {})",
+                    running, self.config.max_detached_workers
+                ),
+            });
+        }
+        
+        let pipeline = self.pipeline();
+        let worker_data = image_data.to_vec();
+        let worker_context = context.clone();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let worker_cancelled = Arc::clone(&cancelled);
+        let state = Arc::new(AtomicU8::new(WORKER_RUNNING));
+        let exit = WorkerExit {
+            state: Arc::clone(&state),
+            detached_workers: Arc::clone(detached_workers),
+        };
+        let (sender, receiver) = mpsc::channel();
+        
+        let worker = thread::Builder::new()
+            .name("rustimage-conversion".to_string())
+            .spawn(move || {
+                let _exit = exit;
+                // 接收端可能已因超时被丢弃，发送失败无需处理
+                let mut worker_metrics = PerformanceMetrics::default();
+                let result = pipeline.run(&worker_data, &worker_context, &worker_cancelled, &mut worker_metrics);
+                let _ = sender.send((result, worker_metrics));
             })
-            .collect();
+            .map_err(|e| ImageError::ResourceExhausted {
+                resource: format!("conversion worker thread: {}", e),
+            })?;
         
-        Ok(results)
+        match receiver.recv_timeout(timeout) {
+            Ok((result, worker_metrics)) => {
+                let _ = worker.join();
+                *metrics = worker_metrics;
+                result
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                // 工作线程在下一个检查点发现取消标志后退出；先占用名额再标记脱离，
+                // 保证线程释放名额时计数已经增加
+                cancelled.store(true, Ordering::Release);
+                detached_workers.fetch_add(1, Ordering::AcqRel);
+                if state
+                    .compare_exchange(WORKER_RUNNING, WORKER_DETACHED, Ordering::AcqRel, Ordering::Acquire)
+                    .is_err()
+                {
+                    // 线程恰好已经结束
+                    detached_workers.fetch_sub(1, Ordering::AcqRel);
+                    let _ = worker.join();
+                }
+                Err(ImageError::OperationTimeout {
+                    operation: format!("{} to {} conversion", context.from_format, context.to_format),
+                    timeout_ms: timeout.as_millis() as u64,
+                })
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(ImageError::ProcessingFailed {
+                stage: "conversion worker".to_string(),
+                reason: "worker thread panicked".to_string(),
+            }),
+        }
+    }
+    
+    /// WASM 环境没有线程 - 同步执行后检查耗时
+    #[cfg(target_arch = "wasm32")]
+    fn execute_with_timeout(
+        &self,
+        image_data: &[u8],
+        context: &ConversionContext,
+        timeout: Duration,
+        metrics: &mut PerformanceMetrics,
+    ) -> Result<ConvertedImage> {
+        let result = self.pipeline().run(image_data, context, &AtomicBool::new(false), metrics)?;
+        if context.start_time.elapsed() > timeout {
+            return Err(ImageError::OperationTimeout {
+                operation: format!("{} to {} conversion", context.from_format, context.to_format),
+                timeout_ms: timeout.as_millis() as u64,
+            });
+        }
+        Ok(result)
+    }
+    
+    /// 创建转换管线 - 共享编解码引擎，可移动到工作线程
+    fn pipeline(&self) -> ConversionPipeline {
+        ConversionPipeline {
+            codec_engine: Arc::clone(&self.codec_engine),
+            quality_assessor: self.quality_assessor.clone(),
+            assess_quality: self.config.enable_quality_assessment,
+            simd_enabled: self.config.enable_simd,
+        }
+    }
+    
+    /// 逐项执行批处理 - 启用并行时每项在共享资源的独立转换器上执行，否则复用当前转换器
+    ///
+    /// 结果顺序与输入一致；并行时创建转换器失败的项以该错误作为结果
+    fn run_batch<T, R, F>(&mut self, items: Vec<T>, per_item: F) -> Vec<Result<R>>
+    where
+        T: Send,
+        R: Send,
+        F: Fn(&mut FormatConverter, T) -> Result<R> + Sync,
+    {
+        if !self.config.enable_parallel {
+            return items.into_iter().map(|item| per_item(self, item)).collect();
+        }
+        
+        let config = &self.config;
+        let shared = &self.shared;
+        Self::install(shared.thread_pool.as_deref(), || {
+            items
+                .into_par_iter()
+                .map(|item| {
+                    FormatConverter::with_shared(config.clone(), shared.clone())
+                        .and_then(|mut converter| per_item(&mut converter, item))
+                })
+                .collect()
+        })
     }
     
-    /// 执行顺序批处理
-    fn execute_sequential_batch(
+    /// 按图像与任务配对执行批处理 - 两者数量不一致时整体返回错误
+    fn run_tasks<R, F>(
         &mut self,
         images: Vec<ImageInput>,
         tasks: Vec<ConversionTask>,
-    ) -> Result<Vec<Result<ConvertedImage>>> {
-        let mut results = Vec::with_capacity(images.len());
-        
-        for (image, task) in images.into_iter().zip(tasks.into_iter()) {
-            let result = self.convert_format(
-                image.data(),
-                task.from_format,
-                task.to_format,
-                task.options,  
-            );
-            results.push(result);
+        per_item: F,
+    ) -> Result<Vec<Result<R>>>
+    where
+        R: Send,
+        F: Fn(&mut FormatConverter, ImageInput, ConversionTask) -> Result<R> + Sync,
+    {
+        if images.len() != tasks.len() {
+            return Err(ImageError::InvalidParameters {
+                details: format!(
+                    "Images count ({}) does not match tasks count ({})",
+                    images.len(),
+                    tasks.len()
+                ),
+            });
         }
         
-        Ok(results)
+        let pairs: Vec<_> = images.into_iter().zip(tasks).collect();
+        Ok(self.run_batch(pairs, |converter, (image, task)| per_item(converter, image, task)))
+    }
+    
+    /// 执行单个批处理任务
+    fn convert_task(&mut self, image: ImageInput, task: ConversionTask) -> Result<ConvertedImage> {
+        self.convert_format(image.data(), task.from_format, task.to_format, task.options)
     }
     
     /// 聚合批处理结果
@@ -466,18 +1936,25 @@ impl FormatConverter {
     /// 获取默认选项
     fn get_default_options(
         &self,
-        _from_format: ImageFormat,
+        from_format: ImageFormat,
         _to_format: ImageFormat,
     ) -> ConversionOptions {
-        match self.config.default_quality_strategy {
-            QualityStrategy::PreserveOriginal => ConversionOptionsBuilder::new().quality(0.95).build(),
-            QualityStrategy::OptimizeSize => ConversionOptionsBuilder::new().quality(0.75).compression_level(9).build(),
-            QualityStrategy::Balanced => ConversionOptionsBuilder::new().quality(0.85).compression_level(6).build(),
-            QualityStrategy::MaxQuality => ConversionOptionsBuilder::new().quality(1.0).compression_level(0).build(),
+        let builder = match self.config.default_quality_strategy {
+            QualityStrategy::PreserveOriginal => ConversionOptionsBuilder::new().quality(0.95),
+            QualityStrategy::OptimizeSize => ConversionOptionsBuilder::new().quality(0.75).compression_level(9),
+            QualityStrategy::Balanced => ConversionOptionsBuilder::new().quality(0.85).compression_level(6),
+            QualityStrategy::MaxQuality => ConversionOptionsBuilder::new().quality(1.0).compression_level(0),
+        };
+        
+        // 无损来源默认保持无损，避免WebP因默认质量参数而走有损编码
+        if from_format.supports_lossy() {
+            builder.build()
+        } else {
+            builder.webp_lossless(true).build()
         }
     }
     
-    fn update_conversion_stats(&self, context: &ConversionContext, result: &Result<ConvertedImage>) {
+    fn update_conversion_stats(&self, context: &ConversionContext, result: std::result::Result<&ConvertedImage, &ImageError>) {
         let mut stats = self.conversion_stats.lock().unwrap();
         
         stats.total_conversions += 1;
@@ -506,6 +1983,279 @@ impl FormatConverter {
     }
 }
 
+impl ConversionPipeline {
+    /// 执行 解码 -> 处理 -> 编码，在阶段之间检查取消标志
+    /// 
+    /// 各阶段耗时与SIMD使用情况写入`metrics`，失败时已完成阶段的数据仍然保留
+    fn run(
+        &self,
+        image_data: &[u8],
+        context: &ConversionContext,
+        cancelled: &AtomicBool,
+        metrics: &mut PerformanceMetrics,
+    ) -> Result<ConvertedImage> {
+        // 0. 同格式且无需像素变换时原样返回，避免重新编码的开销和有损格式的代际损失
+        if context.is_passthrough() {
+            let data = if context.options.strips_all_metadata() {
+                codecs::strip_metadata(image_data, context.from_format)
+            } else {
+                Some(image_data.to_vec())
+            };
+            if let (Some(data), Some(dimensions)) = (data, self.codec_engine.check_header(image_data)?) {
+                return Ok(ConvertedImage::new(
+                    data,
+                    dimensions,
+                    context.to_format,
+                    context.start_time.elapsed().as_secs_f64() * 1000.0,
+                    context.input_size,
+                ));
+            }
+        }
+        
+        // 1. 解码输入图像
+        let image_buffer = self.decode(image_data, context, metrics)?;
+        
+        self.process_and_encode(image_buffer, context, cancelled, metrics)
+    }
+    
+    /// 解码阶段 - 按选项解码并记录耗时
+    fn decode(
+        &self,
+        image_data: &[u8],
+        context: &ConversionContext,
+        metrics: &mut PerformanceMetrics,
+    ) -> Result<ImageBuffer<Rgba8>> {
+        let stage_start = Instant::now();
+        let decoded = match &context.from_custom {
+            Some(custom) => self.codec_engine.decode_custom(image_data, &custom.key),
+            None => self.codec_engine.decode_with_options::<Rgba8>(image_data, context.from_format, &context.options),
+        };
+        metrics.timing.decode_time_ms = elapsed_ms(stage_start);
+        decoded
+    }
+    
+    /// 处理 -> 编码 - 从已解码的缓冲区继续转换
+    fn process_and_encode(
+        &self,
+        image_buffer: ImageBuffer<Rgba8>,
+        context: &ConversionContext,
+        cancelled: &AtomicBool,
+        metrics: &mut PerformanceMetrics,
+    ) -> Result<ConvertedImage> {
+        self.process_and_encode_keeping_buffer(image_buffer, context, cancelled, metrics)
+            .map(|(converted, _)| converted)
+    }
+    
+    /// 处理 -> 编码，同时返回变换后、编码前的缓冲区
+    fn process_and_encode_keeping_buffer(
+        &self,
+        image_buffer: ImageBuffer<Rgba8>,
+        context: &ConversionContext,
+        cancelled: &AtomicBool,
+        metrics: &mut PerformanceMetrics,
+    ) -> Result<(ConvertedImage, ImageBuffer<Rgba8>)> {
+        let check_cancelled = |stage: &str| -> Result<()> {
+            if cancelled.is_cancelled() {
+                return Err(ImageError::OperationCancelled {
+                    operation: format!("{} to {} conversion", context.from_format, context.to_format),
+                    reason: format!("cancelled before {}", stage),
+                    completed: 0,
+                    total: 1,
+                });
+            }
+            Ok(())
+        };
+        check_cancelled("processing")?;
+        
+        // 2. 按选项执行像素变换
+        let stage_start = Instant::now();
+        let mut warnings = Vec::new();
+        let processed = self.process_buffer(image_buffer, context, cancelled, metrics, &mut warnings);
+        metrics.timing.processing_time_ms = elapsed_ms(stage_start);
+        let image_buffer = processed?;
+        check_cancelled("encoding")?;
+        
+        // 3. 编码为目标格式 - 设置了时间预算时按预算调整编码力度，自定义格式不分力度
+        let stage_start = Instant::now();
+        let encoded = match (&context.to_custom, context.options.time_budget()) {
+            (Some(custom), _) => self.codec_engine.encode_custom(&image_buffer, &custom.key, &context.options),
+            (None, Some(budget)) => self.encode_within_budget(&image_buffer, context, budget),
+            (None, None) => self.codec_engine.encode(&image_buffer, context.to_format, &context.options),
+        };
+        metrics.timing.encode_time_ms = elapsed_ms(stage_start);
+        let mut output_data = encoded?;
+        if context.options.strips_all_metadata() && context.to_custom.is_none() {
+            if let Some(stripped) = codecs::strip_metadata(&output_data, context.to_format) {
+                output_data = stripped;
+            }
+        }
+        
+        // 4. 计算指标
+        let conversion_time_ms = context.start_time.elapsed().as_secs_f64() * 1000.0;
+        
+        // 5. 构建结果
+        let converted = ConvertedImage::new(
+            output_data,
+            image_buffer.dimensions(),
+            context.to_format,
+            conversion_time_ms,
+            context.input_size,
+        );
+        
+        // 6. 记录随输出嵌入的色彩配置文件
+        let converted = match image_buffer.icc_profile() {
+            Some(profile) if context.options.preserves_color_space() => {
+                if context.to_format.supports_icc_profile() {
+                    converted.with_icc_profile(profile.to_vec())
+                } else {
+                    warnings.push(ConversionWarning::ColorProfileDropped { format: context.to_format });
+                    converted
+                }
+            }
+            _ => converted,
+        };
+        
+        if context.options.preserves_metadata()
+            && !context.to_format.info().capabilities.supports_metadata()
+        {
+            warnings.push(ConversionWarning::MetadataDropped { format: context.to_format });
+        }
+        let converted = converted.with_warnings(warnings);
+        
+        // 7. 回解码输出并与编码前的图像比较
+        //    质量指标只是附加信息：目标不可解码或比较失败时不记录，不影响编码结果
+        if self.assess_quality {
+            if let Some(metrics) = self.assess_output(&image_buffer, converted.data(), context) {
+                return Ok((converted.with_quality_metrics(metrics), image_buffer));
+            }
+        }
+        
+        Ok((converted, image_buffer))
+    }
+    
+    /// 回解码输出并计算质量指标 - 目标编解码器不支持解码、解码或比较失败时返回`None`
+    fn assess_output(
+        &self,
+        original: &ImageBuffer<Rgba8>,
+        output: &[u8],
+        context: &ConversionContext,
+    ) -> Option<QualityMetrics> {
+        let info = match &context.to_custom {
+            Some(custom) => self.codec_engine.custom_codec_info(&custom.key),
+            None => self.codec_engine.get_codec_info(context.to_format),
+        };
+        if !info.ok()?.supports_decode {
+            return None;
+        }
+        
+        let reconstructed = match &context.to_custom {
+            Some(custom) => self.codec_engine.decode_custom(output, &custom.key),
+            None => self.codec_engine.decode::<Rgba8>(output, context.to_format),
+        };
+        reconstructed
+            .and_then(|reconstructed| self.quality_assessor.assess_quality(original, &reconstructed))
+            .ok()
+    }
+    
+    /// 在时间预算内编码 - 先以最快力度编码，剩余时间足够再按更高力度重新编码
+    ///
+    /// 以上一次编码耗时估计下一级的耗时（力度越高越慢，按两倍估计），
+    /// 预计超出预算或更高力度编码失败时保留已有结果，只有最快一级失败才报错
+    fn encode_within_budget(
+        &self,
+        image_buffer: &ImageBuffer<Rgba8>,
+        context: &ConversionContext,
+        budget: Duration,
+    ) -> Result<Vec<u8>> {
+        let mut levels = effort_ladder(context.to_format, &context.options).into_iter();
+        let fastest = levels.next().unwrap_or_else(|| context.options.clone());
+        
+        let attempt_start = Instant::now();
+        let mut output = self.codec_engine.encode(image_buffer, context.to_format, &fastest)?;
+        let mut last_cost = attempt_start.elapsed();
+        
+        for options in levels {
+            if context.start_time.elapsed() + last_cost * 2 > budget {
+                break;
+            }
+            // 更高力度失败时保留已有结果，不再继续提高
+            let attempt_start = Instant::now();
+            match self.codec_engine.encode(image_buffer, context.to_format, &options) {
+                Ok(encoded) => output = encoded,
+                Err(_) => break,
+            }
+            last_cost = attempt_start.elapsed();
+        }
+        
+        Ok(output)
+    }
+    
+    /// 按选项执行像素变换 - 解码与编码之间的处理管线，缩放时逐行检查取消标志
+    fn process_buffer(
+        &self,
+        mut buffer: ImageBuffer<Rgba8>,
+        context: &ConversionContext,
+        cancelled: &AtomicBool,
+        metrics: &mut PerformanceMetrics,
+        warnings: &mut Vec<ConversionWarning>,
+    ) -> Result<ImageBuffer<Rgba8>> {
+        // 按选项缩放到指定尺寸
+        if let Some(target) = context.options.resize() {
+            if target.width == 0 || target.height == 0 {
+                return Err(ImageError::InvalidParameters {
+                    details: format!("Resize target {} must be non-empty", target),
+                });
+            }
+            let from = buffer.dimensions();
+            let requested = target;
+            let target = if context.options.allows_upscale() { target } else { processing::cap_upscale(target, from) };
+            if target != requested {
+                warnings.push(ConversionWarning::UpscaleCapped { requested, to: target });
+            }
+            if target != from {
+                let filter = context.options.resize_filter(from, target);
+                buffer = processing::resize_cancellable(&buffer, target, filter, context.options.is_linear_resize(), cancelled)?;
+            }
+        }
+        
+        // 超出目标格式尺寸上限时等比缩小 - 自定义格式没有声明上限
+        let from = buffer.dimensions();
+        let limits = context.to_custom.is_none().then(|| context.to_format.info().limits);
+        if let Some(to) = limits.and_then(|limits| processing::fit_within(from, &limits)) {
+            buffer = processing::resize_cancellable(
+                &buffer,
+                to,
+                context.options.downscale_filter(),
+                context.options.is_linear_resize(),
+                cancelled,
+            )?;
+            warnings.push(ConversionWarning::Downscaled { from, to });
+        }
+        
+        // 目标格式或指定的像素格式无法表示透明度时与背景色合成
+        let target_pixel_format = context.options.target_pixel_format();
+        let drops_alpha = !context.target_supports_transparency()
+            || target_pixel_format.is_some_and(|target| !target.has_alpha());
+        if context.source_supports_transparency() && drops_alpha {
+            let background = context.options.background();
+            if buffer.as_slice().iter().any(|p| p.a != u8::MAX) {
+                warnings.push(ConversionWarning::AlphaFlattened { background });
+            }
+            processing::flatten_alpha(&mut buffer, background);
+        }
+        
+        if context.options.is_grayscale() || target_pixel_format.is_some_and(|target| target.is_gray()) {
+            metrics.system.simd_utilized |= processing::grayscale(&mut buffer, self.simd_enabled);
+        }
+        
+        if let Some(clip_percent) = context.options.auto_contrast() {
+            processing::auto_contrast(&mut buffer, clip_percent)?;
+        }
+        
+        Ok(buffer)
+    }
+}
+
 // =============================================================================
 // 配置构建器实现
 // =============================================================================
@@ -537,6 +2287,12 @@ impl ConverterConfigBuilder {
         self
     }
     
+    /// 解码像素上限 - 默认1亿像素，`None`表示不限制
+    pub fn max_decoded_pixels(mut self, limit: Option<u64>) -> Self {
+        self.config.max_decoded_pixels = limit;
+        self
+    }
+    
     pub fn enable_performance_monitoring(mut self, enabled: bool) -> Self {
         self.config.enable_performance_monitoring = enabled;
         self
@@ -557,6 +2313,23 @@ impl ConverterConfigBuilder {
         self
     }
     
+    pub fn enable_error_collection(mut self, enabled: bool) -> Self {
+        self.config.enable_error_collection = enabled;
+        self
+    }
+    
+    /// 关闭后跳过解码前的格式检测，由解码器自行发现不匹配
+    pub fn strict_input_validation(mut self, enabled: bool) -> Self {
+        self.config.strict_input_validation = enabled;
+        self
+    }
+    
+    /// 超时工作线程上限 - 超时的转换在下一个检查点之前仍占用线程，默认4个
+    pub fn max_detached_workers(mut self, limit: usize) -> Self {
+        self.config.max_detached_workers = limit;
+        self
+    }
+    
     pub fn build(self) -> ConverterConfig {
         self.config
     }
@@ -575,12 +2348,16 @@ impl Default for ConverterConfig {
             thread_pool_size: None,
             enable_simd: true,
             memory_limit: None,
+            max_decoded_pixels: Some(DEFAULT_MAX_DECODED_PIXELS),
             enable_performance_monitoring: false,
             enable_quality_assessment: false,
             enable_detailed_logging: false,
             default_quality_strategy: QualityStrategy::Balanced,
             batch_size: 32,
             auto_format_optimization: false,
+            strict_input_validation: true,
+            enable_error_collection: false,
+            max_detached_workers: DEFAULT_MAX_DETACHED_WORKERS,
         }
     }
 }
@@ -598,6 +2375,132 @@ pub enum OptimizationTarget {
     Balanced,
 }
 
+/// 转换观察者 - 用于日志、指标等横切逻辑，也可在转换前拒绝请求
+pub trait ConversionObserver: Send + Sync {
+    /// 转换开始前调用 - 返回错误时跳过转换，该错误成为转换结果；其余观察者仍会收到通知
+    fn before(&self, from_format: ImageFormat, to_format: ImageFormat, input_len: usize) -> Result<()> {
+        let _ = (from_format, to_format, input_len);
+        Ok(())
+    }
+    
+    /// 转换结束后调用 - 无论成功、失败或被拒绝都会调用
+    fn after(&self, result: &Result<ConvertedImage>) {
+        let _ = result;
+    }
+}
+
+/// 取消信号 - 批量操作在任务之间查询
+pub trait Cancellation: Sync {
+    /// 是否已请求取消
+    fn is_cancelled(&self) -> bool;
+}
+
+impl Cancellation for AtomicBool {
+    fn is_cancelled(&self) -> bool {
+        self.load(Ordering::Acquire)
+    }
+}
+
+/// 取消令牌 - 可克隆并跨线程共享的取消标志
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// 请求取消 - 所有克隆共享同一状态
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+}
+
+impl Cancellation for CancelToken {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.is_cancelled()
+    }
+}
+
+/// 已解码的图像 - 不透明句柄，供多次编码复用
+#[derive(Debug, Clone)]
+pub struct DecodedImage {
+    /// 解码后的RGBA像素
+    buffer: ImageBuffer<Rgba8>,
+    /// 源格式 - 无容器的原始像素为`None`
+    source_format: Option<ImageFormat>,
+    /// 源数据大小（字节）
+    source_size: u64,
+}
+
+impl DecodedImage {
+    /// 从外部解码得到的RGBA字节构造句柄（如浏览器原生解码器的输出）
+    ///
+    /// `rgba`必须恰好为`width × height × 4`字节；`source_format`和`source_size`
+    /// 描述原始编码数据，用于统计和默认选项
+    pub fn from_rgba(
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+        source_format: ImageFormat,
+        source_size: u64,
+    ) -> Result<Self> {
+        Self::from_rgba_bytes(width, height, rgba, Some(source_format), source_size)
+    }
+    
+    /// 校验尺寸与长度后构造句柄 - 宽高为零时返回`InvalidDimensions`
+    fn from_rgba_bytes(
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+        source_format: Option<ImageFormat>,
+        source_size: u64,
+    ) -> Result<Self> {
+        if width == 0 || height == 0 {
+            return Err(ImageError::InvalidDimensions {
+                width,
+                height,
+                reason: "zero dimension".to_string(),
+            });
+        }
+        
+        let expected_len = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|pixels| pixels.checked_mul(4));
+        if expected_len != Some(rgba.len()) {
+            return Err(ImageError::InvalidParameters {
+                details: format!(
+                    "RGBA data length {} does not match dimensions {}×{}",
+                    rgba.len(), width, height
+                ),
+            });
+        }
+        
+        let pixels = rgba
+            .chunks_exact(4)
+            .map(|p| Rgba8 { r: p[0], g: p[1], b: p[2], a: p[3] })
+            .collect();
+        
+        Ok(DecodedImage {
+            buffer: ImageBuffer::from_raw(width, height, pixels, PixelFormat::Rgba8)?,
+            source_format,
+            source_size,
+        })
+    }
+    
+    pub fn dimensions(&self) -> ImageDimensions { self.buffer.dimensions() }
+    pub fn buffer(&self) -> &ImageBuffer<Rgba8> { &self.buffer }
+    pub fn source_format(&self) -> Option<ImageFormat> { self.source_format }
+    pub fn source_size(&self) -> u64 { self.source_size }
+    
+    /// 转换上下文中使用的源格式 - 无容器的原始像素按无损且支持透明度的PNG语义处理
+    fn context_format(&self) -> ImageFormat {
+        self.source_format.unwrap_or(ImageFormat::Png)
+    }
+}
+
 /// 转换统计信息
 #[derive(Debug, Clone)]
 pub struct ConversionStatistics {
@@ -609,4 +2512,20 @@ pub struct ConversionStatistics {
     pub compression_ratio: f64,
     pub most_used_conversion: Option<(ImageFormat, ImageFormat)>,
     pub performance_metrics: PerformanceMetrics,
+}
+
+impl std::fmt::Display for ConversionStatistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{} conversions ({:.1}% success), avg {:.2} ms, {} bytes processed, ratio {:.3}",
+               self.successful_conversions,
+               self.total_conversions,
+               self.success_rate * 100.0,
+               self.average_processing_time_ms,
+               self.total_bytes_processed,
+               self.compression_ratio)?;
+        if let Some((from, to)) = self.most_used_conversion {
+            write!(f, ", most used {} -> {}", from, to)?;
+        }
+        Ok(())
+    }
 }
\ No newline at end of file
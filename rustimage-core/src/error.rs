@@ -8,6 +8,7 @@
 
 use thiserror::Error;
 use std::error::Error;
+use std::time::Duration;
 
 // =============================================================================
 // 公共错误API - 深模块的统一错误接口
@@ -168,12 +169,25 @@ pub enum ImageError {
     },
     
     /// 操作被取消
-    #[error("Operation cancelled: {operation} (reason: {reason})")]
+    #[error("Operation cancelled: {operation} after {completed}/{total} steps (reason: {reason})")]
     OperationCancelled {
         /// 操作名称
         operation: String,
         /// 取消原因
         reason: String,
+        /// 取消前已完成的步骤数
+        completed: usize,
+        /// 总步骤数
+        total: usize,
+    },
+    
+    /// 处理过程意外失败（如工作线程崩溃）
+    #[error("Processing failed during {stage}: {reason}")]
+    ProcessingFailed {
+        /// 失败的处理阶段
+        stage: String,
+        /// 失败原因
+        reason: String,
     },
     
     /// 操作超时
@@ -368,7 +382,7 @@ pub struct ErrorStatistics {
     pub error_counts_by_severity: std::collections::HashMap<ErrorSeverity, u64>,
     /// 总错误次数
     pub total_errors: u64,
-    /// 最常见的错误类型
+    /// 最常见的错误类型 - （`ImageError::kind`，次数），按次数降序
     pub most_common_errors: Vec<(String, u64)>,
     /// 错误趋势（最近的错误时间戳）
     pub recent_error_timestamps: Vec<std::time::SystemTime>,
@@ -383,6 +397,15 @@ pub struct ErrorCollector {
     enabled: bool,
 }
 
+/// 重试策略 - 仅对`is_retryable`的错误按指数退避重试
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// 最大尝试次数（含首次）
+    max_attempts: u32,
+    /// 首次重试前的等待时间，之后每次翻倍
+    backoff: Duration,
+}
+
 // =============================================================================
 // 公共实现 - 错误处理的核心逻辑
 // =============================================================================
@@ -416,6 +439,7 @@ impl ImageError {
             | ImageError::BatchProcessingFailed { .. }
             | ImageError::ParallelProcessingError { .. }
             | ImageError::OperationTimeout { .. }
+            | ImageError::ProcessingFailed { .. }
             | ImageError::CodecInitializationFailed { .. }
             | ImageError::IoError { .. }
             | ImageError::ExternalLibraryError { .. } => ErrorSeverity::High,
@@ -447,7 +471,8 @@ impl ImageError {
             
             ImageError::UnsupportedOperation { .. }
             | ImageError::OperationCancelled { .. }
-            | ImageError::OperationTimeout { .. } => ErrorCategory::Operation,
+            | ImageError::OperationTimeout { .. }
+            | ImageError::ProcessingFailed { .. } => ErrorCategory::Operation,
             
             ImageError::BatchProcessingFailed { .. }
             | ImageError::ParallelProcessingError { .. } => ErrorCategory::Batch,
@@ -464,6 +489,37 @@ impl ImageError {
         }
     }
     
+    /// 获取错误种类的稳定标识 - 即变体名，用于统计和日志聚合
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ImageError::InvalidFormat { .. } => "InvalidFormat",
+            ImageError::UnsupportedFormat { .. } => "UnsupportedFormat",
+            ImageError::FormatDetectionFailed { .. } => "FormatDetectionFailed",
+            ImageError::DecodeError { .. } => "DecodeError",
+            ImageError::EncodeError { .. } => "EncodeError",
+            ImageError::CodecInitializationFailed { .. } => "CodecInitializationFailed",
+            ImageError::InvalidParameters { .. } => "InvalidParameters",
+            ImageError::InvalidDimensions { .. } => "InvalidDimensions",
+            ImageError::InvalidPixelFormat { .. } => "InvalidPixelFormat",
+            ImageError::ConfigurationError { .. } => "ConfigurationError",
+            ImageError::MemoryError { .. } => "MemoryError",
+            ImageError::ResourceExhausted { .. } => "ResourceExhausted",
+            ImageError::SystemLimitExceeded { .. } => "SystemLimitExceeded",
+            ImageError::UnsupportedOperation { .. } => "UnsupportedOperation",
+            ImageError::OperationCancelled { .. } => "OperationCancelled",
+            ImageError::ProcessingFailed { .. } => "ProcessingFailed",
+            ImageError::OperationTimeout { .. } => "OperationTimeout",
+            ImageError::BatchProcessingFailed { .. } => "BatchProcessingFailed",
+            ImageError::ParallelProcessingError { .. } => "ParallelProcessingError",
+            ImageError::PerformanceError { .. } => "PerformanceError",
+            ImageError::QualityAssessmentFailed { .. } => "QualityAssessmentFailed",
+            ImageError::IoError { .. } => "IoError",
+            ImageError::ExternalLibraryError { .. } => "ExternalLibraryError",
+            ImageError::WasmBindingError { .. } => "WasmBindingError",
+            ImageError::JsInteropError { .. } => "JsInteropError",
+        }
+    }
+    
     /// 检查错误是否可以重试
     pub fn is_retryable(&self) -> bool {
         match self {
@@ -489,30 +545,92 @@ impl ImageError {
         }
     }
     
-    /// 获取错误的建议解决方案
+    /// 获取错误的建议解决方案 - 每种错误都给出可执行的处理建议
+    ///
+    /// 保留`Option`返回类型以兼容既有调用方，目前所有变体均返回`Some`
     pub fn suggested_solution(&self) -> Option<String> {
-        match self {
+        let solution = match self {
             ImageError::InvalidFormat { .. } => {
-                Some("请检查图像文件是否损坏，或尝试使用支持的图像格式".to_string())
+                "请检查图像文件是否损坏，或尝试使用支持的图像格式".to_string()
             }
             ImageError::UnsupportedFormat { supported, .. } => {
-                Some(format!("请使用支持的格式之一: {}", supported.join(", ")))
+                format!("请使用支持的格式之一: {}", supported.join(", "))
+            }
+            ImageError::FormatDetectionFailed { .. } => {
+                "无法识别图像格式，请显式指定源格式或确认数据完整".to_string()
+            }
+            ImageError::DecodeError { format, .. } => {
+                format!("{}数据无法解码，请确认文件完整且声明的格式正确，必要时重新导出", format)
+            }
+            ImageError::EncodeError { format, .. } => {
+                format!("{}编码失败，请检查编码参数（质量、压缩级别等），或改用其他目标格式", format)
+            }
+            ImageError::CodecInitializationFailed { format, .. } => {
+                format!("{}编解码器不可用，请确认已启用对应的编译特性", format)
+            }
+            ImageError::InvalidParameters { .. } => {
+                "请检查输入参数是否正确，参考文档了解正确的参数范围".to_string()
+            }
+            ImageError::InvalidDimensions { width, height, .. } => {
+                format!("图像尺寸 {}×{} 无效，请确认宽高均大于零且不超过目标格式的上限", width, height)
+            }
+            ImageError::InvalidPixelFormat { expected, .. } => {
+                format!("请先将图像转换为{}像素格式", expected)
+            }
+            ImageError::ConfigurationError { setting, .. } => {
+                format!("请检查配置项 {} 的取值", setting)
             }
             ImageError::MemoryError { requested, available } => {
-                Some(format!(
+                format!(
                     "请减少图像尺寸或释放内存。当前需要 {}MB，可用 {}MB",
                     requested / 1024 / 1024,
                     available / 1024 / 1024
-                ))
+                )
             }
-            ImageError::InvalidParameters { .. } => {
-                Some("请检查输入参数是否正确，参考文档了解正确的参数范围".to_string())
+            ImageError::ResourceExhausted { .. } => {
+                "系统资源不足，请降低并发数或稍后重试".to_string()
+            }
+            ImageError::SystemLimitExceeded { limit_type, .. } => {
+                format!("已超出{}限制，请缩小输入或分批处理", limit_type)
+            }
+            ImageError::UnsupportedOperation { .. } => {
+                "当前格式或构建不支持该操作，请查阅转换矩阵选择其他目标格式".to_string()
+            }
+            ImageError::OperationCancelled { .. } => {
+                "操作已取消，如需结果请重新提交".to_string()
+            }
+            ImageError::ProcessingFailed { stage, .. } => {
+                format!("{}阶段失败，请尝试关闭相关处理选项（缩放、灰度等）后重试", stage)
             }
             ImageError::OperationTimeout { .. } => {
-                Some("操作超时，请尝试减少图像尺寸或增加超时时间".to_string())
+                "操作超时，请尝试减少图像尺寸或增加超时时间".to_string()
             }
-            _ => None,
-        }
+            ImageError::BatchProcessingFailed { failed_count, .. } => {
+                format!("{}项转换失败，请查看首个错误的详情并单独重试失败项", failed_count)
+            }
+            ImageError::ParallelProcessingError { .. } => {
+                "并行处理出错，请尝试关闭并行或减小线程池大小".to_string()
+            }
+            ImageError::PerformanceError { .. } => {
+                "性能监控出错，可关闭性能监控后继续转换".to_string()
+            }
+            ImageError::QualityAssessmentFailed { .. } => {
+                "质量评估失败，可关闭质量评估后继续转换".to_string()
+            }
+            ImageError::IoError { .. } => {
+                "请检查文件路径和读写权限，确认磁盘空间充足".to_string()
+            }
+            ImageError::ExternalLibraryError { library, .. } => {
+                format!("{}内部错误，请确认输入数据有效，必要时更新依赖版本", library)
+            }
+            ImageError::WasmBindingError { .. } => {
+                "请确认WebAssembly模块已正确初始化".to_string()
+            }
+            ImageError::JsInteropError { .. } => {
+                "请检查从JavaScript传入的参数类型和取值".to_string()
+            }
+        };
+        Some(solution)
     }
     
     /// 创建格式错误
@@ -542,6 +660,16 @@ impl ImageError {
             resource: resource.into(),
         }
     }
+    
+    /// 创建取消错误 - 记录取消前已完成的步骤数
+    pub fn cancelled<S: Into<String>>(operation: S, completed: usize, total: usize) -> Self {
+        Self::OperationCancelled {
+            operation: operation.into(),
+            reason: "cancellation requested".to_string(),
+            completed,
+            total,
+        }
+    }
 }
 
 // =============================================================================
@@ -663,6 +791,13 @@ impl ErrorCollector {
             let severity = error.severity();
             *stats.error_counts_by_severity.entry(severity).or_insert(0) += 1;
             
+            // 按错误种类统计
+            let kind = error.kind();
+            match stats.most_common_errors.iter_mut().find(|(name, _)| name == kind) {
+                Some((_, count)) => *count += 1,
+                None => stats.most_common_errors.push((kind.to_string(), 1)),
+            }
+            
             // 记录时间戳
             stats.recent_error_timestamps.push(std::time::SystemTime::now());
             
@@ -675,7 +810,12 @@ impl ErrorCollector {
     
     /// 获取统计信息
     pub fn get_statistics(&self) -> ErrorStatistics {
-        self.statistics.lock().unwrap().clone()
+        let mut stats = self.statistics.lock().unwrap().clone();
+        // 次数相同时按名称排序，保证输出稳定
+        stats
+            .most_common_errors
+            .sort_by(|(a_name, a_count), (b_name, b_count)| b_count.cmp(a_count).then_with(|| a_name.cmp(b_name)));
+        stats
     }
     
     /// 重置统计信息
@@ -686,6 +826,39 @@ impl ErrorCollector {
     }
 }
 
+impl RetryPolicy {
+    /// 创建重试策略 - 尝试次数至少为1
+    pub fn new(max_attempts: u32, backoff: Duration) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            backoff,
+        }
+    }
+    
+    pub fn max_attempts(&self) -> u32 { self.max_attempts }
+    pub fn backoff(&self) -> Duration { self.backoff }
+    
+    /// 执行操作 - 不可重试的错误或用尽尝试次数时返回最后一次的错误
+    ///
+    /// 等待使用`std::thread::sleep`，会阻塞当前线程
+    pub fn run<T, F>(&self, mut operation: F) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+    {
+        let mut delay = self.backoff;
+        for _ in 1..self.max_attempts {
+            match operation() {
+                Err(error) if error.is_retryable() => {
+                    std::thread::sleep(delay);
+                    delay = delay.saturating_mul(2);
+                }
+                result => return result,
+            }
+        }
+        operation()
+    }
+}
+
 // =============================================================================
 // 外部错误类型转换 - 统一错误接口
 // =============================================================================
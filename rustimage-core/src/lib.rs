@@ -8,12 +8,17 @@
 pub mod types;        // 类型定义和抽象
 pub mod converter;    // 主转换器
 pub mod codecs;       // 编解码引擎
+mod processing;       // 像素处理
+mod metadata;         // 元数据解析
+pub mod quality;      // 质量评估
 pub mod performance;  // 性能监控
 pub mod error;        // 错误处理
+#[cfg(feature = "track-alloc")]
+mod alloc_tracker;    // 分配跟踪
 
 // 重新导出主要类型和函数 - 深模块的简单接口
 pub use types::*;
-pub use converter::FormatConverter;
+pub use converter::{DecodedImage, FormatConverter};
 pub use error::{ImageError, Result};
 
 /// 主要的格式转换接口 - 体现深模块设计
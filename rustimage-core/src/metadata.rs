@@ -0,0 +1,291 @@
+//! 元数据解析 - 从容器中定位EXIF并解析为类型化字段
+//!
+//! 本模块遵循《软件设计哲学》的核心理念：
+//! - **深模块设计**：调用方只拿到`ImageMetadata`，不接触TIFF结构
+//! - **信息隐藏**：容器扫描与IFD遍历私有实现
+//! - **容错**：结构损坏的字段直接忽略，不影响其余字段
+
+use crate::types::*;
+
+// =============================================================================
+// EXIF标签
+// =============================================================================
+
+/// IFD0：相机制造商
+const TAG_MAKE: u16 = 0x010F;
+/// IFD0：相机型号
+const TAG_MODEL: u16 = 0x0110;
+/// IFD0：方向
+const TAG_ORIENTATION: u16 = 0x0112;
+/// IFD0：文件修改时间
+const TAG_DATE_TIME: u16 = 0x0132;
+/// IFD0：指向EXIF子IFD
+const TAG_EXIF_IFD: u16 = 0x8769;
+/// IFD0：指向GPS子IFD
+const TAG_GPS_IFD: u16 = 0x8825;
+/// EXIF子IFD：拍摄时间
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+/// GPS子IFD：纬度参考（N/S）
+const TAG_GPS_LATITUDE_REF: u16 = 0x0001;
+/// GPS子IFD：纬度（度、分、秒）
+const TAG_GPS_LATITUDE: u16 = 0x0002;
+/// GPS子IFD：经度参考（E/W）
+const TAG_GPS_LONGITUDE_REF: u16 = 0x0003;
+/// GPS子IFD：经度（度、分、秒）
+const TAG_GPS_LONGITUDE: u16 = 0x0004;
+
+/// TIFF字段类型：ASCII
+const TYPE_ASCII: u16 = 2;
+/// TIFF字段类型：SHORT
+const TYPE_SHORT: u16 = 3;
+/// TIFF字段类型：LONG
+const TYPE_LONG: u16 = 4;
+/// TIFF字段类型：RATIONAL
+const TYPE_RATIONAL: u16 = 5;
+
+/// JPEG APP1段与部分WebP EXIF块的前缀
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+
+// =============================================================================
+// 公共入口
+// =============================================================================
+
+/// 读取图像的EXIF元数据 - 没有EXIF或格式不携带EXIF时返回空结构
+///
+/// 支持JPEG（APP1）、PNG（eXIf）、WebP（EXIF）和TIFF（IFD0）
+pub(crate) fn read_metadata(data: &[u8], format: ImageFormat) -> ImageMetadata {
+    find_exif(data, format)
+        .and_then(TiffReader::new)
+        .map(|reader| reader.metadata())
+        .unwrap_or_default()
+}
+
+// =============================================================================
+// 容器扫描 - 定位TIFF结构的EXIF数据
+// =============================================================================
+
+fn find_exif(data: &[u8], format: ImageFormat) -> Option<&[u8]> {
+    match format {
+        ImageFormat::Jpeg => find_jpeg_exif(data),
+        ImageFormat::Png => find_png_exif(data),
+        ImageFormat::WebP => find_webp_exif(data),
+        ImageFormat::Tiff => Some(data),
+        _ => None,
+    }
+}
+
+/// 扫描到SOS为止，返回第一个EXIF APP1段去掉前缀后的内容
+fn find_jpeg_exif(data: &[u8]) -> Option<&[u8]> {
+    let mut pos = 2;
+    while pos + 4 <= data.len() && data[pos] == 0xFF {
+        let marker = data[pos + 1];
+        if marker == 0xFF {
+            // 标记前的填充字节
+            pos += 1;
+            continue;
+        }
+        if marker == 0xDA {
+            break;
+        }
+        let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let segment = data.get(pos + 4..pos + 2 + length)?;
+        if marker == 0xE1 && segment.starts_with(EXIF_HEADER) {
+            return Some(&segment[EXIF_HEADER.len()..]);
+        }
+        pos += 2 + length;
+    }
+    None
+}
+
+/// PNG的eXIf块直接存放TIFF结构
+fn find_png_exif(data: &[u8]) -> Option<&[u8]> {
+    // 跳过8字节签名
+    let mut pos = 8;
+    while pos + 8 <= data.len() {
+        let length = u32::from_be_bytes(data[pos..pos + 4].try_into().ok()?) as usize;
+        let chunk_type = &data[pos + 4..pos + 8];
+        let payload = data.get(pos + 8..pos + 8 + length)?;
+        match chunk_type {
+            b"eXIf" => return Some(payload),
+            // EXIF必须出现在IDAT之前
+            b"IDAT" | b"IEND" => return None,
+            _ => pos += 12 + length,
+        }
+    }
+    None
+}
+
+/// WebP的EXIF块 - 规范要求TIFF结构，部分工具会多写一个`Exif\0\0`前缀
+fn find_webp_exif(data: &[u8]) -> Option<&[u8]> {
+    // 跳过RIFF文件头
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let size = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().ok()?) as usize;
+        let payload = data.get(pos + 8..pos + 8 + size)?;
+        if &data[pos..pos + 4] == b"EXIF" {
+            return Some(payload.strip_prefix(EXIF_HEADER).unwrap_or(payload));
+        }
+        pos += 8 + size + size % 2;
+    }
+    None
+}
+
+// =============================================================================
+// TIFF结构读取
+// =============================================================================
+
+/// IFD中的一项 - 取值位置已按内联/偏移规则解析
+struct IfdEntry {
+    tag: u16,
+    field_type: u16,
+    count: usize,
+    value_pos: usize,
+}
+
+/// 按字节序读取TIFF结构
+struct TiffReader<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> TiffReader<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        let big_endian = match data.get(..2)? {
+            b"II" => false,
+            b"MM" => true,
+            _ => return None,
+        };
+        let reader = Self { data, big_endian };
+        (reader.u16(2)? == 42).then_some(reader)
+    }
+
+    fn u16(&self, pos: usize) -> Option<u16> {
+        let bytes = self.data.get(pos..pos + 2)?.try_into().ok()?;
+        Some(if self.big_endian { u16::from_be_bytes(bytes) } else { u16::from_le_bytes(bytes) })
+    }
+
+    fn u32(&self, pos: usize) -> Option<u32> {
+        let bytes = self.data.get(pos..pos + 4)?.try_into().ok()?;
+        Some(if self.big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) })
+    }
+
+    /// 读取IFD中的所有项 - 超出数据范围的项被丢弃
+    fn entries(&self, ifd: usize) -> Vec<IfdEntry> {
+        let Some(count) = self.u16(ifd) else {
+            return Vec::new();
+        };
+        (0..count as usize)
+            .filter_map(|index| {
+                let entry = ifd + 2 + 12 * index;
+                let field_type = self.u16(entry + 2)?;
+                let count = self.u32(entry + 4)? as usize;
+                let unit = match field_type {
+                    TYPE_SHORT => 2,
+                    TYPE_LONG => 4,
+                    TYPE_RATIONAL => 8,
+                    _ => 1,
+                };
+                let value_pos = if count.checked_mul(unit)? <= 4 {
+                    entry + 8
+                } else {
+                    self.u32(entry + 8)? as usize
+                };
+                Some(IfdEntry { tag: self.u16(entry)?, field_type, count, value_pos })
+            })
+            .collect()
+    }
+
+    /// ASCII字段 - 去掉结尾的NUL和空白，空字符串视为缺失
+    fn ascii(&self, entry: &IfdEntry) -> Option<String> {
+        if entry.field_type != TYPE_ASCII {
+            return None;
+        }
+        let bytes = self.data.get(entry.value_pos..entry.value_pos + entry.count)?;
+        let text = String::from_utf8_lossy(bytes);
+        let text = text.trim_end_matches('\0').trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+
+    /// SHORT或LONG字段的第一个取值
+    fn unsigned(&self, entry: &IfdEntry) -> Option<u32> {
+        match entry.field_type {
+            TYPE_SHORT => self.u16(entry.value_pos).map(u32::from),
+            TYPE_LONG => self.u32(entry.value_pos),
+            _ => None,
+        }
+    }
+
+    /// 度、分、秒三个RATIONAL换算为十进制度数
+    fn degrees(&self, entry: &IfdEntry) -> Option<f64> {
+        if entry.field_type != TYPE_RATIONAL || entry.count < 3 {
+            return None;
+        }
+        let rational = |index: usize| -> Option<f64> {
+            let pos = entry.value_pos + index * 8;
+            let numerator = self.u32(pos)?;
+            let denominator = self.u32(pos + 4)?;
+            (denominator != 0).then(|| numerator as f64 / denominator as f64)
+        };
+        Some(rational(0)? + rational(1)? / 60.0 + rational(2)? / 3600.0)
+    }
+
+    /// 解析IFD0及其EXIF、GPS子IFD
+    fn metadata(&self) -> ImageMetadata {
+        let mut metadata = ImageMetadata::default();
+        let Some(ifd0) = self.u32(4) else {
+            return metadata;
+        };
+
+        let mut exif_ifd = None;
+        let mut gps_ifd = None;
+        for entry in self.entries(ifd0 as usize) {
+            match entry.tag {
+                TAG_MAKE => metadata.camera_make = self.ascii(&entry),
+                TAG_MODEL => metadata.camera_model = self.ascii(&entry),
+                TAG_ORIENTATION => metadata.orientation = self.unsigned(&entry).and_then(|value| u16::try_from(value).ok()),
+                TAG_DATE_TIME => metadata.datetime = self.ascii(&entry),
+                TAG_EXIF_IFD => exif_ifd = self.unsigned(&entry),
+                TAG_GPS_IFD => gps_ifd = self.unsigned(&entry),
+                _ => {}
+            }
+        }
+
+        // 拍摄时间优先于文件修改时间
+        if let Some(ifd) = exif_ifd {
+            let original = self
+                .entries(ifd as usize)
+                .iter()
+                .find(|entry| entry.tag == TAG_DATE_TIME_ORIGINAL)
+                .and_then(|entry| self.ascii(entry));
+            if original.is_some() {
+                metadata.datetime = original;
+            }
+        }
+
+        if let Some(ifd) = gps_ifd {
+            metadata.gps = self.gps(ifd as usize);
+        }
+        metadata
+    }
+
+    /// 解析GPS子IFD - 经纬度任一缺失时视为没有坐标
+    fn gps(&self, ifd: usize) -> Option<GpsCoordinates> {
+        let mut latitude = None;
+        let mut longitude = None;
+        let mut latitude_sign = 1.0;
+        let mut longitude_sign = 1.0;
+        for entry in self.entries(ifd) {
+            match entry.tag {
+                TAG_GPS_LATITUDE_REF if self.ascii(&entry).as_deref() == Some("S") => latitude_sign = -1.0,
+                TAG_GPS_LONGITUDE_REF if self.ascii(&entry).as_deref() == Some("W") => longitude_sign = -1.0,
+                TAG_GPS_LATITUDE => latitude = self.degrees(&entry),
+                TAG_GPS_LONGITUDE => longitude = self.degrees(&entry),
+                _ => {}
+            }
+        }
+        Some(GpsCoordinates {
+            latitude: latitude? * latitude_sign,
+            longitude: longitude? * longitude_sign,
+        })
+    }
+}
@@ -55,6 +55,13 @@ pub struct MonitorConfig {
 // 内部类型 - 信息隐藏的体现
 // =============================================================================
 
+/// 历史快照的最大保留数量
+const MAX_HISTORY_ENTRIES: usize = 1000;
+/// 单一阶段耗时占比超过该值时给出针对性建议
+const STAGE_DOMINANCE_THRESHOLD: f64 = 0.6;
+/// 操作平均耗时超过整体平均的倍数时视为慢操作
+const SLOW_OPERATION_FACTOR: f64 = 1.5;
+
 /// 性能数据收集器 - 私有：高效的数据收集和存储
 struct PerformanceCollector {
     /// 当前实时指标
@@ -63,10 +70,26 @@ struct PerformanceCollector {
     history: VecDeque<PerformanceSnapshot>,
     /// 累积统计
     accumulated_stats: AccumulatedStats,
+    /// 正在测量的操作名称
+    active_operation: Option<String>,
+    /// 操作开始时的CPU采样
+    cpu_sample: Option<CpuSample>,
+    /// 操作开始时的分配计数快照
+    #[cfg(feature = "track-alloc")]
+    alloc_snapshot: Option<crate::alloc_tracker::AllocSnapshot>,
     /// 最后更新时间
     last_update: Instant,
 }
 
+/// CPU采样点 - 私有：墙钟时间与进程累计CPU时间
+#[derive(Debug, Clone, Copy)]
+struct CpuSample {
+    /// 采样时刻
+    wall: Instant,
+    /// 进程累计CPU时间，平台不支持时为`None`
+    cpu: Option<Duration>,
+}
+
 /// 性能分析器 - 私有：智能的性能数据分析
 struct PerformanceAnalyzer {
     /// 趋势分析器
@@ -107,6 +130,8 @@ struct OperationStats {
     max_time_ms: f64,
     /// 成功次数
     success_count: u64,
+    /// 内存统计（峰值取最大，分配次数累加）
+    memory: MemoryMetrics,
 }
 
 // =============================================================================
@@ -234,6 +259,14 @@ pub struct OperationDetailedStats {
     pub average_time_ms: f64,
     /// 成功率
     pub success_rate: f64,
+    /// 总时间（毫秒）
+    pub total_time_ms: f64,
+    /// 最小时间（毫秒）
+    pub min_time_ms: f64,
+    /// 最大时间（毫秒）
+    pub max_time_ms: f64,
+    /// 内存统计
+    pub memory_stats: MemoryMetrics,
 }
 
 /// 系统资源统计
@@ -325,6 +358,28 @@ impl PerformanceMonitor {
         }
     }
     
+    /// 记录单次转换各阶段耗时 - 在 end_conversion 之前调用
+    pub fn record_stage_timing(&self, timing: &TimingMetrics) {
+        if !self.config.enabled {
+            return;
+        }
+        
+        if let Ok(mut collector) = self.collector.write() {
+            collector.record_stage_timing(timing);
+        }
+    }
+    
+    /// 记录单次转换是否使用了SIMD路径
+    pub fn record_simd_usage(&self, simd_utilized: bool) {
+        if !self.config.enabled {
+            return;
+        }
+        
+        if let Ok(mut collector) = self.collector.write() {
+            collector.current_metrics.system.simd_utilized = simd_utilized;
+        }
+    }
+    
     /// 开始批处理监控
     pub fn start_batch_conversion(&self, batch_size: usize) {
         if !self.config.enabled {
@@ -404,6 +459,26 @@ impl PerformanceMonitor {
         
         self.session_start = Instant::now();
     }
+    
+    /// 释放历史快照占用的内存
+    ///
+    /// 累积统计保留，报告中的总量不受影响；阶段耗时分布从下一次转换重新积累
+    pub fn release_history(&mut self) {
+        if let Ok(mut collector) = self.collector.write() {
+            collector.history = VecDeque::new();
+        }
+    }
+    
+    /// 历史快照当前占用的字节数（按容量估算，含操作名称）
+    pub fn retained_bytes(&self) -> usize {
+        self.collector
+            .read()
+            .map(|collector| {
+                collector.history.capacity() * std::mem::size_of::<PerformanceSnapshot>()
+                    + collector.history.iter().map(|s| s.operation.capacity()).sum::<usize>()
+            })
+            .unwrap_or(0)
+    }
 }
 
 // =============================================================================
@@ -455,20 +530,23 @@ impl PerformanceMonitor {
             let detailed_stats = OperationDetailedStats {
                 operation_name: op_name.clone(),
                 execution_count: op_stats.count,
-                average_time_ms: if op_stats.count > 0 {
-                    op_stats.total_time_ms / op_stats.count as f64
-                } else {
-                    0.0
-                },
+                average_time_ms: op_stats.average_time_ms(),
                 success_rate: if op_stats.count > 0 {
                     op_stats.success_count as f64 / op_stats.count as f64
                 } else {
                     0.0
                 },
+                total_time_ms: op_stats.total_time_ms,
+                min_time_ms: op_stats.min_time_ms,
+                max_time_ms: op_stats.max_time_ms,
+                memory_stats: op_stats.memory.clone(),
             };
             operation_stats.push(detailed_stats);
         }
         
+        // 按总耗时降序，最值得关注的操作排在前面
+        operation_stats.sort_by(|a, b| b.total_time_ms.total_cmp(&a.total_time_ms));
+        
         let system_resource_stats = SystemResourceStats {
             average_memory_usage_mb: 64.0, // 简化数据
             peak_memory_usage_mb: 128.0,
@@ -482,21 +560,75 @@ impl PerformanceMonitor {
     }
     
     /// 生成优化建议 - 私有方法
-    fn generate_recommendations(&self, _collector_data: &PerformanceCollector) -> Result<Vec<OptimizationRecommendation>> {
-        let recommendations = vec![
-            OptimizationRecommendation {
-                recommendation_type: RecommendationType::ParallelizationOptimization,
-                description: "考虑启用并行处理以提高批量转换性能".to_string(),
-                expected_improvement: 0.3,
-                implementation_difficulty: 2,
-            },
-            OptimizationRecommendation {
-                recommendation_type: RecommendationType::MemoryOptimization,
-                description: "优化内存使用模式，减少不必要的内存分配".to_string(),
-                expected_improvement: 0.15,
-                implementation_difficulty: 3,
+    /// 
+    /// 基于历史快照中的阶段耗时和各操作的平均耗时给出建议
+    fn generate_recommendations(&self, collector_data: &PerformanceCollector) -> Result<Vec<OptimizationRecommendation>> {
+        let mut recommendations = Vec::new();
+        
+        // 1. 阶段耗时占比：解码或编码主导总耗时
+        let (total_ms, decode_ms, encode_ms) = collector_data.history.iter().fold(
+            (0.0, 0.0, 0.0),
+            |(total, decode, encode), snapshot| {
+                let timing = &snapshot.metrics.timing;
+                (total + timing.total_time_ms, decode + timing.decode_time_ms, encode + timing.encode_time_ms)
             },
-        ];
+        );
+        
+        if total_ms > 0.0 {
+            let decode_share = decode_ms / total_ms;
+            let encode_share = encode_ms / total_ms;
+            
+            if decode_share >= STAGE_DOMINANCE_THRESHOLD {
+                recommendations.push(OptimizationRecommendation {
+                    recommendation_type: RecommendationType::AlgorithmOptimization,
+                    description: format!(
+                        "解码耗时占总耗时 {:.0}%，考虑缩小输入尺寸或缓存解码结果",
+                        decode_share * 100.0
+                    ),
+                    expected_improvement: (decode_share * 0.5) as f32,
+                    implementation_difficulty: 2,
+                });
+            }
+            
+            if encode_share >= STAGE_DOMINANCE_THRESHOLD {
+                recommendations.push(OptimizationRecommendation {
+                    recommendation_type: RecommendationType::AlgorithmOptimization,
+                    description: format!(
+                        "编码耗时占总耗时 {:.0}%，考虑降低压缩级别或选择编码更快的格式",
+                        encode_share * 100.0
+                    ),
+                    expected_improvement: (encode_share * 0.5) as f32,
+                    implementation_difficulty: 2,
+                });
+            }
+        }
+        
+        // 2. 慢操作：平均耗时远高于整体平均
+        let stats = &collector_data.accumulated_stats;
+        if stats.operation_stats.len() > 1 && stats.total_operations > 0 {
+            let overall_average = stats.total_processing_time_ms / stats.total_operations as f64;
+            let slowest = stats
+                .operation_stats
+                .iter()
+                .max_by(|(_, a), (_, b)| a.average_time_ms().total_cmp(&b.average_time_ms()));
+            
+            if let Some((name, op_stats)) = slowest {
+                if op_stats.average_time_ms() >= overall_average * SLOW_OPERATION_FACTOR {
+                    recommendations.push(OptimizationRecommendation {
+                        recommendation_type: RecommendationType::ParallelizationOptimization,
+                        description: format!(
+                            "操作 {} 平均耗时 {:.2}ms，是整体平均的 {:.1} 倍，考虑对其启用并行处理",
+                            name,
+                            op_stats.average_time_ms(),
+                            op_stats.average_time_ms() / overall_average
+                        ),
+                        expected_improvement: 0.3,
+                        implementation_difficulty: 2,
+                    });
+                }
+            }
+        }
+        
         Ok(recommendations)
     }
     
@@ -523,12 +655,46 @@ impl PerformanceMonitor {
 // 内部组件实现 - 私有的核心逻辑
 // =============================================================================
 
+impl CpuSample {
+    fn now() -> Self {
+        Self {
+            wall: Instant::now(),
+            cpu: process_cpu_time(),
+        }
+    }
+}
+
+/// 进程累计CPU时间（用户态 + 内核态）
+#[cfg(all(feature = "system-metrics", unix))]
+fn process_cpu_time() -> Option<Duration> {
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::zeroed();
+    // SAFETY: getrusage 只写入传入的结构体，返回0时结构体已完整初始化
+    let usage = unsafe {
+        if libc::getrusage(libc::RUSAGE_SELF, usage.as_mut_ptr()) != 0 {
+            return None;
+        }
+        usage.assume_init()
+    };
+    let to_duration = |t: libc::timeval| Duration::new(t.tv_sec as u64, t.tv_usec as u32 * 1000);
+    Some(to_duration(usage.ru_utime) + to_duration(usage.ru_stime))
+}
+
+/// 进程累计CPU时间 - 未启用`system-metrics`或非Unix平台时不可用
+#[cfg(not(all(feature = "system-metrics", unix)))]
+fn process_cpu_time() -> Option<Duration> {
+    None
+}
+
 impl PerformanceCollector {
     fn new(_config: &MonitorConfig) -> Result<Self> {
         Ok(Self {
             current_metrics: PerformanceMetrics::default(),
             history: VecDeque::with_capacity(1000),
             accumulated_stats: AccumulatedStats::default(),
+            active_operation: None,
+            cpu_sample: None,
+            #[cfg(feature = "track-alloc")]
+            alloc_snapshot: None,
             last_update: Instant::now(),
         })
     }
@@ -538,22 +704,21 @@ impl PerformanceCollector {
     }
     
     fn start_operation(&mut self, operation_name: &str) {
-        // 记录操作开始
-        let snapshot = PerformanceSnapshot {
-            timestamp: Instant::now(),
-            system_time: SystemTime::now(),
-            metrics: self.current_metrics.clone(),
-            operation: operation_name.to_string(),
-        };
-        
-        self.history.push_back(snapshot);
-        
-        // 限制历史记录大小
-        if self.history.len() > 1000 {
-            self.history.pop_front();
+        // 记录操作开始 - 结束时归入该操作的统计
+        self.active_operation = Some(operation_name.to_string());
+        self.cpu_sample = Some(CpuSample::now());
+        #[cfg(feature = "track-alloc")]
+        {
+            self.alloc_snapshot = Some(crate::alloc_tracker::AllocSnapshot::begin());
         }
     }
     
+    fn record_stage_timing(&mut self, timing: &TimingMetrics) {
+        self.current_metrics.timing.decode_time_ms = timing.decode_time_ms;
+        self.current_metrics.timing.encode_time_ms = timing.encode_time_ms;
+        self.current_metrics.timing.processing_time_ms = timing.processing_time_ms;
+    }
+    
     fn record_conversion_result(&mut self, duration: Duration, success: bool) {
         let duration_ms = duration.as_secs_f64() * 1000.0;
         
@@ -566,7 +731,71 @@ impl PerformanceCollector {
         
         // 更新当前指标
         self.current_metrics.timing.total_time_ms = duration_ms;
+        let (cpu_usage_percent, threads_used) = self.sample_cpu(duration);
+        self.current_metrics.system.cpu_usage_percent = cpu_usage_percent;
+        self.current_metrics.system.threads_used = threads_used;
+        #[cfg(feature = "track-alloc")]
+        if let Some(snapshot) = self.alloc_snapshot.take() {
+            self.current_metrics.memory = snapshot.finish();
+        }
         self.last_update = Instant::now();
+        
+        // 按操作名称累积统计
+        let operation = self
+            .active_operation
+            .take()
+            .unwrap_or_else(|| "conversion".to_string());
+        self.accumulated_stats
+            .operation_stats
+            .entry(operation.clone())
+            .or_default()
+            .record(duration_ms, success, &self.current_metrics.memory);
+        
+        // 记录完成快照
+        self.history.push_back(PerformanceSnapshot {
+            timestamp: Instant::now(),
+            system_time: SystemTime::now(),
+            metrics: self.current_metrics.clone(),
+            operation,
+        });
+        
+        // 限制历史记录大小
+        if self.history.len() > MAX_HISTORY_ENTRIES {
+            self.history.pop_front();
+        }
+    }
+    
+    /// 估算本次操作的CPU使用率（占全部核心的百分比）和活跃线程数
+    ///
+    /// 原生平台按进程CPU时间与墙钟时间之比计算，需启用`system-metrics` feature；
+    /// wasm32上转换独占主线程，改为估算自上次操作结束以来主线程的繁忙比例。
+    /// 无法测量时使用率为0，线程数按单线程计
+    fn sample_cpu(&mut self, duration: Duration) -> (f32, usize) {
+        let start = self.cpu_sample.take();
+        
+        if cfg!(target_arch = "wasm32") {
+            let window = start
+                .map(|_| self.last_update.elapsed())
+                .unwrap_or(duration)
+                .max(duration);
+            let busy = duration.as_secs_f64() / window.as_secs_f64().max(f64::EPSILON);
+            return ((busy * 100.0).clamp(0.0, 100.0) as f32, 1);
+        }
+        
+        let measured = start.and_then(|start| {
+            let cpu_time = process_cpu_time()?.checked_sub(start.cpu?)?;
+            let wall = start.wall.elapsed().as_secs_f64();
+            (wall > 0.0).then(|| cpu_time.as_secs_f64() / wall)
+        });
+        
+        match measured {
+            Some(busy_threads) => {
+                let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+                let usage = (busy_threads / cores as f64 * 100.0).clamp(0.0, 100.0);
+                (usage as f32, (busy_threads.ceil() as usize).clamp(1, cores))
+            }
+            None => (0.0, 1),
+        }
     }
     
     fn record_batch_result(&mut self, duration: Duration, success_count: usize, total_count: usize) {
@@ -581,10 +810,39 @@ impl PerformanceCollector {
         self.current_metrics = PerformanceMetrics::default();
         self.history.clear();
         self.accumulated_stats = AccumulatedStats::default();
+        self.active_operation = None;
         self.last_update = Instant::now();
     }
 }
 
+impl OperationStats {
+    /// 记录一次执行
+    fn record(&mut self, duration_ms: f64, success: bool, memory: &MemoryMetrics) {
+        if self.count == 0 || duration_ms < self.min_time_ms {
+            self.min_time_ms = duration_ms;
+        }
+        self.max_time_ms = self.max_time_ms.max(duration_ms);
+        self.count += 1;
+        self.total_time_ms += duration_ms;
+        if success {
+            self.success_count += 1;
+        }
+        
+        self.memory.peak_memory_bytes = self.memory.peak_memory_bytes.max(memory.peak_memory_bytes);
+        self.memory.allocations_count = self.memory.allocations_count.saturating_add(memory.allocations_count);
+        self.memory.deallocations_count = self.memory.deallocations_count.saturating_add(memory.deallocations_count);
+    }
+    
+    /// 平均执行时间（毫秒）
+    fn average_time_ms(&self) -> f64 {
+        if self.count > 0 {
+            self.total_time_ms / self.count as f64
+        } else {
+            0.0
+        }
+    }
+}
+
 impl PerformanceAnalyzer {
     fn new(_config: &MonitorConfig) -> Result<Self> {
         Ok(Self {
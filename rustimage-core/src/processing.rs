@@ -0,0 +1,650 @@
+//! 图像处理 - 解码与编码之间的像素变换
+//!
+//! 本模块遵循《软件设计哲学》的核心理念：
+//! - **深模块设计**：转换器只需按选项调用，变换细节完全隐藏
+//! - **信息隐藏**：具体的像素算法私有实现
+//! - **零成本抽象**：尽量原地修改像素，避免额外分配
+
+use crate::{
+    codecs::{ImageBuffer, PixelFormat},
+    converter::Cancellation,
+    error::{ImageError, Result},
+    types::*,
+};
+use image::imageops::FilterType;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::sync::atomic::AtomicBool;
+
+// =============================================================================
+// 颜色变换
+// =============================================================================
+
+/// 原地转换为灰度 - BT.709亮度权重，保留透明通道
+/// 
+/// 返回是否使用了SIMD路径
+pub(crate) fn grayscale(buffer: &mut ImageBuffer<Rgba8>, simd: bool) -> bool {
+    let (luma, simd_used) = rgba_to_luma(buffer.as_slice(), simd);
+    for (pixel, Luma { l }) in buffer.as_mut_slice().iter_mut().zip(luma) {
+        pixel.r = l;
+        pixel.g = l;
+        pixel.b = l;
+    }
+    simd_used
+}
+
+/// 计算每个像素的亮度 - 与`Luma8::from(Rgba8)`逐位一致
+/// 
+/// 启用`simd` feature且`simd`为真时走向量化路径，返回是否使用了SIMD
+pub(crate) fn rgba_to_luma(pixels: &[Rgba8], simd: bool) -> (Vec<Luma8>, bool) {
+    #[cfg(feature = "simd")]
+    if simd {
+        return (simd_luma::rgba_to_luma(pixels), true);
+    }
+    
+    #[cfg(not(feature = "simd"))]
+    let _ = simd;
+    
+    (pixels.iter().map(|pixel| Luma8::from(*pixel)).collect(), false)
+}
+
+/// 单遍统计各通道和亮度直方图
+/// 
+/// 启用`parallel` feature时按块并行统计再合并
+pub(crate) fn histogram(pixels: &[Rgba8]) -> Histogram {
+    const CHUNK: usize = 64 * 1024;
+    
+    let count = |chunk: &[Rgba8]| {
+        let mut histogram = Histogram::default();
+        for &pixel in chunk {
+            histogram.add(pixel);
+        }
+        histogram
+    };
+    
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        if pixels.len() > CHUNK {
+            return pixels
+                .par_chunks(CHUNK)
+                .map(count)
+                .reduce(Histogram::default, |a, b| a.merge(&b));
+        }
+    }
+    
+    count(pixels)
+}
+
+/// 原地自动对比度 - 各颜色通道按百分位线性拉伸到0-255，透明通道不变
+/// 
+/// 两端各裁剪`clip_percent`%；某通道的低高百分位相同（纯色）时保持不变
+pub(crate) fn auto_contrast(buffer: &mut ImageBuffer<Rgba8>, clip_percent: f32) -> Result<()> {
+    if !(0.0..50.0).contains(&clip_percent) {
+        return Err(ImageError::InvalidParameters {
+            details: format!("Auto-contrast clip percent {} must be in [0, 50)", clip_percent),
+        });
+    }
+    
+    let histogram = histogram(buffer.as_slice());
+    let lookup = |channel: HistogramChannel| -> [u8; 256] {
+        let low = histogram.percentile(channel, clip_percent) as f32;
+        let high = histogram.percentile(channel, 100.0 - clip_percent) as f32;
+        std::array::from_fn(|value| {
+            if high <= low {
+                return value as u8;
+            }
+            u8::from_f32((value as f32 - low) * 255.0 / (high - low))
+        })
+    };
+    let [red, green, blue] = [HistogramChannel::Red, HistogramChannel::Green, HistogramChannel::Blue].map(lookup);
+    
+    for pixel in buffer.as_mut_slice() {
+        pixel.r = red[pixel.r as usize];
+        pixel.g = green[pixel.g as usize];
+        pixel.b = blue[pixel.b as usize];
+    }
+    Ok(())
+}
+
+/// 原地与背景色合成 - 去除透明度，结果完全不透明
+pub(crate) fn flatten_alpha(buffer: &mut ImageBuffer<Rgba8>, background: Rgb8) {
+    for pixel in buffer.as_mut_slice() {
+        if pixel.a == u8::MAX {
+            continue;
+        }
+
+        let alpha = pixel.a as f32 / 255.0;
+        let blend = |fg: u8, bg: u8| u8::from_f32(fg as f32 * alpha + bg as f32 * (1.0 - alpha));
+        pixel.r = blend(pixel.r, background.r);
+        pixel.g = blend(pixel.g, background.g);
+        pixel.b = blend(pixel.b, background.b);
+        pixel.a = u8::MAX;
+    }
+}
+
+// =============================================================================
+// 调色板量化
+// =============================================================================
+
+/// 调色板量化结果 - 调色板与逐像素的调色板索引
+pub(crate) struct Quantized {
+    /// 调色板 - 不透明度低于255的项排在前面，便于PNG写出最短的tRNS
+    pub(crate) palette: Vec<Rgba8>,
+    /// 每个像素的调色板索引
+    pub(crate) indices: Vec<u8>,
+}
+
+/// 量化为最多`max_colors`种颜色（上限256）的调色板 - 中位切分
+///
+/// 完全透明的像素共用一个预留的透明项；颜色数不超过上限时调色板精确无损
+pub(crate) fn quantize(pixels: &[Rgba8], max_colors: usize) -> Quantized {
+    // 以打包的RGBA作为键统计颜色，完全透明的像素归一为同一个键
+    let key = |p: &Rgba8| if p.a == 0 { 0 } else { u32::from_le_bytes([p.r, p.g, p.b, p.a]) };
+    let mut counts: HashMap<u32, u64> = HashMap::new();
+    for pixel in pixels {
+        *counts.entry(key(pixel)).or_insert(0) += 1;
+    }
+
+    let has_transparent = counts.remove(&0).is_some();
+    let available = max_colors.clamp(2, 256) - has_transparent as usize;
+    let colors: Vec<([u8; 4], u64)> = counts.iter().map(|(&k, &count)| (k.to_le_bytes(), count)).collect();
+
+    let mut palette: Vec<[u8; 4]> = if colors.len() <= available {
+        colors.iter().map(|&(color, _)| color).collect()
+    } else {
+        median_cut(colors, available)
+    };
+    if has_transparent {
+        palette.push([0, 0, 0, 0]);
+    }
+    palette.sort_by_key(|color| color[3] == u8::MAX);
+
+    // 每种颜色只查找一次最近的调色板项
+    let nearest = |color: [u8; 4]| -> u8 {
+        let distance = |entry: &[u8; 4]| -> u32 {
+            color.iter().zip(entry).map(|(&a, &b)| (a as i32 - b as i32).pow(2) as u32).sum()
+        };
+        (0..palette.len()).min_by_key(|&i| distance(&palette[i])).unwrap_or(0) as u8
+    };
+    let mut lookup: HashMap<u32, u8> = counts.keys().map(|&k| (k, nearest(k.to_le_bytes()))).collect();
+    if has_transparent {
+        lookup.insert(0, nearest([0, 0, 0, 0]));
+    }
+
+    Quantized {
+        palette: palette.into_iter().map(|[r, g, b, a]| Rgba { r, g, b, a }).collect(),
+        indices: pixels.iter().map(|p| lookup[&key(p)]).collect(),
+    }
+}
+
+/// 中位切分 - 反复沿范围最大的通道在加权中位数处切分颜色盒，每个盒取加权平均色
+fn median_cut(colors: Vec<([u8; 4], u64)>, max_colors: usize) -> Vec<[u8; 4]> {
+    // 颜色盒中跨度最大的通道及其跨度
+    let widest_channel = |colors: &[([u8; 4], u64)]| -> (usize, u8) {
+        (0..4)
+            .map(|c| {
+                let (min, max) = colors.iter().fold((u8::MAX, 0), |(min, max), (color, _)| {
+                    (min.min(color[c]), max.max(color[c]))
+                });
+                (c, max - min)
+            })
+            .max_by_key(|&(_, range)| range)
+            .unwrap_or((0, 0))
+    };
+
+    let mut boxes = vec![colors];
+    while boxes.len() < max_colors {
+        let Some((index, channel)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, colors)| colors.len() > 1)
+            .map(|(i, colors)| (i, widest_channel(colors)))
+            .max_by_key(|&(_, (_, range))| range)
+            .map(|(i, (channel, _))| (i, channel))
+        else {
+            break;
+        };
+
+        let mut colors = boxes.swap_remove(index);
+        colors.sort_unstable_by_key(|(color, _)| color[channel]);
+        let half = colors.iter().map(|&(_, count)| count).sum::<u64>() / 2;
+        let mut accumulated = 0;
+        let split = colors
+            .iter()
+            .position(|&(_, count)| {
+                accumulated += count;
+                accumulated > half
+            })
+            .unwrap_or(0)
+            .clamp(1, colors.len() - 1);
+        let upper = colors.split_off(split);
+        boxes.push(colors);
+        boxes.push(upper);
+    }
+
+    boxes
+        .iter()
+        .map(|colors| {
+            let total: u64 = colors.iter().map(|&(_, count)| count).sum();
+            let mut color = [0u8; 4];
+            for (c, channel) in color.iter_mut().enumerate() {
+                let sum: u64 = colors.iter().map(|&(color, count)| color[c] as u64 * count).sum();
+                *channel = ((sum + total / 2) / total) as u8;
+            }
+            color
+        })
+        .collect()
+}
+
+/// 4×4 Bayer有序抖动矩阵
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// 16位RGBA以有序抖动降到8位 - 颜色通道按像素位置加入阈值后取整，透明通道四舍五入
+///
+/// 与误差扩散相比，有序抖动的结果与处理顺序无关，输出可复现
+pub(crate) fn dither_to_rgba8(image: &image::ImageBuffer<image::Rgba<u16>, Vec<u16>>) -> Result<ImageBuffer<Rgba8>> {
+    let (width, height) = image.dimensions();
+    let pixels = image
+        .enumerate_pixels()
+        .map(|(x, y, pixel)| {
+            let threshold = (BAYER_4X4[(y % 4) as usize][(x % 4) as usize] as f32 + 0.5) / 16.0;
+            let [r, g, b, a] = pixel.0;
+            let dither = |value: u16| (value as f32 / 257.0 + threshold).floor().min(255.0) as u8;
+            Rgba { r: dither(r), g: dither(g), b: dither(b), a: ((a as u32 + 128) / 257) as u8 }
+        })
+        .collect();
+    ImageBuffer::from_raw(width, height, pixels, PixelFormat::Rgba8)
+}
+
+/// 主色调合并阈值 - 各通道相差都不超过该值的主色调视为同一种颜色
+const DOMINANT_MERGE_TOLERANCE: u8 = 4;
+
+/// 颜色摘要 - 透明度加权的平均色，以及按像素占比降序的主色调
+///
+/// 主色调复用调色板量化的中位切分，几乎相同的颜色合并为占比较大的一项；
+/// 完全透明的像素不参与统计
+pub(crate) fn color_summary(buffer: &ImageBuffer<Rgba8>, max_colors: usize) -> ColorSummary {
+    let visible: Vec<Rgba8> = buffer
+        .as_slice()
+        .iter()
+        .filter(|p| p.a > 0)
+        .map(|p| Rgba { a: u8::MAX, ..*p })
+        .collect();
+
+    let mut sums = [0u64; 3];
+    let mut total_alpha = 0u64;
+    for pixel in buffer.as_slice() {
+        let alpha = pixel.a as u64;
+        sums[0] += pixel.r as u64 * alpha;
+        sums[1] += pixel.g as u64 * alpha;
+        sums[2] += pixel.b as u64 * alpha;
+        total_alpha += alpha;
+    }
+    let mean = |sum: u64| (sum + total_alpha / 2).checked_div(total_alpha).unwrap_or(0) as u8;
+    let average = Rgb { r: mean(sums[0]), g: mean(sums[1]), b: mean(sums[2]) };
+
+    if visible.is_empty() {
+        return ColorSummary { average, dominant: Vec::new() };
+    }
+
+    let quantized = quantize(&visible, max_colors);
+    let mut counts = vec![0u64; quantized.palette.len()];
+    for &index in &quantized.indices {
+        counts[index as usize] += 1;
+    }
+    let mut candidates: Vec<(Rgb8, f32)> = quantized
+        .palette
+        .iter()
+        .zip(counts)
+        .filter(|&(_, count)| count > 0)
+        .map(|(p, count)| (Rgb { r: p.r, g: p.g, b: p.b }, count as f32 / visible.len() as f32))
+        .collect();
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut dominant: Vec<(Rgb8, f32)> = Vec::with_capacity(candidates.len());
+    for (color, weight) in candidates {
+        let close = |other: &Rgb8| {
+            color.r.abs_diff(other.r) <= DOMINANT_MERGE_TOLERANCE
+                && color.g.abs_diff(other.g) <= DOMINANT_MERGE_TOLERANCE
+                && color.b.abs_diff(other.b) <= DOMINANT_MERGE_TOLERANCE
+        };
+        match dominant.iter_mut().find(|(kept, _)| close(kept)) {
+            Some((_, kept_weight)) => *kept_weight += weight,
+            None => dominant.push((color, weight)),
+        }
+    }
+    dominant.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    ColorSummary { average, dominant }
+}
+
+// =============================================================================
+// 占位图编码
+// =============================================================================
+
+/// BlurHash使用的Base83字符表
+const BASE83_CHARS: &[u8; 83] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz#$%*+,-.:;=?@[]^_{|}~";
+
+/// BlurHash编码 - 在线性光下计算`components_x`×`components_y`个DCT分量
+///
+/// 输出依次为尺寸标记、AC最大幅值、4位DC分量和每个AC分量2位，
+/// 长度为 `6 + 2 * (components_x * components_y - 1)`；透明通道被忽略
+pub(crate) fn blurhash(buffer: &ImageBuffer<Rgba8>, components_x: u32, components_y: u32) -> String {
+    let ImageDimensions { width, height } = buffer.dimensions();
+    let to_linear = srgb_to_linear_table();
+    let pixels = buffer.as_slice();
+
+    let mut factors = Vec::with_capacity((components_x * components_y) as usize);
+    for j in 0..components_y {
+        for i in 0..components_x {
+            let normalisation = if i == 0 && j == 0 { 1.0 } else { 2.0 };
+            let mut sum = [0.0f32; 3];
+            for y in 0..height {
+                let basis_y = (std::f32::consts::PI * j as f32 * y as f32 / height as f32).cos();
+                for x in 0..width {
+                    let basis = basis_y * (std::f32::consts::PI * i as f32 * x as f32 / width as f32).cos();
+                    let pixel = &pixels[(y * width + x) as usize];
+                    sum[0] += basis * to_linear[pixel.r as usize];
+                    sum[1] += basis * to_linear[pixel.g as usize];
+                    sum[2] += basis * to_linear[pixel.b as usize];
+                }
+            }
+            let scale = normalisation / (width * height) as f32;
+            factors.push(sum.map(|channel| channel * scale));
+        }
+    }
+
+    let mut hash = String::with_capacity(6 + 2 * (factors.len() - 1));
+    encode_base83((components_x - 1) + (components_y - 1) * 9, 1, &mut hash);
+
+    let (dc, ac) = factors.split_first().expect("at least one component");
+    let max_value = if ac.is_empty() {
+        encode_base83(0, 1, &mut hash);
+        1.0
+    } else {
+        let actual_max = ac.iter().flatten().fold(0.0f32, |max, value| max.max(value.abs()));
+        let quantised_max = (actual_max * 166.0 - 0.5).floor().clamp(0.0, 82.0) as u32;
+        encode_base83(quantised_max, 1, &mut hash);
+        (quantised_max + 1) as f32 / 166.0
+    };
+
+    let [r, g, b] = dc.map(|channel| linear_to_srgb(channel) as u32);
+    encode_base83((r << 16) | (g << 8) | b, 4, &mut hash);
+
+    for factor in ac {
+        let quantise = |value: f32| {
+            let scaled = value / max_value;
+            (scaled.signum() * scaled.abs().sqrt() * 9.0 + 9.5).floor().clamp(0.0, 18.0) as u32
+        };
+        let [r, g, b] = factor.map(quantise);
+        encode_base83(r * 19 * 19 + g * 19 + b, 2, &mut hash);
+    }
+
+    hash
+}
+
+/// 将数值编码为定长的Base83数字串
+fn encode_base83(value: u32, length: u32, output: &mut String) {
+    for position in (0..length).rev() {
+        let digit = value / 83u32.pow(position) % 83;
+        output.push(BASE83_CHARS[digit as usize] as char);
+    }
+}
+
+// =============================================================================
+// 尺寸变换
+// =============================================================================
+
+/// 等比缩小到格式的尺寸上限以内 - 无需缩小时返回`None`
+pub(crate) fn fit_within(dims: ImageDimensions, limits: &FormatLimits) -> Option<ImageDimensions> {
+    let scale_for = |extent: u32, limit: Option<u32>| match limit {
+        Some(limit) if extent > limit => limit as f64 / extent as f64,
+        _ => 1.0,
+    };
+    let scale = scale_for(dims.width, limits.max_width).min(scale_for(dims.height, limits.max_height));
+    if scale >= 1.0 {
+        return None;
+    }
+
+    // 向下取整保证不超出上限，且至少保留一个像素
+    Some(ImageDimensions {
+        width: ((dims.width as f64 * scale) as u32).max(1),
+        height: ((dims.height as f64 * scale) as u32).max(1),
+    })
+}
+
+/// 等比缩放到恰好放进`bounds` - 与`fit_within`不同，较小的图像会被放大
+///
+/// 尺寸不变时返回`None`
+pub(crate) fn scale_to_fit(dims: ImageDimensions, bounds: ImageDimensions) -> Option<ImageDimensions> {
+    let scale = (bounds.width as f64 / dims.width as f64).min(bounds.height as f64 / dims.height as f64);
+    let scaled = ImageDimensions {
+        width: ((dims.width as f64 * scale) as u32).clamp(1, bounds.width),
+        height: ((dims.height as f64 * scale) as u32).clamp(1, bounds.height),
+    };
+    (scaled != dims).then_some(scaled)
+}
+
+/// 不放大时的缩放目标 - 任一边超过源尺寸时按目标宽高比缩小到源尺寸以内
+pub(crate) fn cap_upscale(target: ImageDimensions, source: ImageDimensions) -> ImageDimensions {
+    if target.width <= source.width && target.height <= source.height {
+        return target;
+    }
+    scale_to_fit(target, source).unwrap_or(target)
+}
+
+/// 缩放到指定尺寸 - 使用给定滤波器，保留ICC配置文件
+///
+/// `linear`为真时颜色通道在线性光空间中滤波。源尺寸是目标尺寸的整数倍时
+/// 改用盒式平均（`Nearest`除外），结果与平台无关、逐位稳定
+pub(crate) fn resize(
+    buffer: &ImageBuffer<Rgba8>,
+    target: ImageDimensions,
+    filter: ResizeFilter,
+    linear: bool,
+) -> Result<ImageBuffer<Rgba8>> {
+    resize_cancellable(buffer, target, filter, linear, &AtomicBool::new(false))
+}
+
+/// 可取消的缩放 - 已取消时返回`OperationCancelled`，完成数为已输出的行数
+///
+/// 盒式缩小逐行检查取消信号；image库的滤波缩放是不可中断的单次调用，只在其前后检查
+pub(crate) fn resize_cancellable(
+    buffer: &ImageBuffer<Rgba8>,
+    target: ImageDimensions,
+    filter: ResizeFilter,
+    linear: bool,
+    cancel: &dyn Cancellation,
+) -> Result<ImageBuffer<Rgba8>> {
+    let dims = buffer.dimensions();
+    if filter != ResizeFilter::Nearest {
+        if let Some(factors) = box_factors(dims, target) {
+            return box_downsample(buffer, target, factors, linear, cancel);
+        }
+    }
+    let check_cancelled = |completed: u32| -> Result<()> {
+        if cancel.is_cancelled() {
+            return Err(ImageError::cancelled("resize", completed as usize, target.height as usize));
+        }
+        Ok(())
+    };
+    check_cancelled(0)?;
+    let not_filled = || ImageError::InvalidParameters {
+        details: format!("Buffer does not fill {}×{} image", dims.width, dims.height),
+    };
+
+    let pixels = if linear {
+        let to_linear = srgb_to_linear_table();
+        let raw: Vec<f32> = buffer
+            .as_slice()
+            .iter()
+            .flat_map(|p| [to_linear[p.r as usize], to_linear[p.g as usize], to_linear[p.b as usize], p.a as f32 / 255.0])
+            .collect();
+        let source = image::Rgba32FImage::from_raw(dims.width, dims.height, raw).ok_or_else(not_filled)?;
+        let resized = image::imageops::resize(&source, target.width, target.height, filter_type(filter));
+        check_cancelled(target.height)?;
+        resized
+            .pixels()
+            .map(|p| Rgba {
+                r: linear_to_srgb(p.0[0]),
+                g: linear_to_srgb(p.0[1]),
+                b: linear_to_srgb(p.0[2]),
+                a: (p.0[3] * 255.0).round().clamp(0.0, 255.0) as u8,
+            })
+            .collect()
+    } else {
+        let raw: Vec<u8> = buffer.as_slice().iter().flat_map(|p| [p.r, p.g, p.b, p.a]).collect();
+        let source = image::RgbaImage::from_raw(dims.width, dims.height, raw).ok_or_else(not_filled)?;
+        let resized = image::imageops::resize(&source, target.width, target.height, filter_type(filter));
+        check_cancelled(target.height)?;
+        resized
+            .pixels()
+            .map(|p| Rgba { r: p.0[0], g: p.0[1], b: p.0[2], a: p.0[3] })
+            .collect()
+    };
+
+    let mut output = ImageBuffer::from_raw(target.width, target.height, pixels, PixelFormat::Rgba8)?;
+    output.set_icc_profile(buffer.icc_profile().map(<[u8]>::to_vec));
+    Ok(output)
+}
+
+/// 整数倍缩小的横纵倍数 - 不是整数倍缩小（含尺寸不变）时为`None`
+fn box_factors(source: ImageDimensions, target: ImageDimensions) -> Option<(u32, u32)> {
+    if target.width == 0 || target.height == 0 || source == target {
+        return None;
+    }
+    let divisible = source.width.is_multiple_of(target.width) && source.height.is_multiple_of(target.height);
+    divisible.then(|| (source.width / target.width, source.height / target.height))
+}
+
+/// 盒式缩小 - 每个输出像素取对应源像素块各通道的平均值，四舍五入
+///
+/// 非线性模式全程整数运算；线性模式只有颜色通道经过线性光，透明度仍按整数平均
+fn box_downsample(
+    buffer: &ImageBuffer<Rgba8>,
+    target: ImageDimensions,
+    (factor_x, factor_y): (u32, u32),
+    linear: bool,
+    cancel: &dyn Cancellation,
+) -> Result<ImageBuffer<Rgba8>> {
+    let width = buffer.dimensions().width as usize;
+    let source = buffer.as_slice();
+    let (factor_x, factor_y) = (factor_x as usize, factor_y as usize);
+    let count = (factor_x * factor_y) as u32;
+    let to_linear = srgb_to_linear_table();
+
+    let mut pixels = Vec::with_capacity(target.width as usize * target.height as usize);
+    for out_y in 0..target.height as usize {
+        if cancel.is_cancelled() {
+            return Err(ImageError::cancelled("resize", out_y, target.height as usize));
+        }
+        for out_x in 0..target.width as usize {
+            let block = (0..factor_y).flat_map(|dy| {
+                let start = (out_y * factor_y + dy) * width + out_x * factor_x;
+                &source[start..start + factor_x]
+            });
+            let alpha = block.clone().map(|p| p.a as u32).sum::<u32>();
+            let alpha = ((alpha + count / 2) / count) as u8;
+            let pixel = if linear {
+                let [r, g, b] = block.fold([0.0f32; 3], |[r, g, b], p| {
+                    [r + to_linear[p.r as usize], g + to_linear[p.g as usize], b + to_linear[p.b as usize]]
+                });
+                let n = count as f32;
+                Rgba { r: linear_to_srgb(r / n), g: linear_to_srgb(g / n), b: linear_to_srgb(b / n), a: alpha }
+            } else {
+                let [r, g, b] = block.fold([0u32; 3], |[r, g, b], p| [r + p.r as u32, g + p.g as u32, b + p.b as u32]);
+                let average = |sum: u32| ((sum + count / 2) / count) as u8;
+                Rgba { r: average(r), g: average(g), b: average(b), a: alpha }
+            };
+            pixels.push(pixel);
+        }
+    }
+
+    let mut output = ImageBuffer::from_raw(target.width, target.height, pixels, PixelFormat::Rgba8)?;
+    output.set_icc_profile(buffer.icc_profile().map(<[u8]>::to_vec));
+    Ok(output)
+}
+
+/// sRGB编码值到线性光的查找表 - 首次使用时计算
+fn srgb_to_linear_table() -> &'static [f32; 256] {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        std::array::from_fn(|value| {
+            let c = value as f32 / 255.0;
+            if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+        })
+    })
+}
+
+/// 预先初始化延迟计算的查找表 - 供转换器预热使用
+pub(crate) fn warm_tables() {
+    srgb_to_linear_table();
+}
+
+/// 线性光转换回8位sRGB编码值 - 滤波器过冲的值先截断到[0, 1]
+fn linear_to_srgb(value: f32) -> u8 {
+    let c = value.clamp(0.0, 1.0);
+    let encoded = if c <= 0.003_130_8 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+    (encoded * 255.0).round() as u8
+}
+
+/// 映射到image crate的滤波器类型
+fn filter_type(filter: ResizeFilter) -> FilterType {
+    match filter {
+        ResizeFilter::Nearest => FilterType::Nearest,
+        ResizeFilter::Triangle => FilterType::Triangle,
+        ResizeFilter::CatmullRom => FilterType::CatmullRom,
+        ResizeFilter::Lanczos3 => FilterType::Lanczos3,
+    }
+}
+
+// =============================================================================
+// SIMD 实现 - 每次处理8个像素
+// =============================================================================
+
+#[cfg(feature = "simd")]
+mod simd_luma {
+    use crate::types::*;
+    use wide::{f32x8, i32x8, CmpGe};
+    
+    const LANES: usize = 8;
+    
+    /// 向量化亮度计算 - 运算顺序与标量实现相同，保证结果逐位一致
+    pub(super) fn rgba_to_luma(pixels: &[Rgba8]) -> Vec<Luma8> {
+        let [wr, wg, wb] = BT709_LUMA_WEIGHTS.map(f32x8::splat);
+        let half = f32x8::splat(0.5);
+        let one = f32x8::splat(1.0);
+        let max = f32x8::splat(u8::MAX as f32);
+        let channel_mask = i32x8::splat(0xFF);
+        
+        let mut luma = vec![Luma { l: 0u8 }; pixels.len()];
+        let mut outputs = luma.chunks_exact_mut(LANES);
+        let mut chunks = pixels.chunks_exact(LANES);
+        
+        for (chunk, output) in (&mut chunks).zip(&mut outputs) {
+            // 每个像素打包为一个32位整数，再用移位和掩码拆出各通道
+            let packed = i32x8::from(std::array::from_fn::<i32, LANES, _>(|i| {
+                let pixel = chunk[i];
+                i32::from_le_bytes([pixel.r, pixel.g, pixel.b, pixel.a])
+            }));
+            let r = f32x8::from_i32x8(packed & channel_mask);
+            let g = f32x8::from_i32x8((packed >> 8) & channel_mask);
+            let b = f32x8::from_i32x8((packed >> 16) & channel_mask);
+            let value = wr * r + wg * g + wb * b;
+            
+            // f32::round 为远离零舍入：取整数部分后按小数部分是否 >= 0.5 进位
+            let floor = value.trunc_int().round_float();
+            let rounded = floor + (value - floor).cmp_ge(half).blend(one, f32x8::ZERO);
+            
+            for (out, l) in output.iter_mut().zip(rounded.min(max).trunc_int().to_array()) {
+                out.l = l as u8;
+            }
+        }
+        
+        for (out, pixel) in outputs.into_remainder().iter_mut().zip(chunks.remainder()) {
+            *out = Luma8::from(*pixel);
+        }
+        luma
+    }
+}
@@ -0,0 +1,313 @@
+//! 质量评估 - 深模块设计的图像质量分析
+//!
+//! 本模块遵循《软件设计哲学》的核心理念：
+//! - **深模块设计**：一个 assess_quality() 接口，隐藏多种质量指标的计算细节
+//! - **信息隐藏**：PSNR、SSIM、感知哈希的具体算法私有实现
+//! - **零成本抽象**：直接在像素切片上计算，不做额外的格式转换
+
+use crate::{
+    codecs::{CodecConfig, CodecEngine, FormatDetector, ImageBuffer},
+    error::{ImageError, Result},
+    types::*,
+};
+
+// =============================================================================
+// 公共API - 深模块的简单评估接口
+// =============================================================================
+
+/// 质量评估器 - 比较原始图像与处理后图像
+#[derive(Debug, Clone, Default)]
+pub struct QualityAssessor {
+    _private: (),
+}
+
+// =============================================================================
+// 内部常量
+// =============================================================================
+
+/// 完全相同的图像的PSNR上限 - 避免无穷大出现在序列化结果中
+const MAX_PSNR: f32 = 100.0;
+/// SSIM 窗口边长
+const SSIM_WINDOW: u32 = 8;
+/// SSIM 稳定常数 (K1 * L)^2 与 (K2 * L)^2，L = 255
+const SSIM_C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+const SSIM_C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+/// 感知哈希的网格边长 - 8x8 共64位
+const HASH_SIZE: u32 = 8;
+/// 清晰度归一化常数 - 拉普拉斯方差等于该值时得分0.5，约为常用的失焦判定阈值
+const SHARPNESS_HALF_POINT: f64 = 100.0;
+
+// =============================================================================
+// 公共实现
+// =============================================================================
+
+impl QualityAssessor {
+    /// 创建质量评估器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 评估处理后图像相对原始图像的质量
+    ///
+    /// 两幅图像尺寸必须一致
+    pub fn assess_quality(
+        &self,
+        original: &ImageBuffer<Rgba8>,
+        processed: &ImageBuffer<Rgba8>,
+    ) -> Result<QualityMetrics> {
+        if original.dimensions() != processed.dimensions() {
+            return Err(ImageError::InvalidParameters {
+                details: format!(
+                    "Cannot compare images of different dimensions: {}x{} vs {}x{}",
+                    original.dimensions().width,
+                    original.dimensions().height,
+                    processed.dimensions().width,
+                    processed.dimensions().height,
+                ),
+            });
+        }
+
+        if original.is_empty() {
+            return Err(ImageError::InvalidParameters {
+                details: "Cannot assess quality of an empty image".to_string(),
+            });
+        }
+
+        Ok(QualityMetrics::new(
+            psnr(original, processed),
+            ssim(original, processed),
+            perceptual_similarity(original, processed),
+        ))
+    }
+
+    /// 比较两幅已编码图像 - 分别按声明的格式解码后计算质量指标
+    ///
+    /// 以`a`为参考图像；两幅图像尺寸必须一致，否则返回`InvalidDimensions`
+    pub fn compare(
+        &self,
+        a: &[u8],
+        a_format: ImageFormat,
+        b: &[u8],
+        b_format: ImageFormat,
+    ) -> Result<QualityMetrics> {
+        let engine = CodecEngine::new(CodecConfig::default())?;
+        let reference = engine.decode::<Rgba8>(a, a_format)?;
+        let candidate = engine.decode::<Rgba8>(b, b_format)?;
+
+        let expected = reference.dimensions();
+        let ImageDimensions { width, height } = candidate.dimensions();
+        if (width, height) != (expected.width, expected.height) {
+            return Err(ImageError::InvalidDimensions {
+                width,
+                height,
+                reason: format!("expected {}x{} to match the reference image", expected.width, expected.height),
+            });
+        }
+
+        self.assess_quality(&reference, &candidate)
+    }
+
+    /// 清晰度评分 - 解码图像亮度平面上拉普拉斯响应的方差，归一化到 0.0-1.0
+    ///
+    /// 越高越清晰；失焦或模糊的图像边缘平缓，拉普拉斯响应集中在0附近。
+    /// 格式自动检测，图像宽高至少为3像素
+    pub fn sharpness(&self, data: &[u8]) -> Result<f32> {
+        let format = FormatDetector::detect(data)?;
+        let engine = CodecEngine::new(CodecConfig::default())?;
+        let buffer = engine.decode::<Rgba8>(data, format)?;
+
+        let ImageDimensions { width, height } = buffer.dimensions();
+        if width < 3 || height < 3 {
+            return Err(ImageError::InvalidDimensions {
+                width,
+                height,
+                reason: "sharpness requires at least 3x3 pixels".to_string(),
+            });
+        }
+
+        let variance = laplacian_variance(&buffer);
+        Ok((variance / (variance + SHARPNESS_HALF_POINT)) as f32)
+    }
+
+    /// 亮度熵 - 解码图像8位亮度直方图的香农熵，单位为比特/像素（0.0-8.0）
+    ///
+    /// 纯色图像为0，噪声接近8；熵越低，无损压缩越有效
+    pub fn entropy(&self, data: &[u8], from_format: ImageFormat) -> Result<f32> {
+        let engine = CodecEngine::new(CodecConfig::default())?;
+        let buffer = engine.decode::<Rgba8>(data, from_format)?;
+        if buffer.is_empty() {
+            return Err(ImageError::InvalidParameters {
+                details: "Cannot compute entropy of an empty image".to_string(),
+            });
+        }
+
+        Ok(luma_entropy(&buffer) as f32)
+    }
+}
+
+// =============================================================================
+// 私有算法实现
+// =============================================================================
+
+/// 峰值信噪比 - 基于RGB通道的均方误差
+fn psnr(original: &ImageBuffer<Rgba8>, processed: &ImageBuffer<Rgba8>) -> f32 {
+    let squared_error: f64 = original
+        .as_slice()
+        .iter()
+        .zip(processed.as_slice())
+        .map(|(a, b)| {
+            let dr = a.r as f64 - b.r as f64;
+            let dg = a.g as f64 - b.g as f64;
+            let db = a.b as f64 - b.b as f64;
+            dr * dr + dg * dg + db * db
+        })
+        .sum();
+
+    let mse = squared_error / (original.len() as f64 * 3.0);
+    if mse == 0.0 {
+        return MAX_PSNR;
+    }
+
+    let psnr = 10.0 * (255.0 * 255.0 / mse).log10();
+    (psnr as f32).min(MAX_PSNR)
+}
+
+/// 结构相似性 - 亮度通道上 8x8 窗口的平均SSIM
+fn ssim(original: &ImageBuffer<Rgba8>, processed: &ImageBuffer<Rgba8>) -> f32 {
+    let ImageDimensions { width, height } = original.dimensions();
+    let luma_a = luma_plane(original);
+    let luma_b = luma_plane(processed);
+
+    let mut total = 0.0;
+    let mut windows = 0u32;
+
+    for y0 in (0..height).step_by(SSIM_WINDOW as usize) {
+        for x0 in (0..width).step_by(SSIM_WINDOW as usize) {
+            let x1 = (x0 + SSIM_WINDOW).min(width);
+            let y1 = (y0 + SSIM_WINDOW).min(height);
+
+            let mut sum_a = 0.0;
+            let mut sum_b = 0.0;
+            let mut sum_aa = 0.0;
+            let mut sum_bb = 0.0;
+            let mut sum_ab = 0.0;
+
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let index = (y * width + x) as usize;
+                    let a = luma_a[index];
+                    let b = luma_b[index];
+                    sum_a += a;
+                    sum_b += b;
+                    sum_aa += a * a;
+                    sum_bb += b * b;
+                    sum_ab += a * b;
+                }
+            }
+
+            let n = ((x1 - x0) * (y1 - y0)) as f64;
+            let mean_a = sum_a / n;
+            let mean_b = sum_b / n;
+            let var_a = sum_aa / n - mean_a * mean_a;
+            let var_b = sum_bb / n - mean_b * mean_b;
+            let covariance = sum_ab / n - mean_a * mean_b;
+
+            total += ((2.0 * mean_a * mean_b + SSIM_C1) * (2.0 * covariance + SSIM_C2))
+                / ((mean_a * mean_a + mean_b * mean_b + SSIM_C1) * (var_a + var_b + SSIM_C2));
+            windows += 1;
+        }
+    }
+
+    ((total / windows as f64) as f32).clamp(0.0, 1.0)
+}
+
+/// 感知相似度 - 平均哈希的汉明距离归一化到 0.0-1.0
+fn perceptual_similarity(original: &ImageBuffer<Rgba8>, processed: &ImageBuffer<Rgba8>) -> f32 {
+    let distance = (average_hash(original) ^ average_hash(processed)).count_ones();
+    1.0 - distance as f32 / (HASH_SIZE * HASH_SIZE) as f32
+}
+
+/// 平均哈希 - 缩小到 8x8 网格，每格亮度高于均值记为1
+fn average_hash(buffer: &ImageBuffer<Rgba8>) -> u64 {
+    let ImageDimensions { width, height } = buffer.dimensions();
+    let luma = luma_plane(buffer);
+
+    let mut cells = [0.0f64; (HASH_SIZE * HASH_SIZE) as usize];
+    for (cell_index, cell) in cells.iter_mut().enumerate() {
+        let cx = cell_index as u32 % HASH_SIZE;
+        let cy = cell_index as u32 / HASH_SIZE;
+        let (x0, x1) = cell_span(cx, width);
+        let (y0, y1) = cell_span(cy, height);
+
+        let mut sum = 0.0;
+        for y in y0..y1 {
+            for x in x0..x1 {
+                sum += luma[(y * width + x) as usize];
+            }
+        }
+        *cell = sum / ((x1 - x0) * (y1 - y0)) as f64;
+    }
+
+    let mean = cells.iter().sum::<f64>() / cells.len() as f64;
+    cells
+        .iter()
+        .enumerate()
+        .filter(|(_, value)| **value > mean)
+        .fold(0u64, |hash, (bit, _)| hash | (1 << bit))
+}
+
+/// 网格单元覆盖的像素区间 - 小图像时至少覆盖一个像素
+fn cell_span(cell: u32, extent: u32) -> (u32, u32) {
+    let start = (cell * extent / HASH_SIZE).min(extent - 1);
+    let end = ((cell + 1) * extent / HASH_SIZE).max(start + 1);
+    (start, end)
+}
+
+/// 亮度直方图的香农熵 - 亮度四舍五入到256级
+fn luma_entropy(buffer: &ImageBuffer<Rgba8>) -> f64 {
+    let mut histogram = [0u64; 256];
+    for luma in luma_plane(buffer) {
+        histogram[luma.round().clamp(0.0, 255.0) as usize] += 1;
+    }
+
+    let total = buffer.len() as f64;
+    histogram
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// 拉普拉斯响应的方差 - 4邻域核作用于内部像素的亮度
+fn laplacian_variance(buffer: &ImageBuffer<Rgba8>) -> f64 {
+    let ImageDimensions { width, height } = buffer.dimensions();
+    let luma = luma_plane(buffer);
+    let at = |x: u32, y: u32| luma[(y * width + x) as usize];
+
+    let mut sum = 0.0;
+    let mut sum_sq = 0.0;
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            let response = at(x - 1, y) + at(x + 1, y) + at(x, y - 1) + at(x, y + 1) - 4.0 * at(x, y);
+            sum += response;
+            sum_sq += response * response;
+        }
+    }
+
+    let n = ((width - 2) * (height - 2)) as f64;
+    let mean = sum / n;
+    (sum_sq / n - mean * mean).max(0.0)
+}
+
+/// 提取BT.709亮度平面
+fn luma_plane(buffer: &ImageBuffer<Rgba8>) -> Vec<f64> {
+    let [wr, wg, wb] = BT709_LUMA_WEIGHTS.map(f64::from);
+    buffer
+        .as_slice()
+        .iter()
+        .map(|pixel| wr * pixel.r as f64 + wg * pixel.g as f64 + wb * pixel.b as f64)
+        .collect()
+}
@@ -8,6 +8,12 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;  
 use std::fmt; // 格式化输出
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::codecs::PixelFormat;
+use crate::error::ImageError;
 
 // =============================================================================
 // 公共API类型 - 对外暴露的简单接口
@@ -35,6 +41,14 @@ pub enum ImageFormat {
     Gif,
     /// ICO格式 - 图标格式
     Ico,
+    /// PNM格式 (PPM/PGM/PBM) - 无压缩，便于调试和管道处理
+    Pnm,
+    /// TGA格式 - 游戏贴图常用，支持透明度
+    Tga,
+    /// HEIC格式 (HEIF/HEVC) - 手机照片常用，解码需要`heif`特性
+    Heic,
+    /// JPEG XL格式 - 适合归档，编解码需要`jxl`特性
+    Jxl,
 }
 
 /// 转换选项构建器 - 使用构建器模式简化复杂配置
@@ -43,6 +57,8 @@ pub enum ImageFormat {
 #[derive(Debug, Clone)]
 pub struct ConversionOptionsBuilder {
     options: ConversionOptions, // 私有：防止直接修改
+    /// 设置时被修正的参数，由`try_build`报告
+    adjustments: Vec<String>,
 }
 
 /// 转换选项 - 内部结构，通过Builder暴露
@@ -63,6 +79,131 @@ pub struct ConversionOptions {
     preserve_metadata: bool,
     /// custom: 自定义参数映射
     custom: HashMap<String, String>,
+    /// loop_count: 动画循环次数，0表示无限循环
+    #[serde(default)]
+    loop_count: Option<u16>,
+    /// grayscale: 编码前是否转换为灰度
+    #[serde(default)]
+    grayscale: bool,
+    /// background: 去除透明度时合成的背景色（默认白色）
+    #[serde(default)]
+    background: Option<Rgb8>,
+    /// timeout: 单次转换的最长耗时
+    #[serde(default)]
+    timeout: Option<Duration>,
+    /// time_budget: 编码力度自适应的时间预算
+    #[serde(default)]
+    time_budget: Option<Duration>,
+    /// webp_lossless: WebP编码模式，未设置时按是否指定质量自动选择
+    #[serde(default)]
+    webp_lossless: Option<bool>,
+    /// resize: 编码前缩放到的精确尺寸
+    #[serde(default)]
+    resize: Option<ImageDimensions>,
+    /// deterministic: 相同输入和选项产生逐字节一致的输出
+    #[serde(default)]
+    deterministic: bool,
+    /// downscale_filter: 缩小时的重采样滤波器（默认Lanczos3）
+    #[serde(default)]
+    downscale_filter: Option<ResizeFilter>,
+    /// upscale_filter: 放大时的重采样滤波器（默认Lanczos3）
+    #[serde(default)]
+    upscale_filter: Option<ResizeFilter>,
+    /// auto_contrast: 自动对比度拉伸时两端各裁剪的像素百分比
+    #[serde(default)]
+    auto_contrast: Option<f32>,
+    /// require_lossless: 目标编码有损时拒绝转换
+    #[serde(default)]
+    require_lossless: bool,
+    /// chroma_subsampling: 有损编码的色度子采样方式
+    #[serde(default)]
+    chroma_subsampling: Option<Subsampling>,
+    /// passthrough: 同格式且无像素变换时直接返回原始数据
+    #[serde(default)]
+    passthrough: bool,
+    /// png_interlaced: PNG是否使用Adam7隔行扫描
+    #[serde(default)]
+    png_interlaced: bool,
+    /// png_filter: PNG扫描线滤波器，未设置时自适应选择
+    #[serde(default)]
+    png_filter: Option<PngFilter>,
+    /// png_compression_strategy: PNG的deflate匹配策略
+    #[serde(default)]
+    png_compression_strategy: Option<CompressionStrategy>,
+    /// palette_colors: 索引色输出的调色板大小
+    #[serde(default)]
+    palette_colors: Option<u16>,
+    /// linear_resize: 在线性光空间中缩放
+    #[serde(default)]
+    linear_resize: bool,
+    /// bit_reduction_dither: 16位来源降到8位时抖动
+    #[serde(default)]
+    bit_reduction_dither: bool,
+    /// strip_all_metadata: 保证输出不含EXIF/XMP/GPS等元数据
+    #[serde(default)]
+    strip_all_metadata: bool,
+    /// target_pixel_format: 输出的像素格式（通道布局），未设置时由编码器决定
+    #[serde(default)]
+    target_pixel_format: Option<PixelFormat>,
+    /// allow_upscale: 缩放和缩略图是否允许超过原始尺寸
+    #[serde(default)]
+    allow_upscale: bool,
+}
+
+/// 色度子采样 - 以色度分辨率换取更小的输出
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Subsampling {
+    /// 4:4:4 - 不降低色度分辨率，锐利的彩色边缘和文字不渗色
+    S444,
+    /// 4:2:2 - 色度水平减半
+    S422,
+    /// 4:2:0 - 色度水平和垂直均减半，照片最常用
+    S420,
+}
+
+/// PNG扫描线滤波器 - 压缩前对每行像素做差分预测
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PngFilter {
+    /// 不滤波 - 适合调色板和纯色图像
+    None,
+    /// 与左侧像素作差
+    Sub,
+    /// 与上一行像素作差
+    Up,
+    /// 与左侧和上方像素的均值作差
+    Average,
+    /// Paeth预测 - 照片类图像通常最优
+    Paeth,
+    /// 自适应 - 逐行选择残差最小的滤波器
+    Adaptive,
+}
+
+/// PNG压缩策略 - deflate的匹配策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompressionStrategy {
+    /// 默认策略
+    Default,
+    /// 只使用较长的匹配 - 适合滤波后的小幅残差数据
+    Filtered,
+    /// 不查找匹配，只做哈夫曼编码 - 最快
+    HuffmanOnly,
+    /// 只匹配距离为1的重复 - 适合大面积纯色
+    Rle,
+    /// 只使用固定哈夫曼表
+    Fixed,
+}
+
+/// 缩放重采样滤波器 - 在速度与清晰度之间取舍
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResizeFilter {
+    /// 最近邻 - 最快，缩小时产生锯齿和摩尔纹
+    Nearest,
+    /// 双线性 - 速度与质量均衡，略显模糊
+    Triangle,
+    /// Catmull-Rom三次插值 - 锐利，开销中等
+    CatmullRom,
+    /// Lanczos3 - 最锐利、抗混叠最好，也最慢
+    Lanczos3,
 }
 
 /// 转换任务 - 简单的值对象
@@ -74,6 +215,29 @@ pub struct ConversionTask {
     pub to_format: ImageFormat,
     /// options: 转换选项
     pub options: Option<ConversionOptions>,
+    /// id: 任务标识（如文件名），批量结果中原样带回
+    #[serde(default)]
+    pub id: Option<String>,
+}
+
+/// 批量转换的单项结果 - 带回任务标识，部分失败时可定位到具体输入
+#[derive(Debug)]
+pub struct BatchItemResult {
+    /// id: 任务标识，任务未设置时取输入的文件名
+    pub id: Option<String>,
+    /// result: 该项的转换结果
+    pub result: Result<ConvertedImage, ImageError>,
+}
+
+/// 输出变体 - 同一解码结果的一个编码目标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Variant {
+    /// format: 目标格式
+    pub format: ImageFormat,
+    /// max_size: 等比缩小到不超过(宽, 高)，不放大；None表示保持原尺寸
+    pub max_size: Option<(u32, u32)>,
+    /// options: 转换选项
+    pub options: Option<ConversionOptions>,
 }
 
 /// 图像输入 - 封装输入数据和元信息
@@ -107,6 +271,118 @@ pub struct ConvertedImage {
     
     /// quality_metrics: 质量评估指标（可选）
     quality_metrics: Option<QualityMetrics>,
+    /// icc_profile: 嵌入输出的ICC色彩配置文件（可选）
+    icc_profile: Option<Vec<u8>>,
+    /// warnings: 转换成功但有损失的提示
+    warnings: Vec<ConversionWarning>,
+}
+
+/// 转换警告 - 转换成功但输出与输入存在差异
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ConversionWarning {
+    /// 要求保留元数据，但目标格式不支持
+    MetadataDropped { format: ImageFormat },
+    /// 超出目标格式的尺寸上限，已等比缩小
+    Downscaled { from: ImageDimensions, to: ImageDimensions },
+    /// 缩放目标超过原始尺寸且未允许放大，已限制在原图以内
+    UpscaleCapped { requested: ImageDimensions, to: ImageDimensions },
+    /// 目标格式不支持透明度，已与背景色合成
+    AlphaFlattened { background: Rgb8 },
+    /// 要求保留色彩空间，但目标格式无法嵌入ICC配置文件
+    ColorProfileDropped { format: ImageFormat },
+}
+
+/// 格式转换能力 - 转换矩阵中的一项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversionCapability {
+    pub from: ImageFormat,
+    pub to: ImageFormat,
+    /// supported: 源格式可解码且目标格式可编码
+    pub supported: bool,
+    /// lossless_capable: 存在不丢失像素信息的编码方式
+    pub lossless_capable: bool,
+}
+
+/// 输出大小估算 - 字节数范围
+///
+/// 按原尺寸编码时三者相等；按缩略样本外推时`min`/`max`为经验误差范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeEstimate {
+    pub min: u64,
+    pub likely: u64,
+    pub max: u64,
+}
+
+/// 格式基准测试结果 - 单个目标格式的编码开销、体积和质量
+#[derive(Debug, Clone)]
+pub struct FormatBenchmark {
+    pub format: ImageFormat,
+    /// 编码耗时（毫秒），含编码前的像素变换
+    pub encode_time_ms: f64,
+    /// 输出大小（字节）
+    pub output_size: u64,
+    /// 与源图像比较的质量指标；目标格式无法解码回读时为`None`
+    pub quality: Option<QualityMetrics>,
+}
+
+/// 转换计划中的单个步骤 - 按执行顺序排列
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PlannedOperation {
+    /// 同格式直通 - 原样返回输入数据，不解码也不编码
+    Passthrough,
+    /// 按声明的源格式解码
+    Decode { format: ImageFormat },
+    /// 按选项缩放到指定尺寸
+    Resize { from: ImageDimensions, to: ImageDimensions, filter: ResizeFilter },
+    /// 超出目标格式的尺寸上限时等比缩小
+    Downscale { from: ImageDimensions, to: ImageDimensions },
+    /// 目标格式不支持透明度时与背景色合成
+    FlattenAlpha { background: Rgb8 },
+    /// 转换为灰度
+    Grayscale,
+    /// 自动对比度拉伸
+    AutoContrast { clip_percent: f32 },
+    /// 量化为索引色调色板（在编码器中进行）
+    Quantize { colors: u16 },
+    /// 编码为目标格式
+    Encode { format: ImageFormat },
+}
+
+/// 转换计划 - 执行前预览将要发生的处理，只读取文件头，不解码像素
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionPlan {
+    /// 按文件签名检测到的源格式；无法识别时为声明的格式
+    pub source_format: ImageFormat,
+    /// 从文件头读取的源图像尺寸
+    pub source_dimensions: ImageDimensions,
+    /// 按执行顺序排列的步骤
+    pub operations: Vec<PlannedOperation>,
+    /// 输出图像尺寸
+    pub output_dimensions: ImageDimensions,
+    /// 是否会丢失信息 - 判断规则同`FormatConverter::is_conversion_lossy`
+    pub lossy: bool,
+}
+
+/// 直方图通道
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HistogramChannel {
+    Red,
+    Green,
+    Blue,
+    Alpha,
+    /// BT.709亮度，与灰度转换一致
+    Luma,
+}
+
+/// 直方图 - 每通道256级计数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    red: [u64; 256],
+    green: [u64; 256],
+    blue: [u64; 256],
+    alpha: [u64; 256],
+    luma: [u64; 256],
+    total: u64,               // 像素总数
 }
 
 /// 图像尺寸 - 简单的值类型
@@ -124,6 +400,92 @@ pub struct QualityMetrics {
     perceptual_similarity: f32,       // 感知哈希相似度
 }
 
+/// 质量等级 - 按PSNR和SSIM分档，按从差到好的顺序声明，可直接比较
+///
+/// | 等级 | PSNR | SSIM |
+/// |------|------|------|
+/// | Excellent | ≥ 40 dB | ≥ 0.98 |
+/// | Good | ≥ 35 dB | ≥ 0.95 |
+/// | Acceptable | ≥ 30 dB | ≥ 0.90 |
+/// | Poor | 其余情况 | |
+///
+/// 两项指标须同时达到该档的下限
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum QualityGrade {
+    /// 可见的压缩失真
+    Poor,
+    /// 细看可察觉差异，适合缩略图和预览
+    Acceptable,
+    /// 正常观看难以察觉差异
+    Good,
+    /// 与原图几乎无法区分
+    Excellent,
+}
+
+/// 转换摘要 - ConvertedImage 的可序列化视图，不含像素数据
+/// 
+/// 适合写入日志或分析系统
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionSummary {
+    pub dimensions: ImageDimensions,
+    pub format: ImageFormat,
+    pub conversion_time_ms: f64,
+    pub original_size: u64,
+    pub converted_size: u64,
+    pub compression_ratio: f32,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub quality_metrics: Option<QualityMetrics>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub warnings: Vec<ConversionWarning>,
+}
+
+/// 颜色摘要 - 图像的平均色与主色调，适合生成占位背景
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorSummary {
+    /// 按透明度加权的平均色
+    pub average: Rgb8,
+    /// 主色调及其像素占比，按占比降序排列，占比之和为1.0
+    pub dominant: Vec<(Rgb8, f32)>,
+}
+
+/// 图像元数据 - 从EXIF解析出的常用字段，缺失的字段为`None`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImageMetadata {
+    /// EXIF方向值 [1, 8]，1表示无需旋转
+    pub orientation: Option<u16>,
+    /// 拍摄时间，原样保留EXIF的`YYYY:MM:DD HH:MM:SS`格式；没有拍摄时间时取文件修改时间
+    pub datetime: Option<String>,
+    /// GPS坐标
+    pub gps: Option<GpsCoordinates>,
+    /// 相机制造商
+    pub camera_make: Option<String>,
+    /// 相机型号
+    pub camera_model: Option<String>,
+}
+
+/// GPS坐标 - 十进制度数，南纬和西经为负
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GpsCoordinates {
+    /// 纬度 [-90, 90]
+    pub latitude: f64,
+    /// 经度 [-180, 180]
+    pub longitude: f64,
+}
+
+/// 批量转换报告 - 汇总一批转换结果，便于写入单行日志
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchConvertReport {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// 成功项的输入字节总数
+    pub total_input_bytes: u64,
+    /// 成功项的输出字节总数
+    pub total_output_bytes: u64,
+    /// 成功项的转换耗时总和（毫秒）
+    pub total_time_ms: f64,
+}
+
 // =============================================================================
 // 格式特性系统 - 零成本抽象的体现
 // =============================================================================
@@ -302,9 +664,21 @@ pub trait Pixel: Copy + Clone + Send + Sync + 'static {
     fn luminance(&self) -> Self::Subpixel;
 }
 
+/// 通道数值特性 - 亮度等计算所需的数值转换
+pub trait Channel: Copy + Clone + Send + Sync + 'static {
+    /// 转换为浮点数
+    fn to_f32(self) -> f32;
+    
+    /// 从浮点数转换（四舍五入并截断到有效范围）
+    fn from_f32(value: f32) -> Self;
+}
+
+/// BT.709亮度权重（R、G、B）
+pub const BT709_LUMA_WEIGHTS: [f32; 3] = [0.2126, 0.7152, 0.0722];
+
 /// RGB像素 - 零开销封装
 #[repr(C)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Rgb<T> {
     /// r: 红色通道
     pub r: T,
@@ -316,7 +690,7 @@ pub struct Rgb<T> {
 
 /// RGBA像素 - 零开销封装
 #[repr(C)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Rgba<T> {
     /// r: 红色通道
     pub r: T,
@@ -330,7 +704,7 @@ pub struct Rgba<T> {
 
 /// 灰度像素 - 零开销封装
 #[repr(C)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Luma<T> {
     pub l: T,
 }
@@ -344,17 +718,24 @@ impl ConversionOptionsBuilder {
     pub fn new() -> Self {
         Self {
             options: ConversionOptions::default(),
+            adjustments: Vec::new(),
         }
     }
     
     /// 设置质量参数 - 带验证的Builder方法
     pub fn quality(mut self, quality: f32) -> Self {
+        if !(0.0..=1.0).contains(&quality) {
+            self.adjustments.push(format!("quality {} is outside [0.0, 1.0]", quality));
+        }
         self.options.quality = Some(quality.clamp(0.0, 1.0));
         self
     }
     
     /// 设置压缩级别 - 带验证的Builder方法
     pub fn compression_level(mut self, level: u8) -> Self {
+        if level > 9 {
+            self.adjustments.push(format!("compression_level {} exceeds 9", level));
+        }
         self.options.compression_level = Some(level.min(9));
         self
     }
@@ -383,16 +764,232 @@ impl ConversionOptionsBuilder {
         self
     }
     
+    /// 指定输出像素格式 - 只改变通道布局，不改变容器格式
+    ///
+    /// 不带透明通道的格式先与背景色合成，灰度格式按BT.709亮度转换。
+    /// PNG支持`Gray8`、`Rgb8`和`Rgba8`，JPEG和PNM支持`Gray8`和`Rgb8`；
+    /// 其他组合（包括16位格式）转换时返回`UnsupportedOperation`，不能与索引色输出同时使用
+    pub fn target_pixel_format(mut self, pixel_format: PixelFormat) -> Self {
+        self.options.target_pixel_format = Some(pixel_format);
+        self
+    }
+    
+    /// 强制清除元数据 - 保证输出不含EXIF、XMP（含GPS位置）、IPTC和文本注释
+    ///
+    /// 直通时从原始数据中剔除这些数据块而不重新编码；无法就地剔除的格式改为重新编码。
+    /// ICC色彩配置文件不属于此列，仍由`preserve_color_space`控制
+    pub fn strip_all_metadata(mut self, strip: bool) -> Self {
+        self.options.strip_all_metadata = strip;
+        self
+    }
+    
+    /// 是否转换为灰度 - 使用BT.709亮度权重
+    pub fn grayscale(mut self, grayscale: bool) -> Self {
+        self.options.grayscale = grayscale;
+        self
+    }
+    
+    /// 设置背景色 - 输出格式不支持透明度时与之合成
+    pub fn background(mut self, color: Rgb8) -> Self {
+        self.options.background = Some(color);
+        self
+    }
+    
+    /// 设置动画循环次数 - 0表示无限循环
+    pub fn loop_count(mut self, count: u16) -> Self {
+        self.options.loop_count = Some(count);
+        self
+    }
+    
+    /// 设置单次转换超时 - 超时后返回`OperationTimeout`
+    ///
+    /// 工作线程在阶段之间和缩放的每一行检查取消标志，到达检查点前仍在后台运行；
+    /// 这类线程达到转换器的`max_detached_workers`上限时，新的带超时转换返回`ResourceExhausted`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+    
+    /// 设置时间预算 - 按预算自适应编码力度，超出时降低力度而不是报错
+    ///
+    /// 对有速度/力度旋钮的格式（AVIF的`avif_speed`、有损WebP的`webp_method`、
+    /// PNG的`compression_level`）先用最快的设置编码，剩余时间足够时再逐级提高力度；
+    /// 显式设置过的旋钮不参与调整。与`timeout`不同，预算耗尽不会返回错误
+    pub fn time_budget(mut self, budget: Duration) -> Self {
+        self.options.time_budget = Some(budget);
+        self
+    }
+    
+    /// 编码前缩放到精确尺寸 - 不保持宽高比
+    pub fn resize(mut self, width: u32, height: u32) -> Self {
+        self.options.resize = Some(ImageDimensions { width, height });
+        self
+    }
+    
+    /// 要求无损编码 - 目标格式或所选模式有损时转换返回`UnsupportedOperation`
+    ///
+    /// 只约束编码方式；缩放、灰度等显式请求的像素变换不受影响
+    pub fn require_lossless(mut self, require: bool) -> Self {
+        self.options.require_lossless = require;
+        self
+    }
+    
+    /// 同格式直通 - 源格式与目标格式相同且未请求缩放、灰度或自动对比度时，原样返回输入数据
+    ///
+    /// 直通时质量等编码参数不生效，原有的元数据和色彩配置文件随数据保留。
+    /// 直通不解码像素，只检查文件头的尺寸和像素上限，截断等数据损坏不会被发现；默认关闭
+    pub fn passthrough(mut self, passthrough: bool) -> Self {
+        self.options.passthrough = passthrough;
+        self
+    }
+    
+    /// 索引色输出 - 编码前量化为最多`colors`种颜色的调色板，`None`保持真彩色
+    ///
+    /// 仅支持PNG和GIF，调色板大小取值范围[2, 256]；
+    /// 存在完全透明的像素时预留一个调色板项表示透明
+    pub fn palette_colors(mut self, colors: Option<u16>) -> Self {
+        self.options.palette_colors = colors;
+        self
+    }
+    
+    /// PNG隔行扫描 - 使用Adam7，浏览器可在下载过程中逐步显示整幅图像
+    ///
+    /// 隔行扫描通常会使文件略微增大
+    pub fn png_interlaced(mut self, interlaced: bool) -> Self {
+        self.options.png_interlaced = interlaced;
+        self
+    }
+    
+    /// 设置PNG扫描线滤波器
+    pub fn png_filter(mut self, filter: PngFilter) -> Self {
+        self.options.png_filter = Some(filter);
+        self
+    }
+    
+    /// 设置PNG压缩策略 - 压缩级别仍由`compression_level`控制
+    pub fn png_compression_strategy(mut self, strategy: CompressionStrategy) -> Self {
+        self.options.png_compression_strategy = Some(strategy);
+        self
+    }
+    
+    /// 设置色度子采样 - 仅对JPEG和有损WebP生效，其他格式忽略
+    ///
+    /// libwebp的有损编码只支持4:2:0：WebP请求4:4:4时改为启用sharp YUV转换以减轻边缘渗色，
+    /// 请求4:2:2时按4:2:0编码
+    pub fn chroma_subsampling(mut self, subsampling: Subsampling) -> Self {
+        self.options.chroma_subsampling = Some(subsampling);
+        self
+    }
+    
+    /// 自动对比度 - 按直方图百分位线性拉伸各颜色通道
+    ///
+    /// 两端各裁剪`clip_percent`%的极端像素，取值范围[0, 50)
+    pub fn auto_contrast(mut self, clip_percent: f32) -> Self {
+        self.options.auto_contrast = Some(clip_percent);
+        self
+    }
+    
+    /// 线性光缩放 - 先将sRGB颜色转换为线性光再滤波，完成后转换回sRGB
+    ///
+    /// 直接在sRGB编码值上滤波会使缩小后的高对比细节（如细线、棋盘格）偏暗；
+    /// 线性缩放结果正确但需要浮点运算，速度较慢。透明通道始终按线性值滤波
+    pub fn linear_resize(mut self, linear: bool) -> Self {
+        self.options.linear_resize = linear;
+        self
+    }
+    
+    /// 位深降低时抖动 - 16位PNG/TIFF解码为8位时使用有序抖动代替四舍五入
+    ///
+    /// 平滑渐变直接量化到8位会出现明显的色带，抖动以细微的噪点打散色带边界
+    pub fn bit_reduction_dither(mut self, dither: bool) -> Self {
+        self.options.bit_reduction_dither = dither;
+        self
+    }
+    
+    /// 设置缩小时的重采样滤波器 - 与放大滤波器分开配置
+    pub fn downscale_filter(mut self, filter: ResizeFilter) -> Self {
+        self.options.downscale_filter = Some(filter);
+        self
+    }
+    
+    /// 设置放大时的重采样滤波器
+    pub fn upscale_filter(mut self, filter: ResizeFilter) -> Self {
+        self.options.upscale_filter = Some(filter);
+        self
+    }
+    
+    /// 允许放大 - 默认关闭，`resize`和缩略图的尺寸不超过原图
+    ///
+    /// 放大不增加细节，只会增大输出体积；关闭时超出原图的缩放目标按原宽高比缩小到原图以内
+    pub fn allow_upscale(mut self, allow: bool) -> Self {
+        self.options.allow_upscale = allow;
+        self
+    }
+    
+    /// 可复现编码 - 不写入时间戳等易变元数据，编码器单线程运行
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.options.deterministic = deterministic;
+        self
+    }
+    
+    /// 设置WebP无损/有损编码 - 覆盖按质量参数的自动选择
+    pub fn webp_lossless(mut self, lossless: bool) -> Self {
+        self.options.webp_lossless = Some(lossless);
+        self
+    }
+    
     /// 添加自定义参数
     pub fn custom_param<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
         self.options.custom.insert(key.into(), value.into());
         self
     }
     
-    /// 构建最终的选项对象
+    /// 构建最终的选项对象 - 宽松模式：越界参数已被修正，冲突的组合不报告
     pub fn build(self) -> ConversionOptions {
         self.options
     }
+    
+    /// 严格构建 - 存在越界参数或相互冲突的选项时返回`InvalidParameters`
+    ///
+    /// 错误信息以分号分隔列出每一处问题
+    pub fn try_build(self) -> Result<ConversionOptions, ImageError> {
+        let options = &self.options;
+        let mut problems = self.adjustments.clone();
+        
+        if let Some(clip) = options.auto_contrast.filter(|clip| !(0.0..50.0).contains(clip)) {
+            problems.push(format!("auto_contrast {} is outside [0, 50)", clip));
+        }
+        if let Some(colors) = options.palette_colors.filter(|colors| !(2..=256).contains(colors)) {
+            problems.push(format!("palette_colors {} is outside [2, 256]", colors));
+        }
+        if let Some(target) = options.resize {
+            if target.width == 0 || target.height == 0 {
+                problems.push(format!("resize target {} is empty", target));
+            }
+            if options.preserve_dimensions {
+                problems.push(format!("resize to {} conflicts with preserve_dimensions", target));
+            }
+        }
+        if options.require_lossless {
+            if let Some(quality) = options.quality.filter(|&quality| quality < 1.0) {
+                problems.push(format!("require_lossless conflicts with quality {} below 1.0", quality));
+            }
+            if options.palette_colors.is_some() {
+                problems.push("require_lossless conflicts with palette_colors quantization".to_string());
+            }
+            if options.webp_lossless == Some(false) {
+                problems.push("require_lossless conflicts with webp_lossless(false)".to_string());
+            }
+        }
+        
+        if problems.is_empty() {
+            Ok(self.options)
+        } else {
+            Err(ImageError::InvalidParameters {
+                details: problems.join("; "),
+            })
+        }
+    }
 }
 
 impl Default for ConversionOptionsBuilder {
@@ -432,6 +1029,135 @@ impl ConversionOptions {
         self.preserve_metadata
     }
     
+    /// 获取输出像素格式 - 未指定时为`None`
+    pub fn target_pixel_format(&self) -> Option<PixelFormat> {
+        self.target_pixel_format
+    }
+    
+    /// 是否强制清除元数据 - 只读访问
+    pub fn strips_all_metadata(&self) -> bool {
+        self.strip_all_metadata
+    }
+    
+    /// 是否转换为灰度 - 只读访问
+    pub fn is_grayscale(&self) -> bool {
+        self.grayscale
+    }
+    
+    /// 获取透明度合成的背景色 - 未设置时为白色
+    pub fn background(&self) -> Rgb8 {
+        self.background.unwrap_or(Rgb { r: u8::MAX, g: u8::MAX, b: u8::MAX })
+    }
+    
+    /// 是否要求无损编码 - 只读访问
+    pub fn requires_lossless(&self) -> bool {
+        self.require_lossless
+    }
+    
+    /// 是否允许同格式直通 - 只读访问
+    pub fn allows_passthrough(&self) -> bool {
+        self.passthrough
+    }
+    
+    /// 是否请求了像素变换 - 缩放、灰度、自动对比度或指定输出像素格式
+    pub fn transforms_pixels(&self) -> bool {
+        self.resize.is_some() || self.grayscale || self.auto_contrast.is_some() || self.target_pixel_format.is_some()
+    }
+    
+    /// 获取调色板大小 - 未启用索引色时为`None`
+    pub fn palette_colors(&self) -> Option<u16> {
+        self.palette_colors
+    }
+    
+    /// 是否PNG隔行扫描 - 只读访问
+    pub fn is_png_interlaced(&self) -> bool {
+        self.png_interlaced
+    }
+    
+    /// 获取PNG滤波器 - 未设置时由编码器决定
+    pub fn png_filter(&self) -> Option<PngFilter> {
+        self.png_filter
+    }
+    
+    /// 获取PNG压缩策略 - 未设置时由编码器决定
+    pub fn png_compression_strategy(&self) -> Option<CompressionStrategy> {
+        self.png_compression_strategy
+    }
+    
+    /// 获取色度子采样方式 - 未设置时由编码器决定
+    pub fn chroma_subsampling(&self) -> Option<Subsampling> {
+        self.chroma_subsampling
+    }
+    
+    /// 获取自动对比度的裁剪百分比 - 未启用时为`None`
+    pub fn auto_contrast(&self) -> Option<f32> {
+        self.auto_contrast
+    }
+    
+    /// 获取动画循环次数 - 只读访问
+    pub fn loop_count(&self) -> Option<u16> {
+        self.loop_count
+    }
+    
+    /// 获取转换超时 - 只读访问
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+    
+    /// 获取时间预算 - 只读访问
+    pub fn time_budget(&self) -> Option<Duration> {
+        self.time_budget
+    }
+    
+    /// 获取缩放目标尺寸 - 只读访问
+    pub fn resize(&self) -> Option<ImageDimensions> {
+        self.resize
+    }
+    
+    /// 是否在线性光空间中缩放 - 只读访问
+    pub fn is_linear_resize(&self) -> bool {
+        self.linear_resize
+    }
+    
+    /// 16位来源降到8位时是否抖动 - 只读访问
+    pub fn is_bit_reduction_dither(&self) -> bool {
+        self.bit_reduction_dither
+    }
+    
+    /// 获取缩小滤波器 - 未设置时为Lanczos3
+    pub fn downscale_filter(&self) -> ResizeFilter {
+        self.downscale_filter.unwrap_or(ResizeFilter::Lanczos3)
+    }
+    
+    /// 获取放大滤波器 - 未设置时为Lanczos3
+    pub fn upscale_filter(&self) -> ResizeFilter {
+        self.upscale_filter.unwrap_or(ResizeFilter::Lanczos3)
+    }
+    
+    /// 是否允许放大到超过原始尺寸 - 只读访问
+    pub fn allows_upscale(&self) -> bool {
+        self.allow_upscale
+    }
+    
+    /// 按缩放方向选择滤波器 - 任一边缩小即视为缩小
+    pub fn resize_filter(&self, from: ImageDimensions, to: ImageDimensions) -> ResizeFilter {
+        if to.width < from.width || to.height < from.height {
+            self.downscale_filter()
+        } else {
+            self.upscale_filter()
+        }
+    }
+    
+    /// 是否可复现编码 - 只读访问
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+    
+    /// 获取WebP编码模式 - 只读访问
+    pub fn webp_lossless(&self) -> Option<bool> {
+        self.webp_lossless
+    }
+    
     /// 获取自定义参数 - 只读访问
     pub fn custom_param(&self, key: &str) -> Option<&str> {
         self.custom.get(key).map(|s| s.as_str())
@@ -441,18 +1167,55 @@ impl ConversionOptions {
     pub fn custom_param_keys(&self) -> impl Iterator<Item = &str> {
         self.custom.keys().map(|s| s.as_str())
     }
+    
+    /// 复制并设置自定义参数 - 包内按时间预算调整编码力度时使用
+    pub(crate) fn with_custom_param(&self, key: &str, value: String) -> Self {
+        let mut options = self.clone();
+        options.custom.insert(key.to_string(), value);
+        options
+    }
+    
+    /// 复制并设置压缩级别 - 包内按时间预算调整编码力度时使用
+    pub(crate) fn with_compression_level(&self, level: u8) -> Self {
+        let mut options = self.clone();
+        options.compression_level = Some(level);
+        options
+    }
 }
 
 impl Default for ConversionOptions {
     fn default() -> Self {
         Self {
-            quality: Some(0.8),
+            quality: None,
             compression_level: Some(6),
             progressive: Some(false),
             preserve_dimensions: true,
             preserve_color_space: true,
             preserve_metadata: false,
             custom: HashMap::new(),
+            loop_count: None,
+            grayscale: false,
+            background: None,
+            timeout: None,
+            time_budget: None,
+            webp_lossless: None,
+            resize: None,
+            deterministic: false,
+            downscale_filter: None,
+            upscale_filter: None,
+            auto_contrast: None,
+            require_lossless: false,
+            chroma_subsampling: None,
+            passthrough: false,
+            png_interlaced: false,
+            png_filter: None,
+            png_compression_strategy: None,
+            palette_colors: None,
+            linear_resize: false,
+            bit_reduction_dither: false,
+            strip_all_metadata: false,
+            target_pixel_format: None,
+            allow_upscale: false,
         }
     }
 }
@@ -520,6 +1283,8 @@ impl ConvertedImage {
             conversion_time_ms,
             original_size,
             quality_metrics: None,
+            icc_profile: None,
+            warnings: Vec::new(),
         }
     }
     
@@ -529,6 +1294,18 @@ impl ConvertedImage {
         self
     }
     
+    /// 记录嵌入的ICC配置文件 - 包内可见
+    pub(crate) fn with_icc_profile(mut self, profile: Vec<u8>) -> Self {
+        self.icc_profile = Some(profile);
+        self
+    }
+    
+    /// 附加转换警告 - 包内可见
+    pub(crate) fn with_warnings(mut self, warnings: Vec<ConversionWarning>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+    
     // 只读访问器方法
     pub fn data(&self) -> &[u8] { &self.data }
     pub fn dimensions(&self) -> ImageDimensions { self.dimensions }
@@ -536,6 +1313,17 @@ impl ConvertedImage {
     pub fn conversion_time_ms(&self) -> f64 { self.conversion_time_ms }
     pub fn original_size(&self) -> u64 { self.original_size }
     pub fn converted_size(&self) -> u64 { self.data.len() as u64 }
+    
+    /// 取出图像数据 - 移动而非复制，丢弃其余结果信息
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+    
+    /// 输出大小与编码后输入大小之比
+    ///
+    /// 分母是输入文件的字节数而非原始像素，跨格式比较时可能大于1.0
+    /// （如JPEG转PNG），此时`compression_percentage`为负；
+    /// 需要衡量相对未压缩像素的压缩效果时使用`compression_ratio_vs_raw`
     pub fn compression_ratio(&self) -> f32 { 
         if self.original_size > 0 {
             self.data.len() as f32 / self.original_size as f32
@@ -543,14 +1331,57 @@ impl ConvertedImage {
             1.0
         }
     }
+    
+    /// 输出大小与未压缩RGBA8像素数据（宽×高×4字节）之比
+    pub fn compression_ratio_vs_raw(&self) -> f32 {
+        let raw_size = self.dimensions.width as u64
+            * self.dimensions.height as u64
+            * Rgba8::CHANNEL_COUNT as u64;
+        if raw_size > 0 {
+            self.data.len() as f32 / raw_size as f32
+        } else {
+            1.0
+        }
+    }
+    
     pub fn quality_metrics(&self) -> Option<&QualityMetrics> { self.quality_metrics.as_ref() }
+    pub fn icc_profile(&self) -> Option<&[u8]> { self.icc_profile.as_deref() }
+    pub fn warnings(&self) -> &[ConversionWarning] { &self.warnings }
+    
+    /// 生成转换摘要 - 不复制图像数据
+    pub fn summary(&self) -> ConversionSummary {
+        ConversionSummary {
+            dimensions: self.dimensions,
+            format: self.format,
+            conversion_time_ms: self.conversion_time_ms,
+            original_size: self.original_size,
+            converted_size: self.converted_size(),
+            compression_ratio: self.compression_ratio(),
+            quality_metrics: self.quality_metrics.clone(),
+            warnings: self.warnings.clone(),
+        }
+    }
+    
+    /// 生成单行结构化日志 - key=value形式，不含图像数据
+    pub fn to_log_line(&self) -> String {
+        format!(
+            "format={} dimensions={} input_bytes={} output_bytes={} ratio={:.3} time_ms={:.2} warnings={}",
+            self.format,
+            self.dimensions,
+            self.original_size,
+            self.converted_size(),
+            self.compression_ratio(),
+            self.conversion_time_ms,
+            self.warnings.len()
+        )
+    }
     
     /// 计算压缩节省的字节数
     pub fn bytes_saved(&self) -> i64 {
         self.original_size as i64 - self.converted_size() as i64
     }
     
-    /// 计算压缩率百分比
+    /// 计算相对输入文件的压缩率百分比 - 输出大于输入时为负
     pub fn compression_percentage(&self) -> f32 {
         if self.original_size > 0 {
             (1.0 - self.compression_ratio()) * 100.0
@@ -560,6 +1391,115 @@ impl ConvertedImage {
     }
 }
 
+impl BatchConvertReport {
+    /// 从批量转换结果生成报告 - 失败项只计入数量
+    pub fn from_results(results: &[Result<ConvertedImage, ImageError>]) -> Self {
+        let mut report = Self { total: results.len(), ..Self::default() };
+        for result in results {
+            match result {
+                Ok(image) => {
+                    report.succeeded += 1;
+                    report.total_input_bytes += image.original_size();
+                    report.total_output_bytes += image.converted_size();
+                    report.total_time_ms += image.conversion_time_ms();
+                }
+                Err(_) => report.failed += 1,
+            }
+        }
+        report
+    }
+    
+    /// 成功率 - 空批次为0.0
+    pub fn success_rate(&self) -> f64 {
+        if self.total > 0 {
+            self.succeeded as f64 / self.total as f64
+        } else {
+            0.0
+        }
+    }
+    
+    /// 成功项的平均转换耗时（毫秒）
+    pub fn average_time_ms(&self) -> f64 {
+        if self.succeeded > 0 {
+            self.total_time_ms / self.succeeded as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Histogram {
+    /// 记录一个像素 - 包内使用
+    #[inline]
+    pub(crate) fn add(&mut self, pixel: Rgba8) {
+        self.red[pixel.r as usize] += 1;
+        self.green[pixel.g as usize] += 1;
+        self.blue[pixel.b as usize] += 1;
+        self.alpha[pixel.a as usize] += 1;
+        self.luma[Luma8::from(pixel).l as usize] += 1;
+        self.total += 1;
+    }
+    
+    /// 合并另一部分像素的直方图 - 包内使用
+    pub(crate) fn merge(mut self, other: &Histogram) -> Self {
+        for (bins, other_bins) in [
+            (&mut self.red, &other.red),
+            (&mut self.green, &other.green),
+            (&mut self.blue, &other.blue),
+            (&mut self.alpha, &other.alpha),
+            (&mut self.luma, &other.luma),
+        ] {
+            for (bin, other_bin) in bins.iter_mut().zip(other_bins) {
+                *bin += other_bin;
+            }
+        }
+        self.total += other.total;
+        self
+    }
+    
+    /// 获取通道的256级计数
+    pub fn channel(&self, channel: HistogramChannel) -> &[u64; 256] {
+        match channel {
+            HistogramChannel::Red => &self.red,
+            HistogramChannel::Green => &self.green,
+            HistogramChannel::Blue => &self.blue,
+            HistogramChannel::Alpha => &self.alpha,
+            HistogramChannel::Luma => &self.luma,
+        }
+    }
+    
+    /// 统计的像素总数
+    pub fn total(&self) -> u64 { self.total }
+    
+    /// 百分位数 - 累计计数达到`p`%的最小取值，`p`限制在0-100
+    ///
+    /// 自动对比度通常取低端和高端各约1%；空直方图返回0
+    pub fn percentile(&self, channel: HistogramChannel, p: f32) -> u8 {
+        let target = (self.total as f64 * p.clamp(0.0, 100.0) as f64 / 100.0).ceil().max(1.0) as u64;
+        let mut cumulative = 0;
+        for (value, &count) in self.channel(channel).iter().enumerate() {
+            cumulative += count;
+            if cumulative >= target {
+                return value as u8;
+            }
+        }
+        0
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            red: [0; 256],
+            green: [0; 256],
+            blue: [0; 256],
+            alpha: [0; 256],
+            luma: [0; 256],
+            total: 0,
+        }
+    }
+}
+
 impl QualityMetrics {
     /// 创建质量指标 - 包内构造器
     pub(crate) fn new(psnr: f32, ssim: f32, perceptual_similarity: f32) -> Self {
@@ -582,6 +1522,24 @@ impl QualityMetrics {
         let weighted_score = normalized_psnr * 0.4 + self.ssim * 0.4 + self.perceptual_similarity * 0.2;
         weighted_score.clamp(0.0, 1.0)
     }
+    
+    /// 质量等级 - 分档边界见[`QualityGrade`]
+    pub fn grade(&self) -> QualityGrade {
+        const BANDS: [(QualityGrade, f32, f32); 3] = [
+            (QualityGrade::Excellent, 40.0, 0.98),
+            (QualityGrade::Good, 35.0, 0.95),
+            (QualityGrade::Acceptable, 30.0, 0.90),
+        ];
+        BANDS
+            .iter()
+            .find(|&&(_, min_psnr, min_ssim)| self.meets(min_psnr, min_ssim))
+            .map_or(QualityGrade::Poor, |&(grade, _, _)| grade)
+    }
+    
+    /// 是否同时达到PSNR（dB）和SSIM下限
+    pub fn meets(&self, min_psnr: f32, min_ssim: f32) -> bool {
+        self.psnr >= min_psnr && self.ssim >= min_ssim
+    }
 }
 
 // 格式能力标志常量 - 编译时常量
@@ -648,6 +1606,52 @@ impl FormatCapabilities {
 }
 
 impl ImageFormat {
+    /// 所有支持的格式
+    pub const ALL: &'static [ImageFormat] = &[
+        ImageFormat::Jpeg,
+        ImageFormat::Png,
+        ImageFormat::WebP,
+        ImageFormat::Avif,
+        ImageFormat::Bmp,
+        ImageFormat::Tiff,
+        ImageFormat::Gif,
+        ImageFormat::Ico,
+        ImageFormat::Pnm,
+        ImageFormat::Tga,
+        ImageFormat::Heic,
+        ImageFormat::Jxl,
+    ];
+    
+    /// 从文件扩展名识别格式 - 不区分大小写，允许前导点
+    pub fn from_extension(extension: &str) -> Option<ImageFormat> {
+        let extension = extension.trim().trim_start_matches('.');
+        Self::ALL.iter().copied().find(|format| {
+            format.info().extensions.iter().any(|ext| ext.eq_ignore_ascii_case(extension))
+        })
+    }
+    
+    /// 从文件路径识别格式 - 只看最后一个扩展名，如 `photo.final.jpeg`
+    pub fn from_path(path: &Path) -> Option<ImageFormat> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_extension)
+    }
+    
+    /// 从MIME类型识别格式 - 忽略参数部分（如`; charset=`）
+    pub fn from_mime_type(mime_type: &str) -> Option<ImageFormat> {
+        let essence = mime_type.split(';').next().unwrap_or("").trim();
+        match essence.to_ascii_lowercase().as_str() {
+            "image/jpg" | "image/pjpeg" => Some(ImageFormat::Jpeg),
+            "image/vnd.microsoft.icon" => Some(ImageFormat::Ico),
+            "image/x-portable-pixmap" | "image/x-portable-graymap" | "image/x-portable-bitmap" => {
+                Some(ImageFormat::Pnm)
+            }
+            "image/x-targa" | "image/tga" => Some(ImageFormat::Tga),
+            "image/heif" | "image/heic-sequence" | "image/heif-sequence" => Some(ImageFormat::Heic),
+            essence => Self::ALL.iter().copied().find(|format| format.mime_type() == essence),
+        }
+    }
+    
     /// 获取格式信息 - 编译时常量查找
     pub const fn info(&self) -> FormatInfo {
         match self {
@@ -774,6 +1778,69 @@ impl ImageFormat {
                     supported_bit_depths: &[8, 16, 24, 32],
                 },
             },
+            ImageFormat::Pnm => FormatInfo {
+                name: "PNM",
+                description: "Portable Anymap (PPM/PGM/PBM) - 无压缩，支持二进制和ASCII",
+                extensions: &["ppm", "pgm", "pbm", "pnm"],
+                mime_type: "image/x-portable-anymap",
+                capabilities: FormatCapabilities::new(),
+                limits: FormatLimits {
+                    max_width: None,
+                    max_height: None,
+                    max_file_size: None,
+                    supported_bit_depths: &[1, 8, 16],
+                },
+            },
+            ImageFormat::Tga => FormatInfo {
+                name: "TGA",
+                description: "Truevision TGA - 游戏贴图格式，无压缩或RLE压缩",
+                extensions: &["tga"],
+                mime_type: "image/x-tga",
+                capabilities: FormatCapabilities::new()
+                    .with_transparency(),
+                limits: FormatLimits {
+                    max_width: Some(65535),
+                    max_height: Some(65535),
+                    max_file_size: None,
+                    supported_bit_depths: &[8, 16, 24, 32],
+                },
+            },
+            ImageFormat::Heic => FormatInfo {
+                name: "HEIC",
+                description: "High Efficiency Image Container - HEVC编码，手机照片常用",
+                extensions: &["heic", "heif", "hif"],
+                mime_type: "image/heic",
+                capabilities: FormatCapabilities::new()
+                    .with_lossy()
+                    .with_transparency()
+                    .with_hdr()
+                    .with_metadata(),
+                limits: FormatLimits {
+                    max_width: None,
+                    max_height: None,
+                    max_file_size: None,
+                    supported_bit_depths: &[8, 10, 12],
+                },
+            },
+            ImageFormat::Jxl => FormatInfo {
+                name: "JXL",
+                description: "JPEG XL - 新一代归档格式，支持无损和有损",
+                extensions: &["jxl"],
+                mime_type: "image/jxl",
+                capabilities: FormatCapabilities::new()
+                    .with_lossy()
+                    .with_transparency()
+                    .with_animation()
+                    .with_progressive()
+                    .with_hdr()
+                    .with_metadata(),
+                limits: FormatLimits {
+                    max_width: Some(1 << 30),
+                    max_height: Some(1 << 30),
+                    max_file_size: None,
+                    supported_bit_depths: &[8, 16, 32],
+                },
+            },
         }
     }
     
@@ -806,16 +1873,42 @@ impl ImageFormat {
     pub fn supports_animation(&self) -> bool {
         self.info().capabilities.supports_animation()
     }
+    
+    /// 检查是否支持嵌入ICC色彩配置文件
+    #[inline]
+    pub fn supports_icc_profile(&self) -> bool {
+        matches!(self, ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP | ImageFormat::Tiff)
+    }
+}
+
+/// 字符串解析 - 接受格式名称或扩展名，不区分大小写
+impl FromStr for ImageFormat {
+    type Err = ImageError;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|format| format.info().name.eq_ignore_ascii_case(name))
+            .or_else(|| Self::from_extension(name))
+            .ok_or_else(|| ImageError::invalid_format(format!("Unknown image format '{}'", s)))
+    }
+}
+
+impl TryFrom<&str> for ImageFormat {
+    type Error = ImageError;
+    
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
 }
 
 // =============================================================================
 // 像素类型实现 - 零成本抽象的完美示例
 // =============================================================================
 
-impl<T> Pixel for Rgb<T> 
-where 
-    T: Copy + Clone + Send + Sync + 'static
-{
+impl<T: Channel> Pixel for Rgb<T> {
     type Subpixel = T;
     const CHANNEL_COUNT: u8 = 3;
     const HAS_ALPHA: bool = false;
@@ -834,15 +1927,12 @@ where
     
     #[inline]
     fn luminance(&self) -> Self::Subpixel {
-        // 简化的亮度计算，避免复杂的类型转换
-        self.r
+        let [wr, wg, wb] = BT709_LUMA_WEIGHTS;
+        T::from_f32(wr * self.r.to_f32() + wg * self.g.to_f32() + wb * self.b.to_f32())
     }
 }
 
-impl<T> Pixel for Rgba<T> 
-where 
-    T: Copy + Clone + Send + Sync + 'static
-{
+impl<T: Channel> Pixel for Rgba<T> {
     type Subpixel = T;
     const CHANNEL_COUNT: u8 = 4;
     const HAS_ALPHA: bool = true;
@@ -861,15 +1951,12 @@ where
     
     #[inline]
     fn luminance(&self) -> Self::Subpixel {
-        // 简化的亮度计算，避免复杂的类型转换
-        self.r
+        let [wr, wg, wb] = BT709_LUMA_WEIGHTS;
+        T::from_f32(wr * self.r.to_f32() + wg * self.g.to_f32() + wb * self.b.to_f32())
     }
 }
 
-impl<T> Pixel for Luma<T> 
-where 
-    T: Copy + Clone + Send + Sync + 'static
-{
+impl<T: Channel> Pixel for Luma<T> {
     type Subpixel = T;
     const CHANNEL_COUNT: u8 = 1;
     const HAS_ALPHA: bool = false;
@@ -892,6 +1979,69 @@ where
     }
 }
 
+impl Channel for u8 {
+    #[inline]
+    fn to_f32(self) -> f32 { self as f32 }
+    
+    #[inline]
+    fn from_f32(value: f32) -> Self { value.round().clamp(0.0, u8::MAX as f32) as u8 }
+}
+
+impl Channel for u16 {
+    #[inline]
+    fn to_f32(self) -> f32 { self as f32 }
+    
+    #[inline]
+    fn from_f32(value: f32) -> Self { value.round().clamp(0.0, u16::MAX as f32) as u16 }
+}
+
+impl Channel for f32 {
+    #[inline]
+    fn to_f32(self) -> f32 { self }
+    
+    #[inline]
+    fn from_f32(value: f32) -> Self { value }
+}
+
+// =============================================================================
+// 像素格式转换 - 配合 ImageBuffer::convert_pixel_format 使用
+// =============================================================================
+
+impl From<Rgba8> for Luma8 {
+    #[inline]
+    fn from(pixel: Rgba8) -> Self {
+        Luma { l: pixel.luminance() }
+    }
+}
+
+impl From<Rgb8> for Luma8 {
+    #[inline]
+    fn from(pixel: Rgb8) -> Self {
+        Luma { l: pixel.luminance() }
+    }
+}
+
+impl From<Luma8> for Rgba8 {
+    #[inline]
+    fn from(pixel: Luma8) -> Self {
+        Rgba { r: pixel.l, g: pixel.l, b: pixel.l, a: u8::MAX }
+    }
+}
+
+impl From<Rgba8> for Rgb8 {
+    #[inline]
+    fn from(pixel: Rgba8) -> Self {
+        Rgb { r: pixel.r, g: pixel.g, b: pixel.b }
+    }
+}
+
+impl From<Rgb8> for Rgba8 {
+    #[inline]
+    fn from(pixel: Rgb8) -> Self {
+        Rgba { r: pixel.r, g: pixel.g, b: pixel.b, a: u8::MAX }
+    }
+}
+
 // =============================================================================
 // 显示实现 - 用户友好的调试输出
 // =============================================================================
@@ -908,6 +2058,30 @@ impl fmt::Display for ImageDimensions {
     }
 }
 
+impl fmt::Display for ConversionWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionWarning::MetadataDropped { format } => {
+                write!(f, "Metadata dropped: {} does not support metadata", format)
+            }
+            ConversionWarning::Downscaled { from, to } => {
+                write!(f, "Downscaled from {} to {} to fit format limits", from, to)
+            }
+            ConversionWarning::UpscaleCapped { requested, to } => {
+                write!(f, "Resize to {} capped at {}: upscaling not allowed", requested, to)
+            }
+            ConversionWarning::AlphaFlattened { background } => write!(
+                f,
+                "Alpha flattened onto background #{:02x}{:02x}{:02x}",
+                background.r, background.g, background.b
+            ),
+            ConversionWarning::ColorProfileDropped { format } => {
+                write!(f, "Color profile dropped: {} cannot embed ICC profiles", format)
+            }
+        }
+    }
+}
+
 impl fmt::Display for ConvertedImage {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{} image {}×{} ({} -> {} bytes, {:.1}% compression)", 
@@ -920,6 +2094,25 @@ impl fmt::Display for ConvertedImage {
     }
 }
 
+impl From<ConvertedImage> for Vec<u8> {
+    fn from(image: ConvertedImage) -> Self {
+        image.into_bytes()
+    }
+}
+
+impl fmt::Display for BatchConvertReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{} converted ({:.1}% success, {} failed), {} -> {} bytes, avg {:.2} ms",
+               self.succeeded,
+               self.total,
+               self.success_rate() * 100.0,
+               self.failed,
+               self.total_input_bytes,
+               self.total_output_bytes,
+               self.average_time_ms())
+    }
+}
+
 // =============================================================================
 // 类型别名 - 常用组合的简化
 // =============================================================================
@@ -0,0 +1,191 @@
+//! 动画帧处理测试
+//!
+//! 测试多帧图像的解码与重新编码
+
+use rustimage_core::{ConversionOptionsBuilder, FormatConverter, ImageError, ImageFormat, Rgba8};
+use rustimage_core::codecs::{ImageBuffer, PixelFormat};
+use rustimage_core::converter::Cancellation;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, RgbaImage};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// 创建指定颜色和帧时长的三帧GIF
+fn create_three_frame_gif(delays_ms: [u64; 3]) -> Vec<u8> {
+    let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+    let mut data = Vec::new();
+    {
+        let mut encoder = GifEncoder::new(&mut data);
+        encoder.set_repeat(Repeat::Infinite).unwrap();
+        for (color, delay) in colors.iter().zip(delays_ms.iter()) {
+            let img = RgbaImage::from_pixel(8, 8, image::Rgba(*color));
+            let delay = Delay::from_saturating_duration(Duration::from_millis(*delay));
+            encoder.encode_frame(Frame::from_parts(img, 0, 0, delay)).unwrap();
+        }
+    }
+    data
+}
+
+/// 允许指定次数的检查通过，之后报告已取消
+struct CancelAfterChecks {
+    remaining: AtomicUsize,
+}
+
+impl CancelAfterChecks {
+    fn new(checks: usize) -> Self {
+        Self { remaining: AtomicUsize::new(checks) }
+    }
+}
+
+impl Cancellation for CancelAfterChecks {
+    fn is_cancelled(&self) -> bool {
+        self.remaining
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_err()
+    }
+}
+
+#[test]
+fn test_decode_gif_frames_with_delays() {
+    let gif_data = create_three_frame_gif([100, 200, 300]);
+    let converter = FormatConverter::with_defaults().unwrap();
+
+    let frames = converter.decode_frames(&gif_data, ImageFormat::Gif).unwrap();
+
+    assert_eq!(frames.len(), 3);
+    let delays: Vec<Duration> = frames.iter().map(|(_, delay)| *delay).collect();
+    assert_eq!(
+        delays,
+        vec![
+            Duration::from_millis(100),
+            Duration::from_millis(200),
+            Duration::from_millis(300),
+        ]
+    );
+
+    // 每一帧都是完整画布
+    for (buffer, _) in &frames {
+        assert_eq!(buffer.dimensions().width, 8);
+        assert_eq!(buffer.dimensions().height, 8);
+    }
+    let first = frames[0].0.get_pixel(0, 0).unwrap();
+    assert!(first.r > 200 && first.g < 50 && first.b < 50);
+}
+
+#[test]
+fn test_encode_frames_round_trip() {
+    let converter = FormatConverter::with_defaults().unwrap();
+
+    let frames: Vec<(ImageBuffer<Rgba8>, Duration)> = (0..3u8)
+        .map(|i| {
+            let pixel = Rgba8 { r: i * 100, g: 0, b: 0, a: 255 };
+            let buffer = ImageBuffer::from_raw(4, 4, vec![pixel; 16], PixelFormat::Rgba8).unwrap();
+            (buffer, Duration::from_millis(50))
+        })
+        .collect();
+
+    let gif_data = converter.encode_frames(&frames, ImageFormat::Gif, None).unwrap();
+    let decoded = converter.decode_frames(&gif_data, ImageFormat::Gif).unwrap();
+
+    assert_eq!(decoded.len(), 3);
+    assert!(decoded.iter().all(|(_, delay)| *delay == Duration::from_millis(50)));
+}
+
+#[test]
+fn test_static_image_decodes_as_single_frame() {
+    let img = RgbaImage::from_pixel(4, 4, image::Rgba([10, 20, 30, 255]));
+    let mut png_data = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut png_data), image::ImageOutputFormat::Png)
+        .unwrap();
+
+    let converter = FormatConverter::with_defaults().unwrap();
+    let frames = converter.decode_frames(&png_data, ImageFormat::Png).unwrap();
+
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].1, Duration::ZERO);
+}
+
+#[test]
+fn test_gif_to_animated_webp_round_trip() {
+    let gif_data = create_three_frame_gif([100, 200, 300]);
+    let converter = FormatConverter::with_defaults().unwrap();
+
+    let frames = converter.decode_frames(&gif_data, ImageFormat::Gif).unwrap();
+    let webp = converter
+        .encode_animation(frames, ImageFormat::WebP, None)
+        .unwrap();
+    assert_eq!(webp.format(), ImageFormat::WebP);
+    assert_eq!(webp.dimensions().width, 8);
+
+    // 动画WebP可以被解码回完整的帧序列
+    let webp_frames = converter.decode_frames(webp.data(), ImageFormat::WebP).unwrap();
+    assert_eq!(webp_frames.len(), 3);
+    assert_eq!(webp_frames[1].1, Duration::from_millis(200));
+
+    let gif = converter
+        .encode_animation(webp_frames, ImageFormat::Gif, None)
+        .unwrap();
+    let gif_frames = converter.decode_frames(gif.data(), ImageFormat::Gif).unwrap();
+    assert_eq!(gif_frames.len(), 3);
+}
+
+#[test]
+fn test_encode_animation_honors_loop_count() {
+    let gif_data = create_three_frame_gif([100, 100, 100]);
+    let converter = FormatConverter::with_defaults().unwrap();
+    let frames = converter.decode_frames(&gif_data, ImageFormat::Gif).unwrap();
+
+    let options = ConversionOptionsBuilder::new().loop_count(3).build();
+    let webp = converter
+        .encode_animation(frames, ImageFormat::WebP, Some(options))
+        .unwrap();
+
+    // ANIM块紧跟在VP8X块之后：背景色4字节，然后是循环次数
+    let anim = webp.data().windows(4).position(|w| w == b"ANIM").unwrap();
+    let loop_count = u16::from_le_bytes([webp.data()[anim + 12], webp.data()[anim + 13]]);
+    assert_eq!(loop_count, 3);
+}
+
+#[test]
+fn test_encode_animation_rejects_static_formats() {
+    let gif_data = create_three_frame_gif([100, 100, 100]);
+    let converter = FormatConverter::with_defaults().unwrap();
+    let frames = converter.decode_frames(&gif_data, ImageFormat::Gif).unwrap();
+
+    let result = converter.encode_animation(frames, ImageFormat::Png, None);
+    assert!(matches!(result, Err(ImageError::UnsupportedOperation { .. })));
+}
+
+#[test]
+fn test_cancel_multi_frame_gif_between_frames() {
+    let gif_data = create_three_frame_gif([100, 100, 100]);
+    let converter = FormatConverter::with_defaults().unwrap();
+
+    // 第一帧解码后取消
+    let result = converter.decode_frames_cancellable(&gif_data, ImageFormat::Gif, &CancelAfterChecks::new(1));
+    match result {
+        Err(ImageError::OperationCancelled { completed, .. }) => assert_eq!(completed, 1),
+        other => panic!("expected OperationCancelled, got {:?}", other.map(|f| f.len())),
+    }
+
+    // 编码两帧后取消，总帧数已知
+    let frames = converter.decode_frames(&gif_data, ImageFormat::Gif).unwrap();
+    let result = converter.encode_animation_cancellable(frames.clone(), ImageFormat::Gif, None, &CancelAfterChecks::new(2));
+    match result {
+        Err(ImageError::OperationCancelled { completed, total, .. }) => {
+            assert_eq!(completed, 2);
+            assert_eq!(total, 3);
+        }
+        other => panic!("expected OperationCancelled, got {:?}", other.map(|c| c.data().len())),
+    }
+
+    // 从未取消时与普通接口结果一致
+    let decoded = converter
+        .decode_frames_cancellable(&gif_data, ImageFormat::Gif, &CancelAfterChecks::new(usize::MAX))
+        .unwrap();
+    assert_eq!(decoded.len(), 3);
+    let encoded = converter
+        .encode_animation_cancellable(frames, ImageFormat::WebP, None, &CancelAfterChecks::new(usize::MAX))
+        .unwrap();
+    assert_eq!(encoded.format(), ImageFormat::WebP);
+}
@@ -0,0 +1,105 @@
+//! AVIF编码测试
+//!
+//! 测试转换选项对AVIF编码速度和质量参数的映射
+
+use rustimage_core::{ConversionOptionsBuilder, FormatConverter, ImageError, ImageFormat};
+use image::{ImageBuffer, ImageEncoder, Rgba};
+#[cfg(feature = "avif")]
+use std::time::Duration;
+
+/// 创建带纹理的PNG - 纹理让编码速度对文件大小的影响可见
+fn create_textured_png(width: u32, height: u32) -> Vec<u8> {
+    let img = ImageBuffer::from_fn(width, height, |x, y| {
+        let noise = (x.wrapping_mul(7919) ^ y.wrapping_mul(104729)) as u8;
+        Rgba([(x * 4) as u8 ^ (noise / 8), (y * 4) as u8, noise, 255])
+    });
+    let mut data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut data)
+        .write_image(img.as_raw(), width, height, image::ColorType::Rgba8)
+        .unwrap();
+    data
+}
+
+#[test]
+fn test_invalid_avif_params_are_rejected() {
+    let png = create_textured_png(8, 8);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    for (key, value) in [("avif_speed", "11"), ("avif_speed", "fast"), ("avif_quality_alpha", "101")] {
+        let options = ConversionOptionsBuilder::new().custom_param(key, value).build();
+        let result = converter.convert_format(&png, ImageFormat::Png, ImageFormat::Avif, Some(options));
+        assert!(
+            matches!(result, Err(ImageError::InvalidParameters { .. })),
+            "{}={} should be rejected",
+            key,
+            value
+        );
+    }
+}
+
+#[cfg(feature = "avif")]
+#[test]
+fn test_slower_speed_produces_smaller_file() {
+    let png = create_textured_png(64, 64);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let mut encode_at = |speed: &str| {
+        let options = ConversionOptionsBuilder::new()
+            .quality(0.6)
+            .custom_param("avif_speed", speed)
+            .build();
+        converter
+            .convert_format(&png, ImageFormat::Png, ImageFormat::Avif, Some(options))
+            .unwrap()
+    };
+
+    let slow = encode_at("1");
+    let fast = encode_at("10");
+
+    assert_eq!(rustimage_core::detect_format(slow.data()).unwrap(), ImageFormat::Avif);
+    assert!(
+        slow.data().len() < fast.data().len(),
+        "speed 1 ({} bytes) should beat speed 10 ({} bytes)",
+        slow.data().len(),
+        fast.data().len()
+    );
+}
+
+#[cfg(feature = "avif")]
+#[test]
+fn test_tiny_time_budget_uses_fastest_speed() {
+    let png = create_textured_png(64, 64);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let budgeted = ConversionOptionsBuilder::new()
+        .quality(0.6)
+        .deterministic(true)
+        .time_budget(Duration::from_micros(1))
+        .build();
+    let fastest = ConversionOptionsBuilder::new()
+        .quality(0.6)
+        .deterministic(true)
+        .custom_param("avif_speed", "10")
+        .build();
+
+    let budgeted = converter
+        .convert_format(&png, ImageFormat::Png, ImageFormat::Avif, Some(budgeted))
+        .unwrap();
+    let fastest = converter
+        .convert_format(&png, ImageFormat::Png, ImageFormat::Avif, Some(fastest))
+        .unwrap();
+
+    assert_eq!(budgeted.data(), fastest.data());
+}
+
+#[cfg(feature = "avif")]
+#[test]
+fn test_high_quality_conversion_to_avif_without_decoder() {
+    let png = create_textured_png(32, 32);
+    let mut converter = FormatConverter::with_high_quality().unwrap();
+
+    // AVIF暂不支持解码，质量评估跳过而不是让转换失败
+    let converted = converter.convert_format(&png, ImageFormat::Png, ImageFormat::Avif, None).unwrap();
+    assert!(!converted.data().is_empty());
+    assert!(converted.quality_metrics().is_none());
+}
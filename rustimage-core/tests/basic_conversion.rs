@@ -5,10 +5,12 @@
 use rustimage_core::{
     convert_format, detect_format,
     ImageFormat, ConversionOptionsBuilder,
-    FormatConverter,
+    DecodedImage, FormatConverter, ImageError, Variant,
 };
+use rustimage_core::converter::ConversionObserver;
 use image::{ImageBuffer, Rgba, ImageEncoder};
 use std::io::Cursor;
+use std::sync::{Arc, Mutex};
 
 #[test]
 fn test_format_detection() {
@@ -277,3 +279,1193 @@ fn test_converter_statistics() {
     // 统计应该被更新（无论转换成功还是失败）
     assert!(final_stats.total_conversions >= initial_stats.total_conversions);
 }
+
+/// 记录每次回调的观察者 - `reject`时在转换前拒绝
+struct RecordingObserver {
+    calls: Arc<Mutex<Vec<String>>>,
+    reject: bool,
+}
+
+impl ConversionObserver for RecordingObserver {
+    fn before(&self, from_format: ImageFormat, to_format: ImageFormat, input_len: usize) -> rustimage_core::Result<()> {
+        self.calls.lock().unwrap().push(format!("before {:?}->{:?} {}", from_format, to_format, input_len));
+        if self.reject {
+            return Err(ImageError::InvalidParameters { details: "rejected by observer".to_string() });
+        }
+        Ok(())
+    }
+
+    fn after(&self, result: &rustimage_core::Result<rustimage_core::ConvertedImage>) {
+        let outcome = if result.is_ok() { "ok" } else { "err" };
+        self.calls.lock().unwrap().push(format!("after {}", outcome));
+    }
+}
+
+#[test]
+fn test_observer_sees_every_conversion() {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_fn(8, 8, |x, y| Rgba([x as u8 * 30, y as u8 * 30, 0, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 8, 8, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    converter.add_observer(Box::new(RecordingObserver { calls: calls.clone(), reject: false }));
+
+    converter.convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, None).unwrap();
+    assert!(converter.convert_format(&[0u8; 16], ImageFormat::Png, ImageFormat::Jpeg, None).is_err());
+    assert_eq!(*calls.lock().unwrap(), vec![
+        format!("before Png->Jpeg {}", png_data.len()),
+        "after ok".to_string(),
+        "before Png->Jpeg 16".to_string(),
+        "after err".to_string(),
+    ]);
+
+    // 观察者拒绝时跳过转换，但所有观察者仍收到前后通知
+    calls.lock().unwrap().clear();
+    converter.add_observer(Box::new(RecordingObserver { calls: calls.clone(), reject: true }));
+    let error = converter.convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, None).unwrap_err();
+    assert!(matches!(error, ImageError::InvalidParameters { .. }), "{:?}", error);
+    assert_eq!(calls.lock().unwrap().iter().filter(|call| call.starts_with("before")).count(), 2);
+    assert_eq!(calls.lock().unwrap().iter().filter(|call| *call == "after err").count(), 2);
+}
+
+#[test]
+fn test_conversion_summary_serialization() {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_pixel(4, 3, Rgba([10, 20, 30, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 4, 3, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let converted = convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, None)
+        .expect("PNG to JPEG conversion failed");
+    let summary = converted.summary();
+    assert_eq!(summary.converted_size, converted.converted_size());
+
+    let json = serde_json::to_value(&summary).expect("Failed to serialize summary");
+    let object = json.as_object().unwrap();
+    for key in [
+        "dimensions",
+        "format",
+        "conversion_time_ms",
+        "original_size",
+        "converted_size",
+        "compression_ratio",
+    ] {
+        assert!(object.contains_key(key), "missing key {}", key);
+    }
+    assert!(!object.contains_key("data"));
+    assert_eq!(json["dimensions"]["width"], 4);
+    assert_eq!(json["format"], "Jpeg");
+}
+
+#[test]
+fn test_into_bytes_moves_data_without_copy() {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_pixel(4, 3, Rgba([10, 20, 30, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 4, 3, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let converted = convert_format(&png_data, ImageFormat::Png, ImageFormat::Bmp, None)
+        .expect("PNG to BMP conversion failed");
+    let expected = converted.data().to_vec();
+    let data_ptr = converted.data().as_ptr();
+
+    let bytes = converted.into_bytes();
+    assert_eq!(bytes, expected);
+    assert_eq!(bytes.as_ptr(), data_ptr);
+
+    let converted = convert_format(&png_data, ImageFormat::Png, ImageFormat::Bmp, None).unwrap();
+    let data_ptr = converted.data().as_ptr();
+    let bytes: Vec<u8> = converted.into();
+    assert_eq!(bytes, expected);
+    assert_eq!(bytes.as_ptr(), data_ptr);
+}
+
+#[test]
+fn test_decode_once_encode_many() {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_fn(6, 4, |x, y| Rgba([x as u8 * 40, y as u8 * 60, 90, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 6, 4, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let decoded = converter.decode_to_buffer(&png_data, ImageFormat::Png).unwrap();
+    assert_eq!(decoded.dimensions().width, 6);
+    assert_eq!(decoded.source_format(), Some(ImageFormat::Png));
+
+    let png = converter.encode_buffer(&decoded, ImageFormat::Png, None).unwrap();
+    let jpeg = converter.encode_buffer(&decoded, ImageFormat::Jpeg, None).unwrap();
+
+    assert_eq!(detect_format(png.data()).unwrap(), ImageFormat::Png);
+    assert_eq!(detect_format(jpeg.data()).unwrap(), ImageFormat::Jpeg);
+    assert_eq!(png.dimensions(), decoded.dimensions());
+    assert_eq!(jpeg.dimensions(), decoded.dimensions());
+    assert_eq!(png.original_size(), png_data.len() as u64);
+
+    // 无损往返保持像素不变
+    let roundtrip = image::load_from_memory(png.data()).unwrap().to_rgba8();
+    assert_eq!(roundtrip.as_raw(), img.as_raw());
+    assert_eq!(converter.get_conversion_statistics().successful_conversions, 2);
+}
+
+#[test]
+fn test_convert_and_keep_buffer_returns_transformed_pixels() {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_fn(300, 200, |x, y| Rgba([x as u8, y as u8, 60, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 300, 200, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let options = ConversionOptionsBuilder::new().resize(150, 100).grayscale(true).build();
+    let (converted, decoded) = converter
+        .convert_and_keep_buffer(&png_data, ImageFormat::Png, ImageFormat::Jpeg, Some(options))
+        .unwrap();
+
+    assert_eq!(detect_format(converted.data()).unwrap(), ImageFormat::Jpeg);
+    assert_eq!(decoded.dimensions(), converted.dimensions());
+    assert_eq!((decoded.dimensions().width, decoded.dimensions().height), (150, 100));
+    assert_eq!(decoded.source_format(), Some(ImageFormat::Png));
+    assert_eq!(decoded.source_size(), png_data.len() as u64);
+    // 保留的是变换之后的像素
+    assert!(decoded.buffer().as_slice().iter().all(|p| p.r == p.g && p.g == p.b));
+
+    // 保留的句柄可直接继续编码
+    let png = converter.encode_buffer(&decoded, ImageFormat::Png, None).unwrap();
+    assert_eq!(png.dimensions(), converted.dimensions());
+    assert_eq!(converter.get_conversion_statistics().successful_conversions, 2);
+}
+
+#[test]
+fn test_raw_rgba_round_trip() {
+    let raw: Vec<u8> = (0..3 * 2 * 4).map(|i| (i * 10) as u8).collect();
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let decoded = converter.from_raw_rgba(&raw, 3, 2).unwrap();
+    assert_eq!((decoded.dimensions().width, decoded.dimensions().height), (3, 2));
+    let first = decoded.buffer().as_slice()[0];
+    assert_eq!((first.r, first.g, first.b, first.a), (0, 10, 20, 30));
+    // 原始像素没有容器格式
+    assert_eq!(decoded.source_format(), None);
+    assert_eq!(converter.encode_raw_rgba(&decoded), raw);
+
+    // 经过无损格式往返后字节不变
+    let png = converter.encode_buffer(&decoded, ImageFormat::Png, None).unwrap();
+    let reloaded = converter.decode_to_buffer(png.data(), ImageFormat::Png).unwrap();
+    assert_eq!(converter.encode_raw_rgba(&reloaded), raw);
+
+    // 长度与尺寸不符时报错
+    let error = converter.from_raw_rgba(&raw[..raw.len() - 1], 3, 2).unwrap_err();
+    assert!(matches!(error, ImageError::InvalidParameters { .. }), "{:?}", error);
+
+    // 零尺寸报错，而不是生成空图像
+    let error = converter.from_raw_rgba(&[], 0, 0).unwrap_err();
+    assert!(matches!(error, ImageError::InvalidDimensions { width: 0, height: 0, .. }), "{:?}", error);
+    let error = converter.from_raw_rgba(&[], 4, 0).unwrap_err();
+    assert!(matches!(error, ImageError::InvalidDimensions { .. }), "{:?}", error);
+}
+
+#[test]
+fn test_encode_variants_from_single_decode() {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_fn(1024, 768, |x, y| Rgba([(x / 4) as u8, (y / 3) as u8, 128, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 1024, 768, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let decoded = converter.decode_to_buffer(&png_data, ImageFormat::Png).unwrap();
+    let variants = [
+        Variant { format: ImageFormat::WebP, max_size: Some((256, 256)), options: None },
+        Variant { format: ImageFormat::Jpeg, max_size: Some((512, 512)), options: None },
+        Variant { format: ImageFormat::Png, max_size: Some((0, 512)), options: None },
+    ];
+    let results = converter.encode_variants(&decoded, &variants);
+    assert_eq!(results.len(), 3);
+
+    // 结果顺序与变体顺序一致
+    let webp = results[0].as_ref().unwrap();
+    assert_eq!(detect_format(webp.data()).unwrap(), ImageFormat::WebP);
+    assert_eq!((webp.dimensions().width, webp.dimensions().height), (256, 192));
+
+    let jpeg = results[1].as_ref().unwrap();
+    assert_eq!(detect_format(jpeg.data()).unwrap(), ImageFormat::Jpeg);
+    assert_eq!((jpeg.dimensions().width, jpeg.dimensions().height), (512, 384));
+    let decoded_jpeg = image::load_from_memory(jpeg.data()).unwrap();
+    assert_eq!((decoded_jpeg.width(), decoded_jpeg.height()), (512, 384));
+
+    assert!(matches!(results[2], Err(ImageError::InvalidParameters { .. })));
+}
+
+#[test]
+fn test_require_lossless_rejects_lossy_targets() {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_pixel(8, 8, Rgba([10, 20, 30, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 8, 8, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let strict = ConversionOptionsBuilder::new().require_lossless(true);
+
+    assert!(converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::Png, Some(strict.clone().build()))
+        .is_ok());
+
+    let err = converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, Some(strict.clone().build()))
+        .unwrap_err();
+    assert!(matches!(err, ImageError::UnsupportedOperation { .. }), "{:?}", err);
+    assert!(err.to_string().contains("Lossless output required"), "{}", err);
+
+    let webp = converter
+        .convert_format(
+            &png_data,
+            ImageFormat::Png,
+            ImageFormat::WebP,
+            Some(strict.clone().webp_lossless(true).build()),
+        )
+        .unwrap();
+    assert_eq!(webp.format(), ImageFormat::WebP);
+
+    // 指定质量即选择有损WebP模式
+    assert!(converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::WebP, Some(strict.quality(0.8).build()))
+        .is_err());
+}
+
+#[test]
+fn test_benchmark_formats_sorted_by_size() {
+    // 近似照片：平滑渐变叠加确定性噪声
+    let img = ImageBuffer::<Rgba<u8>, _>::from_fn(96, 64, |x, y| {
+        let noise = (x.wrapping_mul(7919) ^ y.wrapping_mul(104729)) % 17;
+        Rgba([(x * 2 + noise) as u8, (y * 3 + noise) as u8, ((x + y) + noise) as u8, 255])
+    });
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 96, 64, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let mut targets = vec![ImageFormat::Bmp, ImageFormat::Png, ImageFormat::Jpeg];
+    if cfg!(feature = "avif") {
+        targets.push(ImageFormat::Avif);
+    }
+
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let results = converter
+        .benchmark_formats(&png_data, ImageFormat::Png, &targets, None)
+        .unwrap();
+
+    assert_eq!(results.len(), targets.len());
+    assert!(results.windows(2).all(|pair| pair[0].output_size <= pair[1].output_size));
+
+    let find = |format| results.iter().find(|b| b.format == format).unwrap();
+    let bmp = find(ImageFormat::Bmp);
+    assert_eq!(bmp.output_size, 54 + 96 * 64 * 3);
+    assert!(bmp.quality.as_ref().unwrap().psnr() > 40.0);
+    assert!(find(ImageFormat::Jpeg).quality.is_some());
+
+    if cfg!(feature = "avif") {
+        let avif = find(ImageFormat::Avif);
+        assert!(avif.output_size < bmp.output_size);
+        // AVIF尚不支持解码，无法回读比较
+        assert!(avif.quality.is_none());
+    }
+}
+
+#[test]
+fn test_decoded_image_from_external_rgba() {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_fn(4, 3, |x, y| Rgba([x as u8 * 60, y as u8 * 90, 200, 255]));
+
+    let decoded = DecodedImage::from_rgba(4, 3, img.as_raw(), ImageFormat::Jpeg, 1234).unwrap();
+    assert_eq!(decoded.dimensions().width, 4);
+    assert_eq!(decoded.source_format(), Some(ImageFormat::Jpeg));
+    assert_eq!(decoded.source_size(), 1234);
+
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let png = converter.encode_buffer(&decoded, ImageFormat::Png, None).unwrap();
+    let roundtrip = image::load_from_memory(png.data()).unwrap().to_rgba8();
+    assert_eq!(roundtrip.as_raw(), img.as_raw());
+
+    // 长度与尺寸不符时拒绝
+    assert!(matches!(
+        DecodedImage::from_rgba(4, 4, img.as_raw(), ImageFormat::Jpeg, 1234),
+        Err(ImageError::InvalidParameters { .. })
+    ));
+}
+
+#[test]
+fn test_png_to_binary_ppm_roundtrip() {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_fn(5, 3, |x, y| Rgba([x as u8 * 50, y as u8 * 80, 7, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 5, 3, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let ppm = convert_format(&png_data, ImageFormat::Png, ImageFormat::Pnm, None).unwrap();
+    assert!(ppm.data().starts_with(b"P6"));
+    assert_eq!(detect_format(ppm.data()).unwrap(), ImageFormat::Pnm);
+
+    // 重新读取PPM并转回PNG，像素保持不变
+    let png = convert_format(ppm.data(), ImageFormat::Pnm, ImageFormat::Png, None).unwrap();
+    let roundtrip = image::load_from_memory(png.data()).unwrap().to_rgba8();
+    assert_eq!(roundtrip.as_raw(), img.as_raw());
+
+    // ASCII子类型输出P3，非法子类型被拒绝
+    let ascii = ConversionOptionsBuilder::new().custom_param("pnm_subtype", "ascii").build();
+    let ppm = convert_format(&png_data, ImageFormat::Png, ImageFormat::Pnm, Some(ascii)).unwrap();
+    assert!(ppm.data().starts_with(b"P3"));
+
+    let invalid = ConversionOptionsBuilder::new().custom_param("pnm_subtype", "raw").build();
+    assert!(convert_format(&png_data, ImageFormat::Png, ImageFormat::Pnm, Some(invalid)).is_err());
+}
+
+#[test]
+fn test_tiff_compression_option() {
+    // 大面积纯色块，压缩效果明显
+    let img = ImageBuffer::<Rgba<u8>, _>::from_fn(64, 64, |x, _| Rgba([(x / 16) as u8 * 60, 20, 200, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 64, 64, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let encode = |compression: &str| {
+        let options = ConversionOptionsBuilder::new()
+            .custom_param("tiff_compression", compression)
+            .build();
+        convert_format(&png_data, ImageFormat::Png, ImageFormat::Tiff, Some(options))
+    };
+
+    let uncompressed = encode("none").unwrap();
+    let lzw = encode("lzw").unwrap();
+    assert!(lzw.data().len() < uncompressed.data().len());
+
+    // 压缩后像素不变
+    let decoded = image::load_from_memory(lzw.data()).unwrap().to_rgba8();
+    assert_eq!(decoded.as_raw(), img.as_raw());
+
+    assert!(matches!(
+        encode("lzma"),
+        Err(rustimage_core::ImageError::InvalidParameters { .. })
+    ));
+}
+
+#[test]
+fn test_tiff_byte_order_option() {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_fn(24, 16, |x, y| Rgba([x as u8 * 10, y as u8 * 15, 90, 200]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 24, 16, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let encode = |byte_order: &str, compression: &str| {
+        let options = ConversionOptionsBuilder::new()
+            .custom_param("tiff_byte_order", byte_order)
+            .custom_param("tiff_compression", compression)
+            .build();
+        convert_format(&png_data, ImageFormat::Png, ImageFormat::Tiff, Some(options))
+    };
+
+    for compression in ["none", "lzw"] {
+        let big = encode("MM", compression).unwrap();
+        assert_eq!(&big.data()[..4], &[0x4D, 0x4D, 0x00, 0x2A]);
+        assert_eq!(detect_format(big.data()).unwrap(), ImageFormat::Tiff);
+        let decoded = image::load_from_memory(big.data()).unwrap().to_rgba8();
+        assert_eq!(decoded.as_raw(), img.as_raw());
+
+        let little = encode("II", compression).unwrap();
+        assert_eq!(&little.data()[..4], &[0x49, 0x49, 0x2A, 0x00]);
+    }
+
+    // 多页文档的整条IFD链都被转换
+    let converter = FormatConverter::with_defaults().unwrap();
+    let page = converter.decode_to_buffer(&png_data, ImageFormat::Png).unwrap();
+    let pages = [page.buffer().clone(), page.buffer().clone()];
+    let options = ConversionOptionsBuilder::new().custom_param("tiff_byte_order", "MM").build();
+    let multipage = converter.encode_tiff_pages(&pages, Some(options)).unwrap();
+    assert!(multipage.starts_with(b"MM"));
+    assert_eq!(converter.decode_tiff_pages(&multipage).unwrap().len(), 2);
+
+    assert!(matches!(encode("BE", "none"), Err(ImageError::InvalidParameters { .. })));
+}
+
+#[test]
+fn test_encode_ico_multisize() {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_fn(300, 200, |x, y| Rgba([x as u8, y as u8, 128, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 300, 200, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let converter = FormatConverter::with_defaults().unwrap();
+    let decoded = converter.decode_to_buffer(&png_data, ImageFormat::Png).unwrap();
+
+    let sizes = [16, 32, 48, 256];
+    let ico = converter.encode_ico_multisize(decoded.buffer(), &sizes).unwrap();
+    assert_eq!(ico.format(), ImageFormat::Ico);
+    assert_eq!(detect_format(ico.data()).unwrap(), ImageFormat::Ico);
+
+    // ICONDIR头: 保留字(2) + 类型(2) + 条目数(2)，每个目录项首字节为宽度（256记为0）
+    let data = ico.data();
+    let entry_count = u16::from_le_bytes([data[4], data[5]]);
+    assert_eq!(entry_count as usize, sizes.len());
+    let widths: Vec<u8> = (0..sizes.len()).map(|i| data[6 + i * 16]).collect();
+    assert_eq!(widths, [16, 32, 48, 0]);
+
+    // 超出上限、零尺寸和重复尺寸被拒绝
+    for invalid in [&[16, 512][..], &[0][..], &[32, 32][..], &[][..]] {
+        assert!(converter.encode_ico_multisize(decoded.buffer(), invalid).is_err(), "{:?}", invalid);
+    }
+}
+
+/// 遍历JPEG段，返回第一个SOF标记
+fn jpeg_sof_marker(data: &[u8]) -> Option<u16> {
+    let mut pos = 2;
+    while pos + 4 <= data.len() && data[pos] == 0xFF {
+        let marker = u16::from_be_bytes([data[pos], data[pos + 1]]);
+        if (0xFFC0..=0xFFCF).contains(&marker) && ![0xFFC4, 0xFFC8, 0xFFCC].contains(&marker) {
+            return Some(marker);
+        }
+        let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        pos += 2 + length;
+    }
+    None
+}
+
+#[test]
+fn test_progressive_jpeg_option() {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_fn(40, 24, |x, y| Rgba([x as u8 * 6, y as u8 * 10, 80, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 40, 24, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let baseline = convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, None).unwrap();
+    assert_eq!(jpeg_sof_marker(baseline.data()), Some(0xFFC0));
+
+    let options = ConversionOptionsBuilder::new().progressive(true).build();
+    let progressive = convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, Some(options)).unwrap();
+    assert_eq!(jpeg_sof_marker(progressive.data()), Some(0xFFC2));
+
+    // 渐进式JPEG可以正常解码
+    let decoded = image::load_from_memory(progressive.data()).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (40, 24));
+}
+
+#[test]
+fn test_is_conversion_lossy() {
+    let converter = FormatConverter::with_defaults().unwrap();
+    let defaults = ConversionOptionsBuilder::new().build();
+
+    assert!(converter.is_conversion_lossy(ImageFormat::Png, ImageFormat::Jpeg, &defaults));
+    assert!(converter.is_conversion_supported(ImageFormat::Png, ImageFormat::Jpeg));
+
+    let lossless_webp = ConversionOptionsBuilder::new().webp_lossless(true).build();
+    assert!(!converter.is_conversion_lossy(ImageFormat::Png, ImageFormat::WebP, &lossless_webp));
+    let lossy_webp = ConversionOptionsBuilder::new().quality(0.8).build();
+    assert!(converter.is_conversion_lossy(ImageFormat::Png, ImageFormat::WebP, &lossy_webp));
+
+    assert!(!converter.is_conversion_lossy(ImageFormat::Png, ImageFormat::Png, &defaults));
+    let resize = ConversionOptionsBuilder::new().resize(16, 16).build();
+    assert!(converter.is_conversion_lossy(ImageFormat::Png, ImageFormat::Png, &resize));
+    let grayscale = ConversionOptionsBuilder::new().grayscale(true).build();
+    assert!(converter.is_conversion_lossy(ImageFormat::Png, ImageFormat::Tiff, &grayscale));
+
+    // 指定的像素格式丢弃颜色或透明度
+    use rustimage_core::codecs::PixelFormat;
+    let gray8 = ConversionOptionsBuilder::new().target_pixel_format(PixelFormat::Gray8).build();
+    assert!(converter.is_conversion_lossy(ImageFormat::Png, ImageFormat::Png, &gray8));
+    let rgb8 = ConversionOptionsBuilder::new().target_pixel_format(PixelFormat::Rgb8).build();
+    assert!(converter.is_conversion_lossy(ImageFormat::Png, ImageFormat::Png, &rgb8));
+    // 来源没有透明度时去掉alpha通道不丢失信息
+    assert!(!converter.is_conversion_lossy(ImageFormat::Jpeg, ImageFormat::Png, &rgb8));
+    let rgba8 = ConversionOptionsBuilder::new().target_pixel_format(PixelFormat::Rgba8).build();
+    assert!(!converter.is_conversion_lossy(ImageFormat::Png, ImageFormat::Png, &rgba8));
+}
+
+#[test]
+fn test_resize_option() {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_pixel(40, 30, Rgba([10, 20, 30, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 40, 30, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let options = ConversionOptionsBuilder::new().resize(20, 10).build();
+    let result = convert_format(&png_data, ImageFormat::Png, ImageFormat::Png, Some(options)).unwrap();
+    assert_eq!((result.dimensions().width, result.dimensions().height), (20, 10));
+
+    let decoded = image::load_from_memory(result.data()).unwrap().to_rgba8();
+    assert_eq!(decoded.dimensions(), (20, 10));
+    assert_eq!(decoded.get_pixel(5, 5).0, [10, 20, 30, 255]);
+}
+
+/// 生成缩略图并校验边界与宽高比
+fn assert_thumbnail_fits(width: u32, height: u32, max_w: u32, max_h: u32) {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_pixel(width, height, Rgba([90, 120, 150, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), width, height, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let result = converter
+        .make_thumbnail(&png_data, ImageFormat::Png, ImageFormat::Jpeg, max_w, max_h, None)
+        .unwrap();
+    let dims = result.dimensions();
+    assert!(dims.width <= max_w && dims.height <= max_h, "{}×{}", dims.width, dims.height);
+
+    // 按输出宽度推算的高度与实际高度相差不超过一个像素
+    let expected_height = dims.width as f64 * height as f64 / width as f64;
+    assert!((dims.height as f64 - expected_height).abs() <= 1.0, "{}×{}", dims.width, dims.height);
+
+    let decoded = image::load_from_memory(result.data()).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (dims.width, dims.height));
+}
+
+#[test]
+fn test_thumbnail_wide_image() {
+    assert_thumbnail_fits(400, 100, 64, 64);
+}
+
+#[test]
+fn test_thumbnail_tall_image() {
+    assert_thumbnail_fits(90, 300, 100, 50);
+}
+
+#[test]
+fn test_thumbnail_never_upscales() {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_pixel(20, 10, Rgba([0, 0, 0, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 20, 10, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let result = converter
+        .make_thumbnail(&png_data, ImageFormat::Png, ImageFormat::Png, 200, 200, None)
+        .unwrap();
+    assert_eq!((result.dimensions().width, result.dimensions().height), (20, 10));
+}
+
+#[test]
+fn test_allow_upscale_controls_thumbnail_and_resize() {
+    let img = ImageBuffer::<Rgba<u8>, _>::from_pixel(50, 25, Rgba([30, 60, 90, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 50, 25, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let dims = |result: &rustimage_core::ConvertedImage| (result.dimensions().width, result.dimensions().height);
+
+    let capped = ConversionOptionsBuilder::new().allow_upscale(false).build();
+    let result = converter
+        .make_thumbnail(&png_data, ImageFormat::Png, ImageFormat::Png, 200, 200, Some(capped))
+        .unwrap();
+    assert_eq!(dims(&result), (50, 25));
+
+    let upscale = ConversionOptionsBuilder::new().allow_upscale(true).build();
+    let result = converter
+        .make_thumbnail(&png_data, ImageFormat::Png, ImageFormat::Png, 200, 200, Some(upscale))
+        .unwrap();
+    assert_eq!(dims(&result), (200, 100));
+
+    // 精确缩放默认按目标宽高比限制在原图以内，并给出警告
+    let options = ConversionOptionsBuilder::new().resize(200, 50).build();
+    let result = converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::Png, Some(options))
+        .unwrap();
+    assert_eq!(dims(&result), (50, 12));
+    assert!(result.warnings().iter().any(|w| matches!(w, rustimage_core::ConversionWarning::UpscaleCapped { .. })));
+
+    let options = ConversionOptionsBuilder::new().resize(200, 50).allow_upscale(true).build();
+    let result = converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::Png, Some(options))
+        .unwrap();
+    assert_eq!(dims(&result), (200, 50));
+}
+
+#[test]
+fn test_conversion_matrix() {
+    let converter = FormatConverter::with_defaults().unwrap();
+    let matrix = converter.conversion_matrix();
+    let formats = converter.get_supported_formats();
+    assert_eq!(matrix.len(), formats.len() * formats.len());
+
+    let entry = |from, to| *matrix.iter().find(|c| c.from == from && c.to == to).unwrap();
+    let png_to_png = entry(ImageFormat::Png, ImageFormat::Png);
+    assert!(png_to_png.supported && png_to_png.lossless_capable);
+    assert!(!entry(ImageFormat::Png, ImageFormat::Jpeg).lossless_capable);
+    assert!(!entry(ImageFormat::Png, ImageFormat::Gif).lossless_capable);
+
+    // 可解码的格式都能转换为PNG
+    for capability in matrix.iter().filter(|c| c.supported) {
+        assert!(entry(capability.from, ImageFormat::Png).supported, "{:?} -> PNG", capability.from);
+    }
+    for format in [ImageFormat::Jpeg, ImageFormat::Gif, ImageFormat::WebP, ImageFormat::Tiff, ImageFormat::Ico] {
+        assert!(entry(format, ImageFormat::Png).supported, "{:?} -> PNG", format);
+    }
+}
+
+#[test]
+fn test_targets_for_lists_enabled_formats() {
+    let converter = FormatConverter::with_defaults().unwrap();
+    let supported = converter.get_supported_formats();
+
+    for from in [ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::Gif, ImageFormat::WebP] {
+        let targets = converter.targets_for(from);
+        assert!(targets.contains(&ImageFormat::Png), "{:?} -> PNG", from);
+        assert!(targets.iter().all(|to| supported.contains(to)));
+        assert!(targets.iter().all(|&to| converter.is_conversion_supported(from, to)));
+    }
+
+    // 未启用特性的格式既不能作为目标，也不能作为源
+    #[cfg(not(feature = "heif"))]
+    {
+        assert!(!converter.targets_for(ImageFormat::Png).contains(&ImageFormat::Heic));
+        assert!(converter.targets_for(ImageFormat::Heic).is_empty());
+    }
+    #[cfg(not(feature = "jxl"))]
+    assert!(!converter.targets_for(ImageFormat::Png).contains(&ImageFormat::Jxl));
+}
+
+#[test]
+fn test_estimate_output_size() {
+    // 带纹理的渐变，接近照片的压缩特性
+    let img = ImageBuffer::from_fn(640, 480, |x, y| {
+        let noise = (x.wrapping_mul(7919) ^ y.wrapping_mul(104729)) % 23;
+        image::Rgb([(x * 255 / 640) as u8, (y * 255 / 480) as u8, (noise * 8) as u8])
+    });
+    let mut jpeg_data = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg_data, 85)
+        .write_image(img.as_raw(), 640, 480, image::ColorType::Rgb8)
+        .expect("Failed to encode JPEG");
+
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let estimate = converter
+        .estimate_output_size(&jpeg_data, ImageFormat::Jpeg, ImageFormat::Png, None)
+        .unwrap();
+    let actual = converter
+        .convert_format(&jpeg_data, ImageFormat::Jpeg, ImageFormat::Png, None)
+        .unwrap()
+        .data()
+        .len() as f64;
+
+    assert!(estimate.min <= estimate.likely && estimate.likely <= estimate.max);
+    let factor = estimate.likely as f64 / actual;
+    assert!((0.5..=2.0).contains(&factor), "likely {} vs actual {}", estimate.likely, actual);
+
+    // 样本范围内的小图直接编码，估算是精确值
+    let small = ImageBuffer::<Rgba<u8>, _>::from_pixel(32, 32, Rgba([1, 2, 3, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(small.as_raw(), 32, 32, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+    let estimate = converter
+        .estimate_output_size(&png_data, ImageFormat::Png, ImageFormat::Png, None)
+        .unwrap();
+    let actual = converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::Png, None)
+        .unwrap();
+    assert_eq!(estimate.likely, actual.data().len() as u64);
+    assert_eq!(estimate.min, estimate.max);
+}
+
+#[test]
+fn test_compression_ratio_vs_raw() {
+    // 纯色图像压缩效果显著
+    let img = ImageBuffer::<Rgba<u8>, _>::from_pixel(64, 64, Rgba([200, 100, 50, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 64, 64, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let result = convert_format(&png_data, ImageFormat::Png, ImageFormat::Png, None).unwrap();
+    let ratio = result.compression_ratio_vs_raw();
+    assert!(ratio > 0.0 && ratio < 1.0, "ratio {}", ratio);
+    assert_eq!(ratio, result.converted_size() as f32 / (64.0 * 64.0 * 4.0));
+}
+
+#[test]
+fn test_chroma_subsampling_preserves_stripe_edges() {
+    use rustimage_core::Subsampling;
+
+    // 3像素宽的红蓝竖条纹，边界与2像素色度块错开
+    let stripe = |x: u32| (x / 3).is_multiple_of(2);
+    let img = ImageBuffer::from_fn(96, 32, |x, _| {
+        if stripe(x) { Rgba([255u8, 0, 0, 255]) } else { Rgba([0, 0, 255, 255]) }
+    });
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 96, 32, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    // 条纹边界两侧像素的平均绝对误差
+    let edge_error = |subsampling: Subsampling| -> f64 {
+        let options = ConversionOptionsBuilder::new()
+            .quality(0.9)
+            .chroma_subsampling(subsampling)
+            .build();
+        let result = convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, Some(options)).unwrap();
+        let decoded = image::load_from_memory(result.data()).unwrap().to_rgb8();
+
+        let (mut total, mut count) = (0u64, 0u64);
+        for (x, _, pixel) in decoded.enumerate_pixels() {
+            let on_edge = (x > 0 && stripe(x - 1) != stripe(x)) || (x < 95 && stripe(x + 1) != stripe(x));
+            if !on_edge {
+                continue;
+            }
+            let expected = if stripe(x) { [255, 0, 0] } else { [0, 0, 255] };
+            total += pixel.0.iter().zip(expected).map(|(&a, b)| (a as i32 - b).unsigned_abs() as u64).sum::<u64>();
+            count += 3;
+        }
+        total as f64 / count as f64
+    };
+
+    let full = edge_error(Subsampling::S444);
+    let half = edge_error(Subsampling::S420);
+    assert!(full < half, "4:4:4 edge error {} should be below 4:2:0 {}", full, half);
+}
+
+#[test]
+fn test_same_format_passthrough_returns_input() {
+    let img = ImageBuffer::from_fn(40, 24, |x, y| Rgba([x as u8 * 6, y as u8 * 10, 128u8, 255]));
+    let mut jpeg_data = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg_data, 70)
+        .write_image(img.as_raw(), 40, 24, image::ColorType::Rgba8)
+        .expect("Failed to encode JPEG");
+
+    let options = ConversionOptionsBuilder::new().passthrough(true).build();
+    let result = convert_format(&jpeg_data, ImageFormat::Jpeg, ImageFormat::Jpeg, Some(options)).unwrap();
+    assert_eq!(result.data(), &jpeg_data[..]);
+    assert_eq!((result.dimensions().width, result.dimensions().height), (40, 24));
+
+    // 直通需显式启用：不提供选项时完整解码，截断的数据不会被原样返回
+    let result = convert_format(&jpeg_data, ImageFormat::Jpeg, ImageFormat::Jpeg, None).unwrap();
+    assert_ne!(result.data(), &jpeg_data[..]);
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 40, 24, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+    let truncated = &png_data[..png_data.len() / 2];
+    assert!(convert_format(truncated, ImageFormat::Png, ImageFormat::Png, None).is_err());
+
+    // 请求像素变换时仍然重新编码
+    let options = ConversionOptionsBuilder::new().passthrough(true).grayscale(true).build();
+    let result = convert_format(&jpeg_data, ImageFormat::Jpeg, ImageFormat::Jpeg, Some(options)).unwrap();
+    assert_ne!(result.data(), &jpeg_data[..]);
+
+    // 显式选项默认不直通
+    let options = ConversionOptionsBuilder::new().quality(0.5).build();
+    let result = convert_format(&jpeg_data, ImageFormat::Jpeg, ImageFormat::Jpeg, Some(options)).unwrap();
+    assert_ne!(result.data(), &jpeg_data[..]);
+}
+
+/// 带GPS纬度的EXIF APP1段 - 小端TIFF，IFD0只有指向GPS IFD的0x8825项
+fn gps_exif_segment() -> Vec<u8> {
+    let mut tiff = Vec::new();
+    tiff.extend_from_slice(b"II*\0");
+    tiff.extend_from_slice(&8u32.to_le_bytes());
+    // IFD0: GPSInfo -> 偏移26
+    tiff.extend_from_slice(&1u16.to_le_bytes());
+    tiff.extend_from_slice(&[0x25, 0x88, 4, 0]);
+    tiff.extend_from_slice(&1u32.to_le_bytes());
+    tiff.extend_from_slice(&26u32.to_le_bytes());
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    // GPS IFD: GPSLatitude（3个RATIONAL）-> 偏移44
+    tiff.extend_from_slice(&1u16.to_le_bytes());
+    tiff.extend_from_slice(&[0x02, 0x00, 5, 0]);
+    tiff.extend_from_slice(&3u32.to_le_bytes());
+    tiff.extend_from_slice(&44u32.to_le_bytes());
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    for value in [48u32, 51, 30] {
+        tiff.extend_from_slice(&value.to_le_bytes());
+        tiff.extend_from_slice(&1u32.to_le_bytes());
+    }
+
+    let mut segment = vec![0xFF, 0xE1];
+    segment.extend_from_slice(&((2 + 6 + tiff.len()) as u16).to_be_bytes());
+    segment.extend_from_slice(b"Exif\0\0");
+    segment.extend_from_slice(&tiff);
+    segment
+}
+
+/// JPEG中是否存在带GPS IFD的EXIF段 - 扫描到SOS为止
+fn jpeg_has_exif_gps(data: &[u8]) -> bool {
+    let mut pos = 2;
+    while pos + 4 <= data.len() && data[pos] == 0xFF && data[pos + 1] != 0xDA {
+        let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let segment = &data[pos + 4..pos + 2 + length];
+        if data[pos + 1] == 0xE1 && segment.starts_with(b"Exif\0\0") {
+            let tiff = &segment[6..];
+            let ifd0 = u32::from_le_bytes(tiff[4..8].try_into().unwrap()) as usize;
+            let entries = u16::from_le_bytes([tiff[ifd0], tiff[ifd0 + 1]]) as usize;
+            let has_gps = (0..entries).any(|i| tiff[ifd0 + 2 + i * 12..][..2] == [0x25, 0x88]);
+            if has_gps {
+                return true;
+            }
+        }
+        pos += 2 + length;
+    }
+    false
+}
+
+#[test]
+fn test_strip_all_metadata_removes_gps() {
+    let img = ImageBuffer::from_fn(32, 24, |x, y| Rgba([x as u8 * 8, y as u8 * 10, 120, 255]));
+    let rgb = image::DynamicImage::ImageRgba8(img).to_rgb8();
+    let mut encoded = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut encoded, 90)
+        .write_image(rgb.as_raw(), 32, 24, image::ColorType::Rgb8)
+        .expect("Failed to encode JPEG");
+    let mut jpeg_data = encoded[..2].to_vec();
+    jpeg_data.extend_from_slice(&gps_exif_segment());
+    jpeg_data.extend_from_slice(&encoded[2..]);
+    assert!(jpeg_has_exif_gps(&jpeg_data));
+
+    // 直通时GPS随原始数据保留
+    let options = ConversionOptionsBuilder::new().passthrough(true).build();
+    let result = convert_format(&jpeg_data, ImageFormat::Jpeg, ImageFormat::Jpeg, Some(options)).unwrap();
+    assert!(jpeg_has_exif_gps(result.data()));
+
+    // 强制清除时直通只剔除EXIF段，扫描数据保持不变
+    let options = ConversionOptionsBuilder::new().passthrough(true).strip_all_metadata(true).build();
+    let result = convert_format(&jpeg_data, ImageFormat::Jpeg, ImageFormat::Jpeg, Some(options)).unwrap();
+    assert!(!jpeg_has_exif_gps(result.data()));
+    assert_eq!(result.data(), &encoded[..]);
+
+    // 重新编码同样不含EXIF
+    let options = ConversionOptionsBuilder::new().quality(0.8).strip_all_metadata(true).build();
+    let result = convert_format(&jpeg_data, ImageFormat::Jpeg, ImageFormat::Jpeg, Some(options)).unwrap();
+    assert!(!jpeg_has_exif_gps(result.data()));
+    assert!(!result.data().windows(6).any(|window| window == b"Exif\0\0"));
+    assert_eq!((result.dimensions().width, result.dimensions().height), (32, 24));
+}
+
+#[test]
+fn test_validate_decodable_rejects_truncated_png() {
+    let img = ImageBuffer::from_fn(64, 64, |x, y| Rgba([x as u8 * 4, y as u8 * 4, (x ^ y) as u8, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 64, 64, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+    let converter = FormatConverter::with_defaults().unwrap();
+
+    let dims = converter.validate_decodable(&png_data, None).unwrap();
+    assert_eq!((dims.width, dims.height), (64, 64));
+
+    // 截断后签名检测仍然通过，完整解码失败
+    let truncated = &png_data[..png_data.len() / 2];
+    assert_eq!(detect_format(truncated).unwrap(), ImageFormat::Png);
+    let error = converter.validate_decodable(truncated, Some(ImageFormat::Png)).unwrap_err();
+    assert!(matches!(error, ImageError::DecodeError { .. }), "{:?}", error);
+
+    // 检测结果与期望格式不符
+    let error = converter.validate_decodable(&png_data, Some(ImageFormat::Jpeg)).unwrap_err();
+    assert!(matches!(error, ImageError::InvalidFormat { .. }), "{:?}", error);
+}
+
+#[test]
+fn test_png_interlaced_round_trips() {
+    use rustimage_core::{CompressionStrategy, PngFilter};
+
+    // 奇数尺寸使Adam7各趟的子图像大小不一
+    let img = ImageBuffer::from_fn(37, 23, |x, y| Rgba([(x * 7) as u8, (y * 11) as u8, (x * y) as u8, ((x + y) * 5) as u8]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 37, 23, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let options = ConversionOptionsBuilder::new().png_interlaced(true).build();
+    let result = convert_format(&png_data, ImageFormat::Png, ImageFormat::Png, Some(options)).unwrap();
+    // IHDR的隔行标志位于文件偏移28
+    assert_eq!(result.data()[28], 1);
+    let decoded = image::load_from_memory(result.data()).unwrap().to_rgba8();
+    assert_eq!(decoded.as_raw(), img.as_raw());
+
+    // 各滤波器和压缩策略同样无损
+    let filters = [PngFilter::None, PngFilter::Sub, PngFilter::Up, PngFilter::Average, PngFilter::Paeth, PngFilter::Adaptive];
+    let strategies = [CompressionStrategy::Filtered, CompressionStrategy::HuffmanOnly, CompressionStrategy::Rle, CompressionStrategy::Fixed];
+    for (filter, strategy) in filters.into_iter().zip(strategies.into_iter().cycle()) {
+        let options = ConversionOptionsBuilder::new()
+            .png_filter(filter)
+            .png_compression_strategy(strategy)
+            .build();
+        let result = convert_format(&png_data, ImageFormat::Png, ImageFormat::Png, Some(options)).unwrap();
+        assert_eq!(result.data()[28], 0);
+        let decoded = image::load_from_memory(result.data()).unwrap().to_rgba8();
+        assert_eq!(decoded.as_raw(), img.as_raw(), "{:?} / {:?}", filter, strategy);
+    }
+}
+
+#[test]
+fn test_png_compression_level_changes_output() {
+    // 平滑渐变加少量纹理，使压缩力度影响体积
+    let img = ImageBuffer::from_fn(256, 256, |x, y| Rgba([x as u8, y as u8, ((x * y) / 64) as u8, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 256, 256, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let encode = |level: u8| {
+        let options = ConversionOptionsBuilder::new().compression_level(level).build();
+        convert_format(&png_data, ImageFormat::Png, ImageFormat::Png, Some(options)).unwrap()
+    };
+    let fast = encode(1);
+    let best = encode(9);
+
+    assert_ne!(fast.data(), best.data());
+    assert!(fast.data().len() > best.data().len(), "{} vs {}", fast.data().len(), best.data().len());
+    for converted in [&fast, &best] {
+        let decoded = image::load_from_memory(converted.data()).unwrap().to_rgba8();
+        assert_eq!(decoded.as_raw(), img.as_raw());
+    }
+}
+
+#[test]
+fn test_palette_png_smaller_than_truecolor() {
+    let colors = [
+        Rgba([220u8, 40, 40, 255]),
+        Rgba([40, 180, 60, 255]),
+        Rgba([30, 60, 200, 255]),
+        Rgba([0, 0, 0, 0]),
+    ];
+    // 四种颜色（含完全透明）伪随机分布，避免整块纯色
+    let img = ImageBuffer::from_fn(64, 64, |x, y| colors[((x * 7 + y * 13 + x * y) % 4) as usize]);
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 64, 64, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    let truecolor = convert_format(&png_data, ImageFormat::Png, ImageFormat::Png, Some(ConversionOptionsBuilder::new().build())).unwrap();
+    let options = ConversionOptionsBuilder::new().palette_colors(Some(4)).build();
+    let indexed = convert_format(&png_data, ImageFormat::Png, ImageFormat::Png, Some(options)).unwrap();
+
+    // IHDR：位深偏移24，颜色类型偏移25（3为索引色）
+    assert_eq!(indexed.data()[24], 2);
+    assert_eq!(indexed.data()[25], 3);
+    assert!(indexed.data().len() < truecolor.data().len(), "{} vs {}", indexed.data().len(), truecolor.data().len());
+    let decoded = image::load_from_memory(indexed.data()).unwrap().to_rgba8();
+    assert_eq!(decoded.as_raw(), img.as_raw());
+
+    // 不支持索引色的格式
+    let options = ConversionOptionsBuilder::new().palette_colors(Some(4)).build();
+    let error = convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, Some(options)).unwrap_err();
+    assert!(matches!(error, ImageError::UnsupportedOperation { .. }), "{:?}", error);
+}
+
+#[test]
+fn test_palette_quantizes_many_colors() {
+    let img = ImageBuffer::from_fn(48, 48, |x, y| Rgba([(x * 5) as u8, (y * 5) as u8, 128u8, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 48, 48, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+
+    for (to, interlaced) in [(ImageFormat::Png, false), (ImageFormat::Png, true), (ImageFormat::Gif, false)] {
+        let options = ConversionOptionsBuilder::new()
+            .palette_colors(Some(16))
+            .png_interlaced(interlaced)
+            .build();
+        let result = convert_format(&png_data, ImageFormat::Png, to, Some(options)).unwrap();
+        let decoded = image::load_from_memory(result.data()).unwrap().to_rgba8();
+
+        let mut distinct: Vec<_> = decoded.pixels().map(|p| p.0).collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+        assert!(distinct.len() <= 16, "{:?}: {} colors", to, distinct.len());
+
+        // 中位切分后每个像素都接近原色
+        let max_error = decoded
+            .pixels()
+            .zip(img.pixels())
+            .map(|(a, b)| a.0.iter().zip(b.0).map(|(&x, y)| (x as i32 - y as i32).abs()).max().unwrap())
+            .max()
+            .unwrap();
+        assert!(max_error <= 40, "{:?}: max channel error {}", to, max_error);
+    }
+}
+
+#[test]
+fn test_multipage_tiff_round_trip() {
+    use rustimage_core::codecs::{ImageBuffer as CoreBuffer, PixelFormat};
+    use rustimage_core::Rgba as CoreRgba;
+
+    // 三页尺寸和颜色各不相同
+    let pages: Vec<CoreBuffer<CoreRgba<u8>>> = [(16, 8, 10u8), (12, 12, 120), (8, 20, 240)]
+        .into_iter()
+        .map(|(width, height, shade)| {
+            let pixels = (0..width * height)
+                .map(|i| CoreRgba { r: shade, g: (i % 256) as u8, b: 255 - shade, a: 255 })
+                .collect();
+            CoreBuffer::from_raw(width, height, pixels, PixelFormat::Rgba8).unwrap()
+        })
+        .collect();
+
+    let converter = FormatConverter::with_defaults().unwrap();
+    let tiff_data = converter.encode_tiff_pages(&pages, None).unwrap();
+    assert_eq!(detect_format(&tiff_data).unwrap(), ImageFormat::Tiff);
+
+    let decoded = converter.decode_tiff_pages(&tiff_data).unwrap();
+    assert_eq!(decoded.len(), 3);
+    for (page, original) in decoded.iter().zip(&pages) {
+        assert_eq!(page.dimensions(), original.dimensions());
+        assert_eq!(page.as_slice(), original.as_slice());
+    }
+
+    // 单页TIFF返回一页，普通转换仍取第一页
+    let first = converter.decode_to_buffer(&tiff_data, ImageFormat::Tiff).unwrap();
+    assert_eq!(first.buffer().as_slice(), pages[0].as_slice());
+    let single = converter.encode_tiff_pages(&pages[1..2], None).unwrap();
+    assert_eq!(converter.decode_tiff_pages(&single).unwrap().len(), 1);
+}
+
+#[test]
+fn test_options_try_build_reports_conflicts() {
+    let details = |builder: ConversionOptionsBuilder| -> String {
+        match builder.try_build() {
+            Err(ImageError::InvalidParameters { details }) => details,
+            other => panic!("expected InvalidParameters, got {:?}", other),
+        }
+    };
+
+    assert_eq!(
+        details(ConversionOptionsBuilder::new().quality(1.5)),
+        "quality 1.5 is outside [0.0, 1.0]"
+    );
+    assert_eq!(
+        details(ConversionOptionsBuilder::new().compression_level(12)),
+        "compression_level 12 exceeds 9"
+    );
+    assert_eq!(
+        details(ConversionOptionsBuilder::new().require_lossless(true).quality(0.8)),
+        "require_lossless conflicts with quality 0.8 below 1.0"
+    );
+    assert_eq!(
+        details(ConversionOptionsBuilder::new().require_lossless(true).palette_colors(Some(16))),
+        "require_lossless conflicts with palette_colors quantization"
+    );
+    assert_eq!(
+        details(ConversionOptionsBuilder::new().require_lossless(true).webp_lossless(false)),
+        "require_lossless conflicts with webp_lossless(false)"
+    );
+    assert_eq!(
+        details(ConversionOptionsBuilder::new().resize(64, 32)),
+        "resize to 64×32 conflicts with preserve_dimensions"
+    );
+    assert_eq!(
+        details(ConversionOptionsBuilder::new().preserve_dimensions(false).resize(0, 32)),
+        "resize target 0×32 is empty"
+    );
+    assert_eq!(
+        details(ConversionOptionsBuilder::new().auto_contrast(60.0)),
+        "auto_contrast 60 is outside [0, 50)"
+    );
+    assert_eq!(
+        details(ConversionOptionsBuilder::new().palette_colors(Some(300))),
+        "palette_colors 300 is outside [2, 256]"
+    );
+
+    // 多处问题全部列出
+    let all = details(ConversionOptionsBuilder::new().quality(-1.0).resize(10, 10));
+    assert_eq!(all, "quality -1 is outside [0.0, 1.0]; resize to 10×10 conflicts with preserve_dimensions");
+
+    // 无冲突时与宽松构建一致，宽松构建仍然修正越界值
+    let options = ConversionOptionsBuilder::new()
+        .require_lossless(true)
+        .quality(1.0)
+        .preserve_dimensions(false)
+        .resize(64, 32)
+        .try_build()
+        .unwrap();
+    assert_eq!(options.quality(), Some(1.0));
+    assert_eq!(ConversionOptionsBuilder::new().quality(1.5).build().quality(), Some(1.0));
+}
+
+#[test]
+fn test_plan_lists_operations_in_order() {
+    use rustimage_core::{ImageDimensions, PlannedOperation};
+
+    let img = ImageBuffer::from_pixel(80, 60, Rgba([10u8, 20, 30, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 80, 60, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+    let converter = FormatConverter::with_defaults().unwrap();
+
+    let options = ConversionOptionsBuilder::new()
+        .preserve_dimensions(false)
+        .resize(40, 30)
+        .grayscale(true)
+        .quality(0.8)
+        .build();
+    let plan = converter.plan(&png_data, ImageFormat::Png, ImageFormat::Jpeg, Some(options)).unwrap();
+
+    assert_eq!(plan.source_format, ImageFormat::Png);
+    assert_eq!(plan.source_dimensions, ImageDimensions { width: 80, height: 60 });
+    assert_eq!(plan.output_dimensions, ImageDimensions { width: 40, height: 30 });
+    assert!(plan.lossy);
+
+    let position = |matches: fn(&PlannedOperation) -> bool| plan.operations.iter().position(matches).unwrap();
+    let decode = position(|op| matches!(op, PlannedOperation::Decode { .. }));
+    let resize = position(|op| matches!(op, PlannedOperation::Resize { .. }));
+    let flatten = position(|op| matches!(op, PlannedOperation::FlattenAlpha { .. }));
+    let grayscale = position(|op| matches!(op, PlannedOperation::Grayscale));
+    let encode = position(|op| matches!(op, PlannedOperation::Encode { format: ImageFormat::Jpeg }));
+    assert!(decode < resize && resize < flatten && flatten < grayscale && grayscale < encode, "{:?}", plan.operations);
+    assert_eq!(encode, plan.operations.len() - 1);
+
+    // 启用直通的同格式转换
+    let options = ConversionOptionsBuilder::new().passthrough(true).build();
+    let plan = converter.plan(&png_data, ImageFormat::Png, ImageFormat::Png, Some(options)).unwrap();
+    assert_eq!(plan.operations, vec![PlannedOperation::Passthrough]);
+    assert!(!plan.lossy);
+}
+
+#[test]
+fn test_recommended_options_per_format() {
+    let converter = FormatConverter::with_defaults().unwrap();
+
+    let jpeg = converter.recommended_options(ImageFormat::Jpeg);
+    assert_eq!(jpeg.quality(), Some(0.82));
+    assert_eq!(jpeg.is_progressive(), Some(true));
+
+    let png = converter.recommended_options(ImageFormat::Png);
+    assert_eq!(png.compression_level(), Some(9));
+    assert_eq!(png.png_filter(), Some(rustimage_core::PngFilter::Adaptive));
+
+    let webp = converter.recommended_options(ImageFormat::WebP);
+    assert_eq!(webp.quality(), Some(0.8));
+    assert_eq!(webp.custom_param("webp_method"), Some("6"));
+
+    // 推荐选项可以直接用于转换
+    let img = ImageBuffer::<Rgba<u8>, _>::from_pixel(8, 8, Rgba([10, 20, 30, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 8, 8, image::ColorType::Rgba8)
+        .unwrap();
+    let mut converter = converter;
+    for format in [ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::WebP] {
+        let options = converter.recommended_options(format);
+        let converted = converter.convert_format(&png_data, ImageFormat::Png, format, Some(options)).unwrap();
+        assert_eq!(detect_format(converted.data()).unwrap(), format);
+    }
+
+    // 推荐的PNG选项确实改变输出，且不比默认选项大
+    let img = ImageBuffer::from_fn(256, 256, |x, y| Rgba([x as u8, y as u8, ((x * y) / 64) as u8, 255]));
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 256, 256, image::ColorType::Rgba8)
+        .unwrap();
+    let default = converter.convert_format(&png_data, ImageFormat::Png, ImageFormat::Png, None).unwrap();
+    let recommended = converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::Png, Some(converter.recommended_options(ImageFormat::Png)))
+        .unwrap();
+    assert_ne!(recommended.data(), default.data());
+    assert!(recommended.data().len() <= default.data().len(), "{} vs {}", recommended.data().len(), default.data().len());
+}
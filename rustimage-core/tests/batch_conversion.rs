@@ -0,0 +1,280 @@
+//! 批量转换测试
+//!
+//! 测试批量转换的取消控制和线程池
+
+use rustimage_core::{
+    converter::{CancelToken, Cancellation, ConverterConfigBuilder},
+    BatchConvertReport, ConversionTask, FormatConverter, ImageError, ImageFormat, ImageInput,
+};
+use image::{ImageBuffer, Rgba, ImageEncoder};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+fn create_png(width: u32, height: u32) -> Vec<u8> {
+    let img = ImageBuffer::from_pixel(width, height, Rgba([40u8, 80, 120, 255]));
+    let mut png_bytes = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_bytes)
+        .write_image(img.as_raw(), width, height, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+    png_bytes
+}
+
+fn create_batch(count: usize) -> (Vec<ImageInput>, Vec<ConversionTask>) {
+    let images = (0..count)
+        .map(|_| ImageInput::new(create_png(4, 4), ImageFormat::Png))
+        .collect();
+    let tasks = (0..count)
+        .map(|_| ConversionTask {
+            from_format: ImageFormat::Png,
+            to_format: ImageFormat::Jpeg,
+            options: None,
+            id: None,
+        })
+        .collect();
+    (images, tasks)
+}
+
+fn sequential_converter() -> FormatConverter {
+    FormatConverter::new(ConverterConfigBuilder::new().enable_parallel(false).build()).unwrap()
+}
+
+/// 第一次检查之后即报告已取消 - 模拟第一个任务完成后客户端断开
+struct CancelAfterFirstTask {
+    checks: AtomicUsize,
+}
+
+impl Cancellation for CancelAfterFirstTask {
+    fn is_cancelled(&self) -> bool {
+        self.checks.fetch_add(1, Ordering::SeqCst) >= 1
+    }
+}
+
+#[test]
+fn test_cancel_after_first_task_skips_remaining() {
+    let mut converter = sequential_converter();
+    let (images, tasks) = create_batch(4);
+    let cancel = CancelAfterFirstTask { checks: AtomicUsize::new(0) };
+
+    let result = converter.batch_convert_cancellable(images, tasks, &cancel);
+
+    match result {
+        Err(ImageError::OperationCancelled { completed, total, .. }) => {
+            assert_eq!(completed, 1);
+            assert_eq!(total, 4);
+        }
+        other => panic!("expected cancellation, got {:?}", other.map(|r| r.len())),
+    }
+    assert_eq!(converter.get_conversion_statistics().total_conversions, 1);
+}
+
+#[test]
+fn test_cancel_token_before_start() {
+    let token = CancelToken::new();
+    token.clone().cancel();
+    assert!(token.is_cancelled());
+
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let (images, tasks) = create_batch(3);
+
+    let result = converter.batch_convert_cancellable(images, tasks, &token);
+    assert!(matches!(
+        result,
+        Err(ImageError::OperationCancelled { completed: 0, total: 3, .. })
+    ));
+}
+
+#[test]
+fn test_uncancelled_batch_completes() {
+    let mut converter = sequential_converter();
+    let (images, tasks) = create_batch(3);
+    let flag = AtomicBool::new(false);
+
+    let converted = converter.batch_convert_cancellable(images, tasks, &flag).unwrap();
+    assert_eq!(converted.len(), 3);
+    assert!(converted.iter().all(|image| image.format() == ImageFormat::Jpeg));
+}
+
+#[test]
+fn test_progress_callback_sequential() {
+    let mut converter = sequential_converter();
+    let (images, tasks) = create_batch(3);
+    let mut progress = Vec::new();
+
+    let converted = converter
+        .batch_convert_with_progress(images, tasks, |completed, total| progress.push((completed, total)))
+        .unwrap();
+
+    assert_eq!(converted.len(), 3);
+    assert_eq!(progress, vec![(1, 3), (2, 3), (3, 3)]);
+}
+
+#[test]
+fn test_progress_callback_parallel() {
+    let mut converter = FormatConverter::new(ConverterConfigBuilder::new().enable_parallel(true).build()).unwrap();
+    let (images, tasks) = create_batch(6);
+    let mut progress = Vec::new();
+
+    let converted = converter
+        .batch_convert_with_progress(images, tasks, |completed, total| progress.push((completed, total)))
+        .unwrap();
+
+    assert_eq!(converted.len(), 6);
+    assert_eq!(progress.len(), 6);
+    assert!(progress.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    assert_eq!(progress.last(), Some(&(6, 6)));
+}
+
+/// 记录执行取消检查的线程名 - 用于观察批处理实际运行在哪些线程上
+#[derive(Default)]
+struct RecordWorkerThreads {
+    names: Mutex<HashSet<String>>,
+}
+
+impl Cancellation for RecordWorkerThreads {
+    fn is_cancelled(&self) -> bool {
+        let name = std::thread::current().name().unwrap_or_default().to_string();
+        self.names.lock().unwrap().insert(name);
+        false
+    }
+}
+
+#[test]
+fn test_thread_pool_size_bounds_parallel_batch() {
+    let config = ConverterConfigBuilder::new()
+        .enable_parallel(true)
+        .thread_pool_size(2)
+        .build();
+    let mut converter = FormatConverter::new(config).unwrap();
+    assert_eq!(converter.current_num_threads(), 2);
+
+    let (images, tasks) = create_batch(16);
+    let recorder = RecordWorkerThreads::default();
+    let results = converter.batch_convert_cancellable(images, tasks, &recorder).unwrap();
+    assert_eq!(results.len(), 16);
+
+    let names = recorder.names.into_inner().unwrap();
+    assert!(!names.is_empty() && names.len() <= 2, "{:?}", names);
+    assert!(names.iter().all(|name| name.starts_with("rustimage-worker-")), "{:?}", names);
+}
+
+#[test]
+fn test_batch_convert_auto_mixed_inputs() {
+    let rgb = image::RgbImage::from_pixel(8, 8, image::Rgb([10, 20, 30]));
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new(&mut jpeg)
+        .write_image(rgb.as_raw(), 8, 8, image::ColorType::Rgb8)
+        .expect("Failed to encode JPEG");
+
+    for parallel in [false, true] {
+        let config = ConverterConfigBuilder::new().enable_parallel(parallel).build();
+        let mut converter = FormatConverter::new(config).unwrap();
+        let results = converter.batch_convert_auto(vec![
+            (create_png(4, 4), ImageFormat::Jpeg, None),
+            (vec![0x42; 32], ImageFormat::Png, None),
+            (jpeg.clone(), ImageFormat::Png, None),
+        ]);
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().format(), ImageFormat::Jpeg);
+        assert!(matches!(results[1], Err(ImageError::FormatDetectionFailed { .. })));
+        let png = results[2].as_ref().unwrap();
+        assert_eq!(png.format(), ImageFormat::Png);
+        assert_eq!((png.dimensions().width, png.dimensions().height), (8, 8));
+    }
+}
+
+#[test]
+fn test_convert_directory_skips_non_images() {
+    let root = std::env::temp_dir().join(format!("rustimage-convert-dir-{}", std::process::id()));
+    let input_dir = root.join("input");
+    let output_dir = root.join("output");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&input_dir).unwrap();
+
+    let rgb = image::RgbImage::from_pixel(6, 6, image::Rgb([90, 60, 30]));
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new(&mut jpeg)
+        .write_image(rgb.as_raw(), 6, 6, image::ColorType::Rgb8)
+        .expect("Failed to encode JPEG");
+    std::fs::write(input_dir.join("a.png"), create_png(4, 4)).unwrap();
+    std::fs::write(input_dir.join("b.jpg"), jpeg).unwrap();
+    std::fs::write(input_dir.join("notes.txt"), "not an image").unwrap();
+
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let results = converter
+        .convert_directory(&input_dir, ImageFormat::WebP, &output_dir, None)
+        .unwrap();
+    let written: Vec<_> = results.into_iter().map(|result| result.unwrap()).collect();
+    assert_eq!(written, vec![output_dir.join("a.webp"), output_dir.join("b.webp")]);
+
+    for path in &written {
+        let data = std::fs::read(path).unwrap();
+        assert_eq!(rustimage_core::detect_format(&data).unwrap(), ImageFormat::WebP);
+    }
+    assert_eq!(std::fs::read_dir(&output_dir).unwrap().count(), 2);
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_statistics_and_report_format_summary_lines() {
+    let mut converter = sequential_converter();
+    // 截断的PNG通过签名检查但解码失败，计入统计
+    let truncated = create_png(4, 4)[..40].to_vec();
+    let results: Vec<_> = [create_png(4, 4), create_png(6, 6), truncated]
+        .iter()
+        .map(|data| converter.convert_format(data, ImageFormat::Png, ImageFormat::Jpeg, None))
+        .collect();
+
+    let stats = converter.get_conversion_statistics();
+    let line = stats.to_string();
+    assert!(line.contains("2/3 conversions"), "{}", line);
+    assert!(line.contains("66.7% success"), "{}", line);
+    assert!(line.contains(&format!("avg {:.2} ms", stats.average_processing_time_ms)), "{}", line);
+
+    let report = BatchConvertReport::from_results(&results);
+    assert_eq!((report.total, report.succeeded, report.failed), (3, 2, 1));
+    let line = report.to_string();
+    assert!(line.contains("66.7% success"), "{}", line);
+    assert!(line.contains(&format!("avg {:.2} ms", report.average_time_ms())), "{}", line);
+
+    let log_line = results[0].as_ref().unwrap().to_log_line();
+    assert!(log_line.starts_with("format=JPEG dimensions=4×4 "), "{}", log_line);
+    assert!(!log_line.contains('\n'));
+}
+
+#[test]
+fn test_batch_convert_items_reports_failures_by_id() {
+    let mut converter = sequential_converter();
+    let truncated = create_png(4, 4)[..40].to_vec();
+    let images = vec![
+        ImageInput::new(create_png(4, 4), ImageFormat::Png),
+        ImageInput::new(truncated, ImageFormat::Png),
+        // 任务未设置id时回退到输入的文件名
+        ImageInput::with_filename(create_png(4, 4), ImageFormat::Png, "photo_13.png".to_string()),
+    ];
+    let tasks = ["photo_11.png", "photo_12.png", ""]
+        .iter()
+        .map(|id| ConversionTask {
+            from_format: ImageFormat::Png,
+            to_format: ImageFormat::Jpeg,
+            options: None,
+            id: (!id.is_empty()).then(|| id.to_string()),
+        })
+        .collect();
+
+    let items = converter.batch_convert_items(images, tasks).unwrap();
+    let ids: Vec<_> = items.iter().map(|item| item.id.as_deref()).collect();
+    assert_eq!(ids, [Some("photo_11.png"), Some("photo_12.png"), Some("photo_13.png")]);
+    assert!(items[0].result.is_ok());
+    assert!(items[1].result.is_err());
+    assert!(items[2].result.is_ok());
+
+    let failed: Vec<_> = items
+        .iter()
+        .filter(|item| item.result.is_err())
+        .filter_map(|item| item.id.as_deref())
+        .collect();
+    assert_eq!(failed, ["photo_12.png"]);
+}
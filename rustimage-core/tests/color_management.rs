@@ -0,0 +1,128 @@
+//! 色彩管理测试
+//!
+//! 测试ICC色彩配置文件的提取与嵌入
+
+use rustimage_core::{ConversionOptionsBuilder, FormatConverter, ImageFormat};
+use image::{ImageBuffer, ImageEncoder, Rgb};
+
+/// 测试用的ICC配置文件内容
+fn test_profile() -> Vec<u8> {
+    let mut profile = b"rustimage-test-icc-profile".to_vec();
+    profile.extend((0..=255u8).cycle().take(600));
+    profile
+}
+
+/// 创建携带ICC配置文件（APP2段）的JPEG
+fn create_jpeg_with_profile(profile: &[u8]) -> Vec<u8> {
+    let img = ImageBuffer::from_fn(16, 16, |x, y| Rgb([(x * 16) as u8, (y * 16) as u8, 128]));
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, 90)
+        .write_image(img.as_raw(), 16, 16, image::ColorType::Rgb8)
+        .unwrap();
+
+    let mut segment = vec![0xFF, 0xE2];
+    segment.extend_from_slice(&((profile.len() + 16) as u16).to_be_bytes());
+    segment.extend_from_slice(b"ICC_PROFILE\0");
+    segment.extend_from_slice(&[1, 1]);
+    segment.extend_from_slice(profile);
+
+    let mut output = jpeg[..2].to_vec();
+    output.extend_from_slice(&segment);
+    output.extend_from_slice(&jpeg[2..]);
+    output
+}
+
+#[test]
+fn test_icc_profile_survives_jpeg_png_jpeg() {
+    let profile = test_profile();
+    let jpeg = create_jpeg_with_profile(&profile);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let png = converter
+        .convert_format(&jpeg, ImageFormat::Jpeg, ImageFormat::Png, None)
+        .unwrap();
+    assert_eq!(png.icc_profile(), Some(profile.as_slice()));
+
+    let back = converter
+        .convert_format(png.data(), ImageFormat::Png, ImageFormat::Jpeg, None)
+        .unwrap();
+    assert_eq!(back.icc_profile(), Some(profile.as_slice()));
+
+    // 嵌入的字节必须能被独立的解码器读出
+    let mut decoder = image::codecs::jpeg::JpegDecoder::new(std::io::Cursor::new(back.data())).unwrap();
+    assert_eq!(image::ImageDecoder::icc_profile(&mut decoder), Some(profile));
+}
+
+#[test]
+fn test_icc_profile_embedded_in_webp_and_tiff() {
+    let profile = test_profile();
+    let jpeg = create_jpeg_with_profile(&profile);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    for format in [ImageFormat::WebP, ImageFormat::Tiff] {
+        let converted = converter
+            .convert_format(&jpeg, ImageFormat::Jpeg, format, None)
+            .unwrap();
+        assert_eq!(converted.icc_profile(), Some(profile.as_slice()));
+
+        let frames = converter.decode_frames(converted.data(), format).unwrap();
+        assert_eq!(frames[0].0.icc_profile(), Some(profile.as_slice()));
+    }
+}
+
+#[test]
+fn test_icc_profile_dropped_without_preserve_color_space() {
+    let profile = test_profile();
+    let jpeg = create_jpeg_with_profile(&profile);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let options = ConversionOptionsBuilder::new()
+        .preserve_color_space(false)
+        .build();
+    let png = converter
+        .convert_format(&jpeg, ImageFormat::Jpeg, ImageFormat::Png, Some(options))
+        .unwrap();
+
+    assert!(png.icc_profile().is_none());
+    let mut decoder = image::codecs::png::PngDecoder::new(std::io::Cursor::new(png.data())).unwrap();
+    assert!(image::ImageDecoder::icc_profile(&mut decoder).is_none());
+}
+
+/// 创建左半青色、右半黄色的CMYK JPEG - jpeg-encoder按Adobe约定反相存储并写入APP14
+fn create_cmyk_jpeg(color_type: jpeg_encoder::ColorType, invert_input: bool) -> Vec<u8> {
+    let cmyk: Vec<u8> = (0..16 * 16)
+        .flat_map(|i| if i % 16 < 8 { [255, 0, 0, 0] } else { [0, 0, 255, 0] })
+        .map(|v: u8| if invert_input { 255 - v } else { v })
+        .collect();
+    let mut jpeg = Vec::new();
+    jpeg_encoder::Encoder::new(&mut jpeg, 95)
+        .encode(&cmyk, 16, 16, color_type)
+        .unwrap();
+    jpeg
+}
+
+/// 移除Adobe APP14段 - 配合预先反相的输入得到常规（非反相）存储的CMYK JPEG
+fn strip_adobe_marker(jpeg: &[u8]) -> Vec<u8> {
+    let start = jpeg.windows(2).position(|w| w == [0xFF, 0xEE]).unwrap();
+    let length = u16::from_be_bytes([jpeg[start + 2], jpeg[start + 3]]) as usize;
+    [&jpeg[..start], &jpeg[start + 2 + length..]].concat()
+}
+
+#[test]
+fn test_cmyk_jpeg_decodes_to_expected_colors() {
+    let converter = FormatConverter::with_defaults().unwrap();
+    let samples = [
+        ("adobe cmyk", create_cmyk_jpeg(jpeg_encoder::ColorType::Cmyk, false)),
+        ("adobe ycck", create_cmyk_jpeg(jpeg_encoder::ColorType::CmykAsYcck, false)),
+        ("plain cmyk", strip_adobe_marker(&create_cmyk_jpeg(jpeg_encoder::ColorType::Cmyk, true))),
+    ];
+
+    for (name, jpeg) in samples {
+        let decoded = converter.decode_to_buffer(&jpeg, ImageFormat::Jpeg).unwrap();
+        let pixels = decoded.buffer().as_slice();
+        let cyan = pixels[16 * 8 + 2];
+        let yellow = pixels[16 * 8 + 13];
+        assert!(cyan.r < 40 && cyan.g > 215 && cyan.b > 215, "{}: cyan decoded as {:?}", name, cyan);
+        assert!(yellow.r > 215 && yellow.g > 215 && yellow.b < 40, "{}: yellow decoded as {:?}", name, yellow);
+    }
+}
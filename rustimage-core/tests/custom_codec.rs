@@ -0,0 +1,171 @@
+//! 自定义编解码器测试
+//!
+//! 测试注册用户实现的编解码器并通过格式键转换
+
+use rustimage_core::codecs::{
+    Codec, CodecInfo, ImageBuffer as CoreBuffer, PerformanceLevel, PixelFormat, QualityFeatures,
+};
+use rustimage_core::{ConversionOptions, ConversionOptionsBuilder, FormatConverter, ImageError, ImageFormat, Rgba};
+use image::{ImageBuffer, ImageEncoder};
+
+const MAGIC: &[u8; 4] = b"RAW4";
+
+/// 最简单的自定义格式：魔数 + 小端宽高 + RGBA像素
+struct RawRgbaCodec;
+
+impl Codec<Rgba<u8>> for RawRgbaCodec {
+    fn decode(&self, data: &[u8]) -> rustimage_core::Result<CoreBuffer<Rgba<u8>>> {
+        let width = u32::from_le_bytes(data[4..8].try_into().unwrap());
+        let height = u32::from_le_bytes(data[8..12].try_into().unwrap());
+        let pixels = data[12..]
+            .chunks_exact(4)
+            .map(|p| Rgba { r: p[0], g: p[1], b: p[2], a: p[3] })
+            .collect();
+        CoreBuffer::from_raw(width, height, pixels, PixelFormat::Rgba8)
+    }
+
+    fn encode(&self, buffer: &CoreBuffer<Rgba<u8>>, _options: &ConversionOptions) -> rustimage_core::Result<Vec<u8>> {
+        let dims = buffer.dimensions();
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&dims.width.to_le_bytes());
+        data.extend_from_slice(&dims.height.to_le_bytes());
+        data.extend(buffer.as_slice().iter().flat_map(|p| [p.r, p.g, p.b, p.a]));
+        Ok(data)
+    }
+
+    fn info(&self) -> CodecInfo {
+        CodecInfo {
+            format: ImageFormat::Bmp,
+            name: "Raw RGBA".to_string(),
+            version: "1.0.0".to_string(),
+            supports_decode: true,
+            supports_encode: true,
+            performance_level: PerformanceLevel::Fast,
+            quality_features: QualityFeatures {
+                supports_lossless: true,
+                supports_lossy: false,
+                supports_progressive: false,
+                supports_transparency: true,
+                supports_animation: false,
+                max_quality_level: 100,
+            },
+        }
+    }
+
+    fn validate_format(&self, data: &[u8]) -> bool {
+        data.len() >= 12 && data.starts_with(MAGIC)
+    }
+}
+
+/// 只能编码的自定义格式 - 解码总是失败
+struct EncodeOnlyCodec;
+
+impl Codec<Rgba<u8>> for EncodeOnlyCodec {
+    fn decode(&self, _data: &[u8]) -> rustimage_core::Result<CoreBuffer<Rgba<u8>>> {
+        Err(ImageError::UnsupportedOperation { operation: "encode-only decode".to_string() })
+    }
+
+    fn encode(&self, buffer: &CoreBuffer<Rgba<u8>>, options: &ConversionOptions) -> rustimage_core::Result<Vec<u8>> {
+        RawRgbaCodec.encode(buffer, options)
+    }
+
+    fn info(&self) -> CodecInfo {
+        CodecInfo { name: "Encode only".to_string(), supports_decode: false, ..RawRgbaCodec.info() }
+    }
+
+    fn validate_format(&self, _data: &[u8]) -> bool {
+        false
+    }
+}
+
+fn create_png(width: u32, height: u32) -> Vec<u8> {
+    let img = ImageBuffer::from_fn(width, height, |x, y| image::Rgba([x as u8 * 20, y as u8 * 30, 77, 200]));
+    let mut data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut data)
+        .write_image(img.as_raw(), width, height, image::ColorType::Rgba8)
+        .unwrap();
+    data
+}
+
+#[test]
+fn test_custom_codec_round_trip() {
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    converter.register_codec("raw-rgba", Box::new(RawRgbaCodec)).unwrap();
+
+    let png = create_png(6, 4);
+    let raw = converter.convert_custom(&png, "png", "raw-rgba", None).unwrap();
+    assert!(raw.data().starts_with(MAGIC));
+    assert_eq!(raw.data().len(), 12 + 6 * 4 * 4);
+    assert_eq!((raw.dimensions().width, raw.dimensions().height), (6, 4));
+    // 自定义格式以编解码器声明的内置格式表示
+    assert_eq!(raw.format(), ImageFormat::Bmp);
+
+    let back = converter.convert_custom(raw.data(), "raw-rgba", "png", None).unwrap();
+    assert_eq!(back.format(), ImageFormat::Png);
+    let decoded = image::load_from_memory(back.data()).unwrap().to_rgba8();
+    let original = image::load_from_memory(&png).unwrap().to_rgba8();
+    assert_eq!(decoded.as_raw(), original.as_raw());
+
+    // 与内置格式一样计入统计
+    assert_eq!(converter.get_conversion_statistics().successful_conversions, 2);
+}
+
+#[test]
+fn test_custom_codec_applies_pixel_transforms() {
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    converter.register_codec("raw-rgba", Box::new(RawRgbaCodec)).unwrap();
+
+    let png = create_png(8, 6);
+    let options = ConversionOptionsBuilder::new().resize(4, 3).grayscale(true).build();
+    let raw = converter.convert_custom(&png, "png", "raw-rgba", Some(options)).unwrap();
+
+    assert_eq!((raw.dimensions().width, raw.dimensions().height), (4, 3));
+    assert_eq!(raw.data().len(), 12 + 4 * 3 * 4);
+    assert!(raw.data()[12..].chunks_exact(4).all(|p| p[0] == p[1] && p[1] == p[2]));
+}
+
+#[test]
+fn test_custom_codec_errors() {
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    // 不能遮蔽内置格式，也不能为空
+    for key in ["PNG", "jpg", " "] {
+        let result = converter.register_codec(key, Box::new(RawRgbaCodec));
+        assert!(matches!(result, Err(ImageError::InvalidParameters { .. })), "{:?}", key);
+    }
+
+    converter.register_codec("raw-rgba", Box::new(RawRgbaCodec)).unwrap();
+    let png = create_png(4, 4);
+
+    // 未注册的键列出可用格式
+    match converter.convert_custom(&png, "png", "unknown", None) {
+        Err(ImageError::UnsupportedFormat { supported, .. }) => {
+            assert!(supported.contains(&"raw-rgba".to_string()));
+            assert!(supported.contains(&"PNG".to_string()));
+        }
+        other => panic!("expected UnsupportedFormat, got {:?}", other.map(|c| c.data().len())),
+    }
+
+    // 数据与自定义格式不符
+    let result = converter.convert_custom(&png, "raw-rgba", "png", None);
+    assert!(matches!(result, Err(ImageError::InvalidFormat { .. })));
+    let stats = converter.get_conversion_statistics();
+    assert_eq!((stats.total_conversions, stats.successful_conversions), (1, 0));
+}
+
+#[test]
+fn test_quality_assessment_skips_encode_only_target() {
+    let mut converter = FormatConverter::with_high_quality().unwrap();
+    converter.register_codec("encode-only", Box::new(EncodeOnlyCodec)).unwrap();
+    converter.register_codec("raw-rgba", Box::new(RawRgbaCodec)).unwrap();
+    let png = create_png(6, 4);
+
+    // 目标无法回解码时仍然成功，只是没有质量指标
+    let converted = converter.convert_custom(&png, "png", "encode-only", None).unwrap();
+    assert!(converted.data().starts_with(MAGIC));
+    assert!(converted.quality_metrics().is_none());
+
+    // 可解码的目标照常计算质量指标
+    let converted = converter.convert_custom(&png, "png", "raw-rgba", None).unwrap();
+    assert!(converted.quality_metrics().is_some());
+}
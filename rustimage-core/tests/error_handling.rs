@@ -0,0 +1,255 @@
+//! 错误处理测试
+//!
+//! 测试错误收集器的统计、转换器对它的接入以及重试策略
+
+use rustimage_core::converter::ConverterConfigBuilder;
+use rustimage_core::error::{ErrorCategory, ErrorCollector, ErrorSeverity, RetryPolicy};
+use rustimage_core::ImageError;
+use rustimage_core::{ConversionOptionsBuilder, ConversionTask, FormatConverter, ImageFormat, ImageInput};
+use std::cell::Cell;
+use std::time::Duration;
+
+/// 带有PNG签名但内容损坏的数据 - 通过格式校验，在解码阶段失败
+fn corrupt_png() -> Vec<u8> {
+    let mut data = vec![0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+    data.extend_from_slice(&[0xAB; 32]);
+    data
+}
+
+fn collecting_converter(parallel: bool) -> FormatConverter {
+    let config = ConverterConfigBuilder::new()
+        .enable_parallel(parallel)
+        .enable_error_collection(true)
+        .build();
+    FormatConverter::new(config).unwrap()
+}
+
+#[test]
+fn test_error_statistics_count_decode_failures() {
+    let mut converter = collecting_converter(false);
+    for _ in 0..3 {
+        assert!(converter
+            .convert_format(&corrupt_png(), ImageFormat::Png, ImageFormat::Jpeg, None)
+            .is_err());
+    }
+    // 空输入属于参数错误
+    assert!(converter.convert_format(&[], ImageFormat::Png, ImageFormat::Jpeg, None).is_err());
+
+    let stats = converter.error_statistics();
+    assert_eq!(stats.total_errors, 4);
+    assert_eq!(stats.error_counts_by_category.get(&ErrorCategory::Codec), Some(&3));
+    assert_eq!(stats.error_counts_by_category.get(&ErrorCategory::Parameter), Some(&1));
+    assert_eq!(stats.error_counts_by_severity.get(&ErrorSeverity::Medium), Some(&3));
+}
+
+#[test]
+fn test_error_statistics_include_parallel_batch_items() {
+    let mut converter = collecting_converter(true);
+    let images = (0..4).map(|_| ImageInput::new(corrupt_png(), ImageFormat::Png)).collect();
+    let tasks = (0..4)
+        .map(|_| ConversionTask { from_format: ImageFormat::Png, to_format: ImageFormat::Jpeg, options: None, id: None })
+        .collect();
+
+    assert!(converter.batch_convert(images, tasks).is_err());
+    let stats = converter.error_statistics();
+    assert_eq!(stats.total_errors, 4);
+    assert_eq!(stats.error_counts_by_category.get(&ErrorCategory::Codec), Some(&4));
+}
+
+#[test]
+fn test_error_collection_disabled_by_default() {
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    assert!(converter
+        .convert_format(&corrupt_png(), ImageFormat::Png, ImageFormat::Jpeg, None)
+        .is_err());
+    assert_eq!(converter.error_statistics().total_errors, 0);
+}
+
+#[test]
+fn test_most_common_errors_sorted_by_count() {
+    let collector = ErrorCollector::new(true);
+    for _ in 0..2 {
+        collector.record_error(&ImageError::invalid_parameters("bad quality"));
+    }
+    for _ in 0..5 {
+        collector.record_error(&ImageError::DecodeError {
+            format: "PNG".to_string(),
+            message: "truncated".to_string(),
+            source: None,
+        });
+    }
+
+    let stats = collector.get_statistics();
+    assert_eq!(
+        stats.most_common_errors,
+        vec![("DecodeError".to_string(), 5), ("InvalidParameters".to_string(), 2)]
+    );
+}
+
+/// 首次调用超时、之后成功的模拟编解码操作
+fn flaky_encode(calls: &Cell<u32>) -> rustimage_core::Result<Vec<u8>> {
+    calls.set(calls.get() + 1);
+    if calls.get() == 1 {
+        Err(ImageError::OperationTimeout { operation: "encode".to_string(), timeout_ms: 10 })
+    } else {
+        Ok(vec![1, 2, 3])
+    }
+}
+
+#[test]
+fn test_retry_policy_succeeds_on_second_attempt() {
+    let calls = Cell::new(0);
+    let result = RetryPolicy::new(3, Duration::from_millis(1)).run(|| flaky_encode(&calls));
+    assert_eq!(result.unwrap(), vec![1, 2, 3]);
+    assert_eq!(calls.get(), 2);
+
+    // 尝试次数用尽时返回最后一次的错误
+    let calls = Cell::new(0);
+    let result = RetryPolicy::new(1, Duration::from_millis(1)).run(|| flaky_encode(&calls));
+    assert!(matches!(result, Err(ImageError::OperationTimeout { .. })));
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn test_convert_with_retry_does_not_retry_invalid_format() {
+    let mut converter = collecting_converter(false);
+    let blob = vec![0x42u8; 64];
+    let result = converter.convert_with_retry(
+        &blob,
+        ImageFormat::Png,
+        ImageFormat::Jpeg,
+        None,
+        5,
+        Duration::from_millis(1),
+    );
+
+    assert!(matches!(result, Err(ImageError::InvalidFormat { .. })));
+    // 每次尝试都会记录一次失败，只有一条说明没有重试
+    assert_eq!(converter.error_statistics().total_errors, 1);
+}
+
+#[test]
+fn test_zero_dimension_decode_is_rejected() {
+    // 头部合法但宽高为0的PPM - 解码成功，得到空图像
+    let empty_ppm = b"P6\n0 0\n255\n".to_vec();
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let result = converter.convert_format(&empty_ppm, ImageFormat::Pnm, ImageFormat::Png, None);
+
+    match result {
+        Err(ImageError::InvalidDimensions { width, height, reason }) => {
+            assert_eq!((width, height), (0, 0));
+            assert_eq!(reason, "zero dimension");
+        }
+        other => panic!("expected InvalidDimensions, got {:?}", other.map(|image| image.dimensions())),
+    }
+}
+
+/// 标准CRC-32 - PNG块校验
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |crc, _| if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 })
+    })
+}
+
+/// 只有文件头的PNG - IHDR声明给定尺寸，没有像素数据
+fn png_header_only(width: u32, height: u32) -> Vec<u8> {
+    let mut ihdr = b"IHDR".to_vec();
+    ihdr.extend(width.to_be_bytes());
+    ihdr.extend(height.to_be_bytes());
+    ihdr.extend([8, 6, 0, 0, 0]);
+
+    let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    png.extend(13u32.to_be_bytes());
+    png.extend(&ihdr);
+    png.extend(crc32(&ihdr).to_be_bytes());
+    png
+}
+
+#[test]
+fn test_decompression_bomb_rejected_before_decoding() {
+    let bomb = png_header_only(50_000, 50_000);
+    let config = ConverterConfigBuilder::new().max_decoded_pixels(Some(1_000_000)).build();
+    let mut converter = FormatConverter::new(config).unwrap();
+
+    match converter.convert_format(&bomb, ImageFormat::Png, ImageFormat::Jpeg, None) {
+        Err(ImageError::SystemLimitExceeded { limit_type, current, limit }) => {
+            assert_eq!(limit_type, "pixels");
+            assert_eq!(current, 2_500_000_000);
+            assert_eq!(limit, 1_000_000);
+        }
+        other => panic!("expected SystemLimitExceeded, got {:?}", other.map(|image| image.dimensions())),
+    }
+    assert!(matches!(
+        converter.decode_to_buffer(&bomb, ImageFormat::Png),
+        Err(ImageError::SystemLimitExceeded { .. })
+    ));
+    // 同格式直通同样检查文件头声明的像素数
+    for options in [None, Some(ConversionOptionsBuilder::new().passthrough(true).build())] {
+        assert!(matches!(
+            converter.convert_format(&bomb, ImageFormat::Png, ImageFormat::Png, options),
+            Err(ImageError::SystemLimitExceeded { .. })
+        ));
+    }
+
+    // 默认上限同样拦截；关闭上限后交给解码器，因缺少像素数据而解码失败
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    assert!(matches!(
+        converter.convert_format(&bomb, ImageFormat::Png, ImageFormat::Jpeg, None),
+        Err(ImageError::SystemLimitExceeded { .. })
+    ));
+    let config = ConverterConfigBuilder::new().max_decoded_pixels(None).build();
+    let small = png_header_only(4, 4);
+    let converter = FormatConverter::new(config).unwrap();
+    assert!(matches!(
+        converter.decode_to_buffer(&small, ImageFormat::Png),
+        Err(ImageError::DecodeError { .. })
+    ));
+}
+
+/// 每种错误变体的一个实例
+fn one_of_each_error() -> Vec<ImageError> {
+    let text = || "x".to_string();
+    vec![
+        ImageError::InvalidFormat { format: text() },
+        ImageError::UnsupportedFormat { format: text(), supported: vec!["png".to_string()] },
+        ImageError::FormatDetectionFailed { reason: text() },
+        ImageError::DecodeError { format: text(), message: text(), source: None },
+        ImageError::EncodeError { format: text(), message: text(), source: None },
+        ImageError::CodecInitializationFailed { format: text(), details: text() },
+        ImageError::InvalidParameters { details: text() },
+        ImageError::InvalidDimensions { width: 0, height: 0, reason: text() },
+        ImageError::InvalidPixelFormat { expected: text(), actual: text() },
+        ImageError::ConfigurationError { setting: text(), value: text(), reason: text() },
+        ImageError::MemoryError { requested: 1, available: 0 },
+        ImageError::ResourceExhausted { resource: text() },
+        ImageError::SystemLimitExceeded { limit_type: text(), current: 2, limit: 1 },
+        ImageError::UnsupportedOperation { operation: text() },
+        ImageError::OperationCancelled { operation: text(), reason: text(), completed: 0, total: 1 },
+        ImageError::ProcessingFailed { stage: text(), reason: text() },
+        ImageError::OperationTimeout { operation: text(), timeout_ms: 1 },
+        ImageError::BatchProcessingFailed {
+            successful_count: 0,
+            failed_count: 1,
+            first_error: Box::new(ImageError::invalid_parameters("x")),
+        },
+        ImageError::ParallelProcessingError { details: text(), failed_task_indices: vec![0] },
+        ImageError::PerformanceError { details: text() },
+        ImageError::QualityAssessmentFailed { metric: text(), reason: text() },
+        ImageError::IoError { operation: text(), source: std::io::Error::other("x") },
+        ImageError::ExternalLibraryError { library: text(), message: text(), source: None },
+        ImageError::WasmBindingError { details: text() },
+        ImageError::JsInteropError { operation: text(), reason: text() },
+    ]
+}
+
+#[test]
+fn test_every_error_has_suggested_solution() {
+    let errors = one_of_each_error();
+    let kinds: std::collections::HashSet<_> = errors.iter().map(ImageError::kind).collect();
+    assert_eq!(kinds.len(), errors.len());
+
+    for error in &errors {
+        let solution = error.suggested_solution();
+        assert!(solution.is_some_and(|s| !s.is_empty()), "{} has no suggestion", error.kind());
+    }
+}
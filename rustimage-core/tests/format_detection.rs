@@ -0,0 +1,328 @@
+//! 格式检测测试
+//!
+//! 测试详细格式检测的置信度、检测方式和子类型
+
+use rustimage_core::codecs::{DetectionMethod, FormatDetector, FormatSubtype, WebPVariant};
+use rustimage_core::{detect_format, FormatConverter, ImageFormat};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, ImageEncoder, RgbaImage};
+
+/// 创建指定帧数的GIF
+fn create_gif(frame_count: u8) -> Vec<u8> {
+    let mut data = Vec::new();
+    {
+        let mut encoder = GifEncoder::new(&mut data);
+        encoder.set_repeat(Repeat::Infinite).unwrap();
+        for i in 0..frame_count {
+            let img = RgbaImage::from_pixel(4, 4, image::Rgba([i * 80, 0, 0, 255]));
+            encoder
+                .encode_frame(Frame::from_parts(img, 0, 0, Delay::from_numer_denom_ms(100, 1)))
+                .unwrap();
+        }
+    }
+    data
+}
+
+/// 创建小尺寸PNG
+fn create_png() -> Vec<u8> {
+    let img = RgbaImage::from_pixel(4, 4, image::Rgba([1, 2, 3, 255]));
+    let mut data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut data)
+        .write_image(img.as_raw(), 4, 4, image::ColorType::Rgba8)
+        .unwrap();
+    data
+}
+
+/// 构造最小的AVIF文件头（ftyp盒）
+fn avif_header(major_brand: &[u8; 4]) -> Vec<u8> {
+    let mut data = vec![0x00, 0x00, 0x00, 0x1C];
+    data.extend_from_slice(b"ftyp");
+    data.extend_from_slice(major_brand);
+    data.extend_from_slice(&[0, 0, 0, 0]);
+    data.extend_from_slice(b"mif1miafavif");
+    data
+}
+
+#[test]
+fn test_detect_detailed_still_webp() {
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let webp = converter
+        .convert_format(&create_png(), ImageFormat::Png, ImageFormat::WebP, None)
+        .unwrap();
+
+    let detection = FormatDetector::detect_detailed(webp.data()).unwrap();
+    assert_eq!(detection.format(), ImageFormat::WebP);
+    assert_eq!(detection.method(), DetectionMethod::StructureParsing);
+    assert_eq!(detection.subtype(), Some(FormatSubtype::Still));
+    assert_eq!(detection.webp_variant(), Some(WebPVariant::Lossless));
+    assert_eq!(detection.confidence(), 1.0);
+}
+
+/// 构造简单格式WebP的文件头 - 首个数据块为`fourcc`，负载为`payload`
+fn webp_header(fourcc: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut data = b"RIFF".to_vec();
+    data.extend_from_slice(&(4 + 8 + payload.len() as u32).to_le_bytes());
+    data.extend_from_slice(b"WEBP");
+    data.extend_from_slice(fourcc);
+    data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    data.extend_from_slice(payload);
+    data
+}
+
+#[test]
+fn test_detect_detailed_webp_variants() {
+    // 有损关键帧：帧标记、起始码9D 01 2A和4×4尺寸
+    let lossy = webp_header(b"VP8 ", &[0x10, 0x02, 0x00, 0x9D, 0x01, 0x2A, 0x04, 0x00, 0x04, 0x00]);
+    let detection = FormatDetector::detect_detailed(&lossy).unwrap();
+    assert_eq!(detection.webp_variant(), Some(WebPVariant::Lossy));
+    assert_eq!(detection.subtype(), Some(FormatSubtype::Still));
+    assert!(!detection.is_animated());
+
+    // 动画WebP使用带动画标志位的VP8X扩展头
+    let converter = FormatConverter::with_defaults().unwrap();
+    let frames = converter.decode_frames(&create_gif(3), ImageFormat::Gif).unwrap();
+    let animated = converter.encode_animation(frames, ImageFormat::WebP, None).unwrap();
+    let detection = FormatDetector::detect_detailed(animated.data()).unwrap();
+    assert_eq!(detection.webp_variant(), Some(WebPVariant::Extended));
+    assert_eq!(detection.subtype(), Some(FormatSubtype::Animated));
+    assert!(detection.is_animated());
+
+    // 截断的VP8X头只按魔数识别
+    let truncated = webp_header(b"VP8X", &[0x02, 0, 0]);
+    let detection = FormatDetector::detect_detailed(&truncated).unwrap();
+    assert_eq!(detection.format(), ImageFormat::WebP);
+    assert_eq!(detection.method(), DetectionMethod::MagicBytes);
+    assert_eq!(detection.webp_variant(), None);
+    assert_eq!(detection.subtype(), None);
+}
+
+#[test]
+fn test_detect_detailed_animated_gif() {
+    let animated = FormatDetector::detect_detailed(&create_gif(3)).unwrap();
+    assert_eq!(animated.format(), ImageFormat::Gif);
+    assert!(animated.is_animated());
+
+    let still = FormatDetector::detect_detailed(&create_gif(1)).unwrap();
+    assert_eq!(still.subtype(), Some(FormatSubtype::Still));
+}
+
+#[test]
+fn test_detect_detailed_avif_brands() {
+    let still = FormatDetector::detect_detailed(&avif_header(b"avif")).unwrap();
+    assert_eq!(still.format(), ImageFormat::Avif);
+    assert_eq!(still.subtype(), Some(FormatSubtype::Still));
+
+    let sequence = FormatDetector::detect_detailed(&avif_header(b"avis")).unwrap();
+    assert_eq!(sequence.format(), ImageFormat::Avif);
+    assert!(sequence.is_animated());
+
+    // 非AVIF品牌的ISOBMFF容器不应被误判
+    let mut mp4 = avif_header(b"isom");
+    mp4.truncate(16);
+    assert!(FormatDetector::detect_detailed(&mp4).is_err());
+}
+
+#[test]
+fn test_detect_detailed_heic_brands() {
+    for brand in [b"heic", b"heix", b"mif1"] {
+        let mut data = vec![0x00, 0x00, 0x00, 0x18];
+        data.extend_from_slice(b"ftyp");
+        data.extend_from_slice(brand);
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        data.extend_from_slice(b"mif1heic");
+
+        let detection = FormatDetector::detect_detailed(&data).unwrap();
+        assert_eq!(detection.format(), ImageFormat::Heic, "brand {:?}", brand);
+        assert_eq!(detection.method(), DetectionMethod::StructureParsing);
+    }
+
+    // AVIF同样声明mif1兼容品牌，仍应识别为AVIF
+    assert_eq!(detect_format(&avif_header(b"mif1")).unwrap(), ImageFormat::Avif);
+}
+
+#[test]
+fn test_detect_delegates_to_detailed() {
+    let png = create_png();
+    let detection = FormatDetector::detect_detailed(&png).unwrap();
+    assert_eq!(detect_format(&png).unwrap(), detection.format());
+    assert_eq!(detection.method(), DetectionMethod::MagicBytes);
+}
+
+#[test]
+fn test_detect_skips_utf8_bom() {
+    let mut data = vec![0xEF, 0xBB, 0xBF];
+    data.extend_from_slice(&create_png());
+
+    let detection = FormatDetector::detect_detailed(&data).unwrap();
+    assert_eq!(detection.format(), ImageFormat::Png);
+    assert_eq!(detection.offset(), 3);
+    assert!(detection.confidence() < 1.0);
+
+    // 自动检测的转换与解码校验都从偏移处开始
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let dimensions = converter.validate_decodable(&data, Some(ImageFormat::Png)).unwrap();
+    let converted = converter.convert_auto(&data, ImageFormat::Jpeg, None).unwrap();
+    assert_eq!(converted.format(), ImageFormat::Jpeg);
+    assert_eq!(converted.dimensions(), dimensions);
+}
+
+#[test]
+fn test_detect_skips_leading_junk_before_jpeg() {
+    let mut data = vec![0x00, 0x13, 0x37, 0x42];
+    data.extend_from_slice(&[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, 0x4A, 0x46, 0x49, 0x46]);
+
+    let detection = FormatDetector::detect_detailed(&data).unwrap();
+    assert_eq!(detection.format(), ImageFormat::Jpeg);
+    assert_eq!(detection.offset(), 4);
+    assert_eq!(detect_format(&data).unwrap(), ImageFormat::Jpeg);
+
+    // 扫描窗口之外的签名不被接受
+    let mut far = vec![0x20; 40];
+    far.extend_from_slice(&create_png());
+    assert!(FormatDetector::detect_detailed(&far).is_err());
+}
+
+#[test]
+fn test_tga_roundtrip_and_footer_detection() {
+    let img = RgbaImage::from_fn(6, 5, |x, y| image::Rgba([x as u8 * 40, y as u8 * 50, 9, (x * y * 8) as u8]));
+    let mut png = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png)
+        .write_image(img.as_raw(), 6, 5, image::ColorType::Rgba8)
+        .unwrap();
+
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let tga = converter
+        .convert_format(&png, ImageFormat::Png, ImageFormat::Tga, None)
+        .unwrap();
+    assert!(tga.data().ends_with(b"TRUEVISION-XFILE.\0"));
+    assert_eq!(detect_format(tga.data()).unwrap(), ImageFormat::Tga);
+
+    // 透明度在往返中保留
+    let back = converter
+        .convert_format(tga.data(), ImageFormat::Tga, ImageFormat::Png, None)
+        .unwrap();
+    let roundtrip = image::load_from_memory(back.data()).unwrap().to_rgba8();
+    assert_eq!(roundtrip.as_raw(), img.as_raw());
+}
+
+#[test]
+fn test_tga_without_footer_needs_extension_hint() {
+    // image库写出的TGA 1.0没有尾部签名
+    let img = RgbaImage::from_pixel(4, 4, image::Rgba([10, 20, 30, 255]));
+    let mut tga = Vec::new();
+    image::codecs::tga::TgaEncoder::new(&mut tga)
+        .write_image(img.as_raw(), 4, 4, image::ColorType::Rgba8)
+        .unwrap();
+
+    assert!(FormatDetector::detect_detailed(&tga).is_err());
+
+    let detection = FormatDetector::detect_detailed_with_hint(&tga, "TGA").unwrap();
+    assert_eq!(detection.format(), ImageFormat::Tga);
+    assert_eq!(detection.method(), DetectionMethod::ExtensionHint);
+
+    // 提示不能覆盖结构不合理的数据，也不作用于有可靠签名的格式
+    assert!(FormatDetector::detect_detailed_with_hint(&[0xAB; 32], "tga").is_err());
+    assert!(FormatDetector::detect_detailed_with_hint(&tga, "png").is_err());
+    let png = create_png();
+    let detection = FormatDetector::detect_detailed_with_hint(&png, "tga").unwrap();
+    assert_eq!(detection.format(), ImageFormat::Png);
+}
+
+#[test]
+fn test_strict_validation_rejects_declared_format_mismatch() {
+    use rustimage_core::converter::ConverterConfigBuilder;
+    use rustimage_core::ImageError;
+
+    let png = create_png();
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let result = converter.convert_format(&png, ImageFormat::Jpeg, ImageFormat::Png, None);
+    match result {
+        Err(ImageError::InvalidFormat { format }) => {
+            assert_eq!(format, "declared Jpeg but data is Png");
+        }
+        other => panic!("expected InvalidFormat, got {:?}", other.map(|r| r.format())),
+    }
+
+    // 关闭严格校验后由解码器报告错误
+    let config = ConverterConfigBuilder::new().strict_input_validation(false).build();
+    let mut converter = FormatConverter::new(config).unwrap();
+    let result = converter.convert_format(&png, ImageFormat::Jpeg, ImageFormat::Png, None);
+    assert!(!matches!(&result, Err(ImageError::InvalidFormat { format }) if format.starts_with("declared")));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_convert_auto_detects_png() {
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let result = converter.convert_auto(&create_png(), ImageFormat::Jpeg, None).unwrap();
+    assert_eq!(result.format(), ImageFormat::Jpeg);
+    assert_eq!(detect_format(result.data()).unwrap(), ImageFormat::Jpeg);
+}
+
+#[test]
+fn test_convert_auto_rejects_undetectable_data() {
+    use rustimage_core::ImageError;
+
+    let blob = vec![0x42u8; 64];
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let result = converter.convert_auto(&blob, ImageFormat::Png, None);
+    assert!(matches!(result, Err(ImageError::FormatDetectionFailed { .. })));
+}
+
+#[test]
+fn test_builtin_codecs_validate_their_own_format() {
+    use rustimage_core::codecs::{Codec, JpegCodec, PngCodec};
+
+    let rgb = image::RgbImage::from_pixel(4, 4, image::Rgb([9, 8, 7]));
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new(&mut jpeg)
+        .write_image(rgb.as_raw(), 4, 4, image::ColorType::Rgb8)
+        .unwrap();
+    let png = create_png();
+
+    let jpeg_codec = JpegCodec::default();
+    assert!(jpeg_codec.validate_format(&jpeg));
+    assert!(!jpeg_codec.validate_format(&png));
+    assert_eq!(jpeg_codec.info().format, ImageFormat::Jpeg);
+
+    let png_codec = PngCodec::default();
+    assert!(png_codec.validate_format(&png));
+    assert!(!png_codec.validate_format(&jpeg));
+
+    // 不经过引擎直接解码
+    let decoded = jpeg_codec.decode(&jpeg).unwrap();
+    assert_eq!((decoded.dimensions().width, decoded.dimensions().height), (4, 4));
+}
+
+#[test]
+fn test_codec_validate_format_checks_signatures() {
+    use rustimage_core::codecs::{BmpCodec, Codec, JpegCodec, WebPCodec};
+
+    assert!(!JpegCodec::default().validate_format(&create_png()));
+
+    let mut webp = b"RIFF".to_vec();
+    webp.extend_from_slice(&[0x24, 0x00, 0x00, 0x00]);
+    webp.extend_from_slice(b"WEBPVP8 ");
+    assert!(WebPCodec::default().validate_format(&webp));
+    assert!(!WebPCodec::default().validate_format(b"RIFF\x24\x00\x00\x00WAVEfmt "));
+
+    let mut bmp = b"BM".to_vec();
+    bmp.resize(54, 0);
+    assert!(BmpCodec.validate_format(&bmp));
+    assert!(!BmpCodec.validate_format(&webp));
+}
+
+#[test]
+fn test_declared_format_mismatch_rejected_before_passthrough() {
+    use rustimage_core::converter::ConverterConfigBuilder;
+    use rustimage_core::ImageError;
+
+    // 关闭检测器校验后仍由编解码器签名检查拒绝，不会把PNG当作JPEG原样返回
+    let config = ConverterConfigBuilder::new().strict_input_validation(false).build();
+    let mut converter = FormatConverter::new(config).unwrap();
+    let result = converter.convert_format(&create_png(), ImageFormat::Jpeg, ImageFormat::Jpeg, None);
+    match result {
+        Err(ImageError::InvalidFormat { format }) => assert_eq!(format, "Data does not match format JPEG"),
+        other => panic!("expected InvalidFormat, got {:?}", other.map(|r| r.format())),
+    }
+}
@@ -0,0 +1,71 @@
+//! 格式解析测试
+//!
+//! 测试从字符串、扩展名和MIME类型识别图像格式
+
+use rustimage_core::{ImageError, ImageFormat};
+
+#[test]
+fn test_parse_names_and_extensions() {
+    let cases = [
+        ("jpeg", ImageFormat::Jpeg),
+        ("JPG", ImageFormat::Jpeg),
+        ("png", ImageFormat::Png),
+        ("WebP", ImageFormat::WebP),
+        ("avif", ImageFormat::Avif),
+        ("bmp", ImageFormat::Bmp),
+        ("tiff", ImageFormat::Tiff),
+        ("TIF", ImageFormat::Tiff),
+        ("gif", ImageFormat::Gif),
+        ("ico", ImageFormat::Ico),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(input.parse::<ImageFormat>().unwrap(), expected, "parse {}", input);
+        assert_eq!(ImageFormat::try_from(input).unwrap(), expected, "try_from {}", input);
+    }
+
+    // 每种格式的名称和全部扩展名都能被解析
+    for format in ImageFormat::ALL {
+        assert_eq!(format.info().name.parse::<ImageFormat>().unwrap(), *format);
+        for ext in format.info().extensions {
+            assert_eq!(ImageFormat::from_extension(ext), Some(*format));
+        }
+    }
+}
+
+#[test]
+fn test_parse_invalid_strings() {
+    for input in ["", "jpegg", "image/png", "p n g", "psd"] {
+        let result = input.parse::<ImageFormat>();
+        assert!(
+            matches!(result, Err(ImageError::InvalidFormat { .. })),
+            "{:?} should be rejected",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_from_extension_and_mime_type() {
+    assert_eq!(ImageFormat::from_extension(".JPEG"), Some(ImageFormat::Jpeg));
+    assert_eq!(ImageFormat::from_extension("txt"), None);
+
+    for format in ImageFormat::ALL {
+        assert_eq!(ImageFormat::from_mime_type(format.mime_type()), Some(*format));
+    }
+    assert_eq!(ImageFormat::from_mime_type("IMAGE/PNG; q=0.9"), Some(ImageFormat::Png));
+    assert_eq!(ImageFormat::from_mime_type("image/jpg"), Some(ImageFormat::Jpeg));
+    assert_eq!(ImageFormat::from_mime_type("text/html"), None);
+}
+
+#[test]
+fn test_from_path() {
+    use std::path::Path;
+
+    assert_eq!(ImageFormat::from_path(Path::new("IMG_0001.JPG")), Some(ImageFormat::Jpeg));
+    assert_eq!(ImageFormat::from_path(Path::new("out/photo.webp")), Some(ImageFormat::WebP));
+    assert_eq!(ImageFormat::from_path(Path::new("photo.final.jpeg")), Some(ImageFormat::Jpeg));
+    assert_eq!(ImageFormat::from_path(Path::new("out/photo")), None);
+    assert_eq!(ImageFormat::from_path(Path::new(".png")), None);
+    assert_eq!(ImageFormat::from_path(Path::new("notes.txt")), None);
+}
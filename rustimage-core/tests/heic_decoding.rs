@@ -0,0 +1,65 @@
+//! HEIC解码测试
+//!
+//! 测试`heif`特性开启时的解码，以及关闭时的错误报告
+
+use rustimage_core::{FormatConverter, ImageError, ImageFormat};
+
+#[cfg(not(feature = "heif"))]
+#[test]
+fn test_heic_without_feature_lists_enabled_formats() {
+    let mut heic = vec![0x00, 0x00, 0x00, 0x18];
+    heic.extend_from_slice(b"ftypheic\0\0\0\0mif1heic");
+
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    assert!(!converter.get_supported_formats().contains(&ImageFormat::Heic));
+
+    let result = converter.convert_format(&heic, ImageFormat::Heic, ImageFormat::Png, None);
+    match result {
+        Err(ImageError::UnsupportedFormat { format, supported }) => {
+            assert!(format.contains("heif"), "{}", format);
+            assert!(supported.contains(&"PNG".to_string()));
+            assert!(!supported.contains(&"HEIC".to_string()));
+        }
+        other => panic!("expected UnsupportedFormat, got {:?}", other.map(|r| r.format())),
+    }
+}
+
+#[cfg(feature = "heif")]
+#[test]
+fn test_heic_decodes_to_png() {
+    use libheif_rs::{Channel, ColorSpace, CompressionFormat, EncoderQuality, HeifContext, Image, LibHeif, RgbChroma};
+
+    // 用libheif编码一张纯色HEIC作为输入
+    let (width, height) = (64u32, 48u32);
+    let mut source = Image::new(width, height, ColorSpace::Rgb(RgbChroma::Rgb)).unwrap();
+    source.create_plane(Channel::Interleaved, width, height, 8).unwrap();
+    {
+        let plane = source.planes_mut().interleaved.unwrap();
+        for row in plane.data.chunks_mut(plane.stride).take(height as usize) {
+            for pixel in row[..width as usize * 3].chunks_exact_mut(3) {
+                pixel.copy_from_slice(&[200, 60, 30]);
+            }
+        }
+    }
+    let mut encoder = LibHeif::new().encoder_for_format(CompressionFormat::Hevc).unwrap();
+    encoder.set_quality(EncoderQuality::Lossy(90)).unwrap();
+    let mut context = HeifContext::new().unwrap();
+    context.encode_image(&source, &mut encoder, None).unwrap();
+    let heic = context.write_to_bytes().unwrap();
+
+    assert_eq!(rustimage_core::detect_format(&heic).unwrap(), ImageFormat::Heic);
+
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let result = converter
+        .convert_format(&heic, ImageFormat::Heic, ImageFormat::Png, None)
+        .unwrap();
+    assert_eq!((result.dimensions().width, result.dimensions().height), (width, height));
+
+    let decoded = image::load_from_memory(result.data()).unwrap().to_rgb8();
+    let center = decoded.get_pixel(width / 2, height / 2);
+    assert!(center[0].abs_diff(200) <= 8 && center[1].abs_diff(60) <= 8, "{:?}", center);
+
+    // 编码不受支持
+    let encode = converter.convert_format(result.data(), ImageFormat::Png, ImageFormat::Heic, None);
+    assert!(matches!(encode, Err(ImageError::UnsupportedOperation { .. })));
+}
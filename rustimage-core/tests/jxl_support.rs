@@ -0,0 +1,102 @@
+//! JPEG XL测试
+//!
+//! 测试两种签名的检测、`jxl`特性下的往返转换，以及特性关闭时的错误报告
+
+use rustimage_core::{detect_format, FormatConverter, ImageError, ImageFormat};
+use image::{ImageBuffer, ImageEncoder, Rgba};
+
+/// JPEG XL容器签名盒
+const CONTAINER_SIGNATURE: [u8; 12] = [0x00, 0x00, 0x00, 0x0C, b'J', b'X', b'L', b' ', 0x0D, 0x0A, 0x87, 0x0A];
+
+/// 创建带透明度渐变的PNG
+fn create_png(width: u32, height: u32) -> Vec<u8> {
+    let img = ImageBuffer::from_fn(width, height, |x, y| {
+        Rgba([(x * 255 / width) as u8, (y * 255 / height) as u8, 90, (128 + x) as u8])
+    });
+    let mut data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut data)
+        .write_image(img.as_raw(), width, height, image::ColorType::Rgba8)
+        .unwrap();
+    data
+}
+
+/// 将裸码流包装进JPEG XL容器（签名盒 + ftyp + jxlc）
+#[cfg_attr(not(feature = "jxl"), allow(dead_code))]
+fn wrap_in_container(codestream: &[u8]) -> Vec<u8> {
+    let mut data = CONTAINER_SIGNATURE.to_vec();
+    data.extend_from_slice(&[0x00, 0x00, 0x00, 0x14]);
+    data.extend_from_slice(b"ftypjxl \0\0\0\0jxl ");
+    data.extend_from_slice(&(8 + codestream.len() as u32).to_be_bytes());
+    data.extend_from_slice(b"jxlc");
+    data.extend_from_slice(codestream);
+    data
+}
+
+#[test]
+fn test_jxl_signatures_detected() {
+    let mut codestream = vec![0xFF, 0x0A];
+    codestream.resize(16, 0);
+    assert_eq!(detect_format(&codestream).unwrap(), ImageFormat::Jxl);
+
+    let mut container = CONTAINER_SIGNATURE.to_vec();
+    container.extend_from_slice(&[0x00, 0x00, 0x00, 0x14]);
+    assert_eq!(detect_format(&container).unwrap(), ImageFormat::Jxl);
+
+    assert_eq!(ImageFormat::from_extension("jxl"), Some(ImageFormat::Jxl));
+    assert_eq!(ImageFormat::from_mime_type("image/jxl"), Some(ImageFormat::Jxl));
+}
+
+#[cfg(not(feature = "jxl"))]
+#[test]
+fn test_jxl_without_feature_is_unsupported_format() {
+    let png = create_png(8, 8);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    assert!(!converter.get_supported_formats().contains(&ImageFormat::Jxl));
+
+    let result = converter.convert_format(&png, ImageFormat::Png, ImageFormat::Jxl, None);
+    match result {
+        Err(ImageError::UnsupportedFormat { format, supported }) => {
+            assert!(format.contains("jxl"), "{}", format);
+            assert!(supported.contains(&"PNG".to_string()));
+            assert!(!supported.contains(&"JXL".to_string()));
+        }
+        other => panic!("expected UnsupportedFormat, got {:?}", other.map(|r| r.format())),
+    }
+}
+
+#[cfg(feature = "jxl")]
+#[test]
+fn test_jxl_round_trip_is_lossless() {
+    let png = create_png(40, 24);
+    let original = image::load_from_memory(&png).unwrap().to_rgba8();
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let jxl = converter
+        .convert_format(&png, ImageFormat::Png, ImageFormat::Jxl, None)
+        .unwrap();
+    assert_eq!(detect_format(jxl.data()).unwrap(), ImageFormat::Jxl);
+
+    // 裸码流和容器两种封装都能解码
+    for data in [jxl.data().to_vec(), wrap_in_container(jxl.data())] {
+        let back = converter
+            .convert_format(&data, ImageFormat::Jxl, ImageFormat::Png, None)
+            .unwrap();
+        let decoded = image::load_from_memory(back.data()).unwrap().to_rgba8();
+        assert_eq!(decoded.dimensions(), (40, 24));
+        assert_eq!(decoded.as_raw(), original.as_raw());
+    }
+}
+
+#[cfg(feature = "jxl")]
+#[test]
+fn test_jxl_decode_rejects_truncated_data() {
+    let png = create_png(16, 16);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let jxl = converter
+        .convert_format(&png, ImageFormat::Png, ImageFormat::Jxl, None)
+        .unwrap();
+
+    let truncated = &jxl.data()[..jxl.data().len() / 2];
+    let result = converter.convert_format(truncated, ImageFormat::Jxl, ImageFormat::Png, None);
+    assert!(matches!(result, Err(ImageError::DecodeError { .. })));
+}
@@ -0,0 +1,37 @@
+//! 分配跟踪测试
+//!
+//! 需要启用`track-alloc` feature：`cargo test --features track-alloc --test memory_tracking`
+
+#![cfg(feature = "track-alloc")]
+
+use image::{ImageEncoder, Rgba};
+use rustimage_core::converter::ConverterConfigBuilder;
+use rustimage_core::{FormatConverter, ImageFormat};
+
+#[test]
+fn test_conversion_records_peak_memory() {
+    let (width, height) = (1024u32, 1024u32);
+    let img = image::ImageBuffer::from_fn(width, height, |x, y| {
+        Rgba([x as u8, y as u8, (x ^ y) as u8, 255])
+    });
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), width, height, image::ColorType::Rgba8)
+        .unwrap();
+
+    let config = ConverterConfigBuilder::new()
+        .enable_performance_monitoring(true)
+        .build();
+    let mut converter = FormatConverter::new(config).unwrap();
+    converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, None)
+        .unwrap();
+
+    let memory = converter.get_conversion_statistics().performance_metrics.memory;
+    let decoded_bytes = (width * height * 4) as u64;
+    assert!(memory.allocations_count > 0, "{:?}", memory);
+    assert!(memory.deallocations_count > 0, "{:?}", memory);
+    // 峰值至少容纳一份解码后的RGBA缓冲区，且不超过其数倍
+    assert!(memory.peak_memory_bytes >= decoded_bytes, "{:?}", memory);
+    assert!(memory.peak_memory_bytes <= decoded_bytes * 8, "{:?}", memory);
+}
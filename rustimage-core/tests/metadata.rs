@@ -0,0 +1,100 @@
+//! 元数据读取测试
+//!
+//! 测试从EXIF解析方向、拍摄时间、GPS坐标和相机信息
+
+use rustimage_core::{FormatConverter, ImageError, ImageFormat, ImageMetadata};
+use image::{ImageBuffer, ImageEncoder, Rgb};
+
+fn push_entry(tiff: &mut Vec<u8>, tag: u16, field_type: u16, count: u32, value: [u8; 4]) {
+    tiff.extend_from_slice(&tag.to_be_bytes());
+    tiff.extend_from_slice(&field_type.to_be_bytes());
+    tiff.extend_from_slice(&count.to_be_bytes());
+    tiff.extend_from_slice(&value);
+}
+
+fn push_rationals(tiff: &mut Vec<u8>, values: [(u32, u32); 3]) {
+    for (numerator, denominator) in values {
+        tiff.extend_from_slice(&numerator.to_be_bytes());
+        tiff.extend_from_slice(&denominator.to_be_bytes());
+    }
+}
+
+/// 大端TIFF结构的EXIF - 悉尼歌剧院附近（33°51'54.36"S 151°12'55.8"E）
+fn geotagged_exif() -> Vec<u8> {
+    let mut tiff = b"MM\0\x2A".to_vec();
+    tiff.extend_from_slice(&8u32.to_be_bytes());
+
+    // IFD0（8..74）：Make@74、Model@80、方向6、EXIF IFD@88、GPS IFD@126
+    tiff.extend_from_slice(&5u16.to_be_bytes());
+    push_entry(&mut tiff, 0x010F, 2, 6, 74u32.to_be_bytes());
+    push_entry(&mut tiff, 0x0110, 2, 7, 80u32.to_be_bytes());
+    push_entry(&mut tiff, 0x0112, 3, 1, [0, 6, 0, 0]);
+    push_entry(&mut tiff, 0x8769, 4, 1, 88u32.to_be_bytes());
+    push_entry(&mut tiff, 0x8825, 4, 1, 126u32.to_be_bytes());
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+    tiff.extend_from_slice(b"Canon\0");
+    tiff.extend_from_slice(b"EOS R5\0\0");
+
+    // EXIF IFD（88..106）：DateTimeOriginal@106
+    tiff.extend_from_slice(&1u16.to_be_bytes());
+    push_entry(&mut tiff, 0x9003, 2, 20, 106u32.to_be_bytes());
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+    tiff.extend_from_slice(b"2024:05:01 12:34:56\0");
+
+    // GPS IFD（126..180）：纬度@180、经度@204
+    assert_eq!(tiff.len(), 126);
+    tiff.extend_from_slice(&4u16.to_be_bytes());
+    push_entry(&mut tiff, 0x0001, 2, 2, *b"S\0\0\0");
+    push_entry(&mut tiff, 0x0002, 5, 3, 180u32.to_be_bytes());
+    push_entry(&mut tiff, 0x0003, 2, 2, *b"E\0\0\0");
+    push_entry(&mut tiff, 0x0004, 5, 3, 204u32.to_be_bytes());
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+    push_rationals(&mut tiff, [(33, 1), (51, 1), (5436, 100)]);
+    push_rationals(&mut tiff, [(151, 1), (12, 1), (558, 10)]);
+    tiff
+}
+
+fn plain_jpeg() -> Vec<u8> {
+    let img = ImageBuffer::from_fn(16, 16, |x, y| Rgb([x as u8 * 16, y as u8 * 16, 100]));
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new(&mut jpeg)
+        .write_image(img.as_raw(), 16, 16, image::ColorType::Rgb8)
+        .expect("Failed to encode JPEG");
+    jpeg
+}
+
+fn with_exif_segment(jpeg: &[u8], tiff: &[u8]) -> Vec<u8> {
+    let mut data = jpeg[..2].to_vec();
+    data.extend_from_slice(&[0xFF, 0xE1]);
+    data.extend_from_slice(&((2 + 6 + tiff.len()) as u16).to_be_bytes());
+    data.extend_from_slice(b"Exif\0\0");
+    data.extend_from_slice(tiff);
+    data.extend_from_slice(&jpeg[2..]);
+    data
+}
+
+#[test]
+fn test_read_metadata_from_geotagged_jpeg() {
+    let jpeg = with_exif_segment(&plain_jpeg(), &geotagged_exif());
+    let converter = FormatConverter::with_defaults().unwrap();
+    let metadata = converter.read_metadata(&jpeg, ImageFormat::Jpeg).unwrap();
+
+    let gps = metadata.gps.expect("GPS coordinates should be parsed");
+    assert!((gps.latitude - -33.8651).abs() < 1e-6, "latitude {}", gps.latitude);
+    assert!((gps.longitude - 151.2155).abs() < 1e-6, "longitude {}", gps.longitude);
+    assert_eq!(metadata.orientation, Some(6));
+    assert_eq!(metadata.datetime.as_deref(), Some("2024:05:01 12:34:56"));
+    assert_eq!(metadata.camera_make.as_deref(), Some("Canon"));
+    assert_eq!(metadata.camera_model.as_deref(), Some("EOS R5"));
+}
+
+#[test]
+fn test_read_metadata_without_exif_is_empty() {
+    let converter = FormatConverter::with_defaults().unwrap();
+    let metadata = converter.read_metadata(&plain_jpeg(), ImageFormat::Jpeg).unwrap();
+    assert_eq!(metadata, ImageMetadata::default());
+
+    // 声明的格式与数据不符时报错
+    let error = converter.read_metadata(&plain_jpeg(), ImageFormat::Png).unwrap_err();
+    assert!(matches!(error, ImageError::InvalidFormat { .. }), "{:?}", error);
+}
@@ -0,0 +1,203 @@
+//! 性能报告测试
+//!
+//! 测试性能监控器按操作汇总统计并生成优化建议
+
+use rustimage_core::performance::{PerformanceMonitor, RecommendationType};
+use rustimage_core::ImageFormat;
+use std::time::Duration;
+
+fn record(monitor: &PerformanceMonitor, from: ImageFormat, to: ImageFormat, millis: u64) {
+    monitor.start_conversion(&from, &to);
+    monitor.end_conversion(Duration::from_millis(millis), true);
+}
+
+#[test]
+fn test_report_aggregates_operation_stats() {
+    let monitor = PerformanceMonitor::new(true).unwrap();
+    for millis in [4, 5, 6] {
+        record(&monitor, ImageFormat::Png, ImageFormat::Jpeg, millis);
+    }
+    for millis in [10, 30] {
+        record(&monitor, ImageFormat::Jpeg, ImageFormat::Png, millis);
+    }
+
+    let report = monitor.generate_report().unwrap();
+    let stats = &report.detailed_statistics.operation_stats;
+    assert_eq!(stats.len(), 2);
+
+    // 按总耗时降序排列
+    let jpeg_to_png = &stats[0];
+    assert!(jpeg_to_png.operation_name.contains("JPEG"));
+    assert_eq!(jpeg_to_png.execution_count, 2);
+    assert!((jpeg_to_png.total_time_ms - 40.0).abs() < 1e-6);
+    assert!((jpeg_to_png.average_time_ms - 20.0).abs() < 1e-6);
+    assert!((jpeg_to_png.min_time_ms - 10.0).abs() < 1e-6);
+    assert!((jpeg_to_png.max_time_ms - 30.0).abs() < 1e-6);
+    assert_eq!(jpeg_to_png.success_rate, 1.0);
+
+    let png_to_jpeg = &stats[1];
+    assert_eq!(png_to_jpeg.execution_count, 3);
+    assert!((png_to_jpeg.average_time_ms - 5.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_report_recommends_for_slow_operation() {
+    let monitor = PerformanceMonitor::new(true).unwrap();
+    for _ in 0..4 {
+        record(&monitor, ImageFormat::Png, ImageFormat::Jpeg, 2);
+    }
+    record(&monitor, ImageFormat::Tiff, ImageFormat::WebP, 50);
+
+    let report = monitor.generate_report().unwrap();
+    let recommendation = report
+        .optimization_recommendations
+        .iter()
+        .find(|r| r.recommendation_type == RecommendationType::ParallelizationOptimization)
+        .expect("slow operation should produce a recommendation");
+    assert!(recommendation.description.contains("TIFF"));
+}
+
+#[test]
+fn test_report_without_data_has_no_recommendations() {
+    let monitor = PerformanceMonitor::new(true).unwrap();
+    let report = monitor.generate_report().unwrap();
+
+    assert!(report.detailed_statistics.operation_stats.is_empty());
+    assert!(report.optimization_recommendations.is_empty());
+}
+
+#[test]
+fn test_conversion_records_stage_timings() {
+    use image::{ImageEncoder, Rgba};
+    use rustimage_core::converter::ConverterConfigBuilder;
+    use rustimage_core::FormatConverter;
+
+    let img = image::ImageBuffer::from_fn(256, 256, |x, y| {
+        Rgba([x as u8, y as u8, (x ^ y) as u8, 255])
+    });
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 256, 256, image::ColorType::Rgba8)
+        .unwrap();
+
+    let config = ConverterConfigBuilder::new()
+        .enable_performance_monitoring(true)
+        .build();
+    let mut converter = FormatConverter::new(config).unwrap();
+    converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, None)
+        .unwrap();
+
+    let timing = converter.get_conversion_statistics().performance_metrics.timing;
+    assert!(timing.decode_time_ms > 0.0);
+    assert!(timing.encode_time_ms > 0.0);
+
+    // 阶段耗时之和不超过总耗时，且占据总耗时的主要部分
+    let stages = timing.decode_time_ms + timing.encode_time_ms + timing.processing_time_ms;
+    assert!(stages <= timing.total_time_ms, "{:?}", timing);
+    assert!(timing.decode_time_ms + timing.encode_time_ms >= timing.total_time_ms * 0.5, "{:?}", timing);
+}
+
+#[test]
+fn test_report_recommends_when_decode_dominates() {
+    use rustimage_core::TimingMetrics;
+
+    let monitor = PerformanceMonitor::new(true).unwrap();
+    for _ in 0..3 {
+        monitor.start_conversion(&ImageFormat::Png, &ImageFormat::Jpeg);
+        monitor.record_stage_timing(&TimingMetrics {
+            total_time_ms: 10.0,
+            decode_time_ms: 8.0,
+            encode_time_ms: 1.5,
+            processing_time_ms: 0.5,
+        });
+        monitor.end_conversion(Duration::from_millis(10), true);
+    }
+
+    let report = monitor.generate_report().unwrap();
+    assert!(report
+        .optimization_recommendations
+        .iter()
+        .any(|r| r.recommendation_type == RecommendationType::AlgorithmOptimization
+            && r.description.contains("解码")));
+}
+
+#[test]
+fn test_release_history_keeps_accumulated_stats() {
+    let mut monitor = PerformanceMonitor::new(true).unwrap();
+    for millis in [4, 5, 6] {
+        record(&monitor, ImageFormat::Png, ImageFormat::Jpeg, millis);
+    }
+    assert!(monitor.retained_bytes() > 0);
+
+    monitor.release_history();
+    assert_eq!(monitor.retained_bytes(), 0);
+
+    // 累积统计不受影响
+    let report = monitor.generate_report().unwrap();
+    assert_eq!(report.detailed_statistics.operation_stats[0].execution_count, 3);
+
+    // 释放后继续记录
+    record(&monitor, ImageFormat::Png, ImageFormat::Jpeg, 5);
+    assert!(monitor.retained_bytes() > 0);
+}
+
+#[test]
+fn test_conversion_records_cpu_usage() {
+    use image::{ImageEncoder, Rgba};
+    use rustimage_core::converter::ConverterConfigBuilder;
+    use rustimage_core::FormatConverter;
+
+    let img = image::ImageBuffer::from_fn(512, 512, |x, y| {
+        Rgba([x as u8, y as u8, (x ^ y) as u8, 255])
+    });
+    let mut png_data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_data)
+        .write_image(img.as_raw(), 512, 512, image::ColorType::Rgba8)
+        .unwrap();
+
+    let config = ConverterConfigBuilder::new()
+        .enable_performance_monitoring(true)
+        .build();
+    let mut converter = FormatConverter::new(config).unwrap();
+    converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, None)
+        .unwrap();
+
+    let system = converter.get_conversion_statistics().performance_metrics.system;
+    assert!((0.0..=100.0).contains(&system.cpu_usage_percent), "{:?}", system);
+    assert!(system.threads_used >= 1, "{:?}", system);
+}
+
+#[test]
+fn test_warmup_removes_first_conversion_overhead() {
+    use image::{ImageEncoder, Rgba, RgbaImage};
+    use rustimage_core::FormatConverter;
+    use std::time::Instant;
+
+    let img = RgbaImage::from_fn(64, 64, |x, y| Rgba([(x * 4) as u8, (y * 4) as u8, 100, 255]));
+    let mut png = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png)
+        .write_image(img.as_raw(), 64, 64, image::ColorType::Rgba8)
+        .unwrap();
+
+    let mut converter = FormatConverter::with_high_performance().unwrap();
+    converter.warmup().unwrap();
+    assert_eq!(converter.get_conversion_statistics().total_conversions, 0);
+
+    let mut timed = || {
+        let start = Instant::now();
+        converter.convert_format(&png, ImageFormat::Png, ImageFormat::WebP, None).unwrap();
+        start.elapsed()
+    };
+    let first = timed();
+    let second = timed();
+
+    // 宽松的时间断言 - 只排除数量级上的初始化开销
+    assert!(
+        first <= second * 10 + Duration::from_millis(50),
+        "first {:?} vs second {:?}",
+        first,
+        second
+    );
+}
@@ -0,0 +1,512 @@
+//! 像素处理测试
+//!
+//! 测试转换过程中由选项触发的像素变换
+
+use rustimage_core::{ConversionOptionsBuilder, FormatConverter, ImageError, ImageFormat, Rgb8};
+use image::{ImageBuffer, ImageEncoder, Rgba};
+
+/// 创建彩色渐变PNG
+fn create_color_png(width: u32, height: u32) -> Vec<u8> {
+    let img = ImageBuffer::from_fn(width, height, |x, y| {
+        Rgba([(x * 255 / width) as u8, (y * 255 / height) as u8, 200, 255])
+    });
+    encode_png(&img)
+}
+
+/// 将RGBA图像编码为PNG
+fn encode_png(img: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> Vec<u8> {
+    let mut data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut data)
+        .write_image(img.as_raw(), img.width(), img.height(), image::ColorType::Rgba8)
+        .unwrap();
+    data
+}
+
+#[test]
+fn test_grayscale_jpeg_has_equal_channels() {
+    let png = create_color_png(32, 32);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let options = ConversionOptionsBuilder::new().grayscale(true).build();
+    let result = converter
+        .convert_format(&png, ImageFormat::Png, ImageFormat::Jpeg, Some(options))
+        .unwrap();
+
+    let decoded = image::load_from_memory(result.data()).unwrap().to_rgb8();
+    for pixel in decoded.pixels() {
+        assert_eq!(pixel[0], pixel[1]);
+        assert_eq!(pixel[1], pixel[2]);
+    }
+}
+
+#[test]
+fn test_grayscale_png_uses_bt709_and_keeps_alpha() {
+    let img = ImageBuffer::from_pixel(4, 4, Rgba([0u8, 255, 0, 128]));
+    let png = encode_png(&img);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let options = ConversionOptionsBuilder::new().grayscale(true).build();
+    let result = converter
+        .convert_format(&png, ImageFormat::Png, ImageFormat::Png, Some(options))
+        .unwrap();
+
+    // 纯绿色的BT.709亮度为 0.7152 * 255 ≈ 182
+    let decoded = image::load_from_memory(result.data()).unwrap().to_rgba8();
+    assert_eq!(decoded.get_pixel(0, 0).0, [182, 182, 182, 128]);
+}
+
+#[test]
+fn test_transparent_png_flattens_over_background() {
+    let img = ImageBuffer::from_pixel(16, 16, Rgba([255u8, 0, 0, 128]));
+    let png = encode_png(&img);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let options = ConversionOptionsBuilder::new()
+        .background(Rgb8 { r: 0, g: 0, b: 255 })
+        .build();
+    let result = converter
+        .convert_format(&png, ImageFormat::Png, ImageFormat::Jpeg, Some(options))
+        .unwrap();
+
+    // 50%红色叠加在蓝色上得到紫色
+    let decoded = image::load_from_memory(result.data()).unwrap().to_rgb8();
+    let [r, g, b] = decoded.get_pixel(8, 8).0;
+    assert!((100..=155).contains(&r), "red channel {}", r);
+    assert!(g < 30, "green channel {}", g);
+    assert!((100..=155).contains(&b), "blue channel {}", b);
+}
+
+#[test]
+fn test_flatten_defaults_to_white() {
+    let img = ImageBuffer::from_pixel(16, 16, Rgba([0u8, 0, 0, 0]));
+    let png = encode_png(&img);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let result = converter
+        .convert_format(&png, ImageFormat::Png, ImageFormat::Jpeg, None)
+        .unwrap();
+
+    let decoded = image::load_from_memory(result.data()).unwrap().to_rgb8();
+    assert!(decoded.pixels().all(|p| p.0.iter().all(|&c| c > 245)));
+}
+
+#[test]
+fn test_simd_luma_matches_scalar() {
+    use rustimage_core::codecs::{ImageBuffer as RgbaBuffer, PixelFormat};
+    use rustimage_core::{Luma8, Rgba as Pixel};
+
+    // 覆盖所有通道取值组合的代表样本，长度不是8的倍数以覆盖尾部
+    let pixels: Vec<_> = (0..4099u32)
+        .map(|i| {
+            let v = i.wrapping_mul(2654435761);
+            Pixel { r: v as u8, g: (v >> 8) as u8, b: (v >> 16) as u8, a: (v >> 24) as u8 }
+        })
+        .collect();
+    let buffer = RgbaBuffer::from_raw(4099, 1, pixels, PixelFormat::Rgba8).unwrap();
+
+    let simd = buffer.to_luma();
+    let scalar = buffer.clone().convert_pixel_format::<Luma8>();
+    assert_eq!(simd.as_slice(), scalar.as_slice());
+    assert_eq!(simd.pixel_format(), PixelFormat::Gray8);
+}
+
+#[test]
+fn test_copy_region_and_blit() {
+    use rustimage_core::codecs::{ImageBuffer as RgbaBuffer, PixelFormat};
+    use rustimage_core::{ImageError, Rgba as Pixel};
+
+    // 每个像素的r通道编码其坐标：r = y * 4 + x
+    let pixels: Vec<_> = (0..16u8).map(|i| Pixel { r: i, g: 0, b: 0, a: 255 }).collect();
+    let mut buffer = RgbaBuffer::from_raw(4, 4, pixels, PixelFormat::Rgba8).unwrap();
+
+    let region = buffer.copy_region(1, 1, 2, 2).unwrap();
+    assert_eq!(region.dimensions().width, 2);
+    assert_eq!(region.dimensions().height, 2);
+    let values: Vec<u8> = region.as_slice().iter().map(|p| p.r).collect();
+    assert_eq!(values, [5, 6, 9, 10]);
+
+    buffer.blit(&region, 2, 0).unwrap();
+    let values: Vec<u8> = buffer.as_slice().iter().map(|p| p.r).collect();
+    assert_eq!(values, [0, 1, 5, 6, 4, 5, 9, 10, 8, 9, 10, 11, 12, 13, 14, 15]);
+
+    // 越界和空区域被拒绝，缓冲区保持不变
+    for (x, y, w, h) in [(3, 3, 2, 1), (0, 0, 0, 1), (u32::MAX, 0, 2, 2)] {
+        assert!(matches!(buffer.copy_region(x, y, w, h), Err(ImageError::InvalidParameters { .. })));
+    }
+    assert!(buffer.blit(&region, 3, 0).is_err());
+    assert!(buffer.blit(&region, 0, 3).is_err());
+    assert_eq!(buffer.as_slice()[3].r, 6);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_par_rows_mut_zeroes_even_rows() {
+    use rayon::prelude::*;
+    use rustimage_core::codecs::{ImageBuffer as RgbaBuffer, PixelFormat};
+    use rustimage_core::Rgba as Pixel;
+
+    let (width, height) = (5u32, 6u32);
+    let pixels = vec![Pixel { r: 7u8, g: 8, b: 9, a: 255 }; (width * height) as usize];
+    let mut buffer = RgbaBuffer::from_raw(width, height, pixels, PixelFormat::Rgba8).unwrap();
+
+    buffer.par_rows_mut().enumerate().filter(|(y, _)| y % 2 == 0).for_each(|(_, row)| {
+        row.fill(Pixel { r: 0, g: 0, b: 0, a: 0 });
+    });
+
+    assert_eq!(buffer.rows().count(), height as usize);
+    for (y, row) in buffer.rows().enumerate() {
+        assert_eq!(row.len(), width as usize);
+        let expected = if y % 2 == 0 { 0 } else { 7 };
+        assert!(row.iter().all(|p| p.r == expected), "row {}", y);
+    }
+}
+
+#[test]
+fn test_histogram_of_gradient_is_uniform() {
+    use rustimage_core::codecs::{ImageBuffer as RgbaBuffer, PixelFormat};
+    use rustimage_core::{HistogramChannel, Rgba as Pixel};
+
+    // 足够大以覆盖并行分块路径
+    let (width, height) = (256u32, 300u32);
+    let pixels: Vec<_> = (0..width * height)
+        .map(|i| {
+            let v = (i % width) as u8;
+            Pixel { r: v, g: v, b: v, a: 255 }
+        })
+        .collect();
+    let buffer = RgbaBuffer::from_raw(width, height, pixels, PixelFormat::Rgba8).unwrap();
+
+    let histogram = buffer.histogram();
+    assert_eq!(histogram.total(), (width * height) as u64);
+
+    let luma = histogram.channel(HistogramChannel::Luma);
+    let (min, max) = (luma.iter().min().unwrap(), luma.iter().max().unwrap());
+    assert!(*min > 0 && *max - *min <= height as u64 / 10, "min {} max {}", min, max);
+    assert_eq!(histogram.channel(HistogramChannel::Red), &[height as u64; 256]);
+    assert_eq!(histogram.channel(HistogramChannel::Alpha)[255], (width * height) as u64);
+
+    assert_eq!(histogram.percentile(HistogramChannel::Luma, 0.0), 0);
+    assert_eq!(histogram.percentile(HistogramChannel::Luma, 1.0), 2);
+    assert_eq!(histogram.percentile(HistogramChannel::Luma, 50.0), 127);
+    assert_eq!(histogram.percentile(HistogramChannel::Luma, 99.0), 253);
+    assert_eq!(histogram.percentile(HistogramChannel::Luma, 100.0), 255);
+}
+
+#[test]
+fn test_auto_contrast_widens_value_range() {
+    // 低对比度：取值集中在100-140之间
+    let low_contrast = ImageBuffer::from_fn(64, 16, |x, _| {
+        let v = 100 + (x * 40 / 63) as u8;
+        Rgba([v, v, v, 255])
+    });
+    let options = ConversionOptionsBuilder::new().auto_contrast(1.0).build();
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let result = converter
+        .convert_format(&encode_png(&low_contrast), ImageFormat::Png, ImageFormat::Png, Some(options.clone()))
+        .unwrap();
+    let output = image::load_from_memory(result.data()).unwrap().to_rgba8();
+    let (min, max) = output.pixels().fold((u8::MAX, 0), |(lo, hi), p| (lo.min(p.0[0]), hi.max(p.0[0])));
+    assert!(min < 10 && max > 245, "range {}..={}", min, max);
+    assert!(converter.is_conversion_lossy(ImageFormat::Png, ImageFormat::Png, &options));
+
+    // 纯色图像不拉伸，也不会除零
+    let flat = ImageBuffer::from_pixel(8, 8, Rgba([90u8, 90, 90, 255]));
+    let result = converter
+        .convert_format(&encode_png(&flat), ImageFormat::Png, ImageFormat::Png, Some(options))
+        .unwrap();
+    let output = image::load_from_memory(result.data()).unwrap().to_rgba8();
+    assert_eq!(output.as_raw(), flat.as_raw());
+
+    // 裁剪比例超出范围被拒绝
+    let invalid = ConversionOptionsBuilder::new().auto_contrast(50.0).build();
+    assert!(converter
+        .convert_format(&encode_png(&flat), ImageFormat::Png, ImageFormat::Png, Some(invalid))
+        .is_err());
+}
+
+#[test]
+fn test_grayscale_reports_simd_usage() {
+    use rustimage_core::converter::ConverterConfigBuilder;
+
+    let png_data = create_color_png(16, 16);
+    let options = ConversionOptionsBuilder::new().grayscale(true).build();
+    let config = ConverterConfigBuilder::new()
+        .enable_simd(true)
+        .enable_performance_monitoring(true)
+        .build();
+    let mut converter = FormatConverter::new(config).unwrap();
+
+    converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::Png, Some(options))
+        .unwrap();
+
+    let metrics = converter.get_conversion_statistics().performance_metrics;
+    assert_eq!(metrics.system.simd_utilized, cfg!(feature = "simd"));
+}
+
+#[test]
+fn test_transparent_png_to_jpeg_warns_alpha_flattened() {
+    use rustimage_core::ConversionWarning;
+
+    let png = encode_png(&ImageBuffer::from_pixel(8, 8, Rgba([255u8, 0, 0, 100])));
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let result = converter
+        .convert_format(&png, ImageFormat::Png, ImageFormat::Jpeg, None)
+        .unwrap();
+    assert!(result
+        .warnings()
+        .contains(&ConversionWarning::AlphaFlattened { background: Rgb8 { r: 255, g: 255, b: 255 } }));
+
+    // 不透明图像无需合成，也就没有警告
+    let opaque = create_color_png(8, 8);
+    let result = converter
+        .convert_format(&opaque, ImageFormat::Png, ImageFormat::Jpeg, None)
+        .unwrap();
+    assert!(result.warnings().is_empty());
+}
+
+#[test]
+fn test_oversized_ico_warns_downscaled() {
+    use rustimage_core::{ConversionWarning, ImageDimensions};
+
+    let png = create_color_png(300, 200);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let result = converter
+        .convert_format(&png, ImageFormat::Png, ImageFormat::Ico, None)
+        .unwrap();
+    let expected = ImageDimensions { width: 256, height: 170 };
+    assert_eq!(result.dimensions(), expected);
+    assert_eq!(
+        result.warnings(),
+        [ConversionWarning::Downscaled { from: ImageDimensions { width: 300, height: 200 }, to: expected }]
+    );
+}
+
+/// 相邻像素差的平方和 - 衡量高频（混叠）能量
+fn high_frequency_energy(img: &image::RgbImage) -> u64 {
+    let (width, height) = img.dimensions();
+    let diff = |a: &image::Rgb<u8>, b: &image::Rgb<u8>| (a[0] as i64 - b[0] as i64).pow(2) as u64;
+    let mut energy = 0;
+    for y in 0..height {
+        for x in 0..width {
+            let pixel = img.get_pixel(x, y);
+            if x + 1 < width {
+                energy += diff(pixel, img.get_pixel(x + 1, y));
+            }
+            if y + 1 < height {
+                energy += diff(pixel, img.get_pixel(x, y + 1));
+            }
+        }
+    }
+    energy
+}
+
+#[test]
+fn test_lanczos_downscale_aliases_less_than_nearest() {
+    use rustimage_core::ResizeFilter;
+
+    // 单像素黑白棋盘格 - 缩小时最容易产生混叠
+    let checkerboard = ImageBuffer::from_fn(96, 96, |x, y| {
+        let v = if (x + y) % 2 == 0 { 0u8 } else { 255 };
+        Rgba([v, v, v, 255])
+    });
+    let png = encode_png(&checkerboard);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let mut downscale = |filter| {
+        let options = ConversionOptionsBuilder::new().resize(37, 37).downscale_filter(filter).build();
+        let result = converter
+            .convert_format(&png, ImageFormat::Png, ImageFormat::Png, Some(options))
+            .unwrap();
+        high_frequency_energy(&image::load_from_memory(result.data()).unwrap().to_rgb8())
+    };
+
+    let nearest = downscale(ResizeFilter::Nearest);
+    let lanczos = downscale(ResizeFilter::Lanczos3);
+    assert!(lanczos < nearest, "lanczos {} vs nearest {}", lanczos, nearest);
+}
+
+#[test]
+fn test_linear_resize_averages_in_linear_light() {
+    use rustimage_core::ResizeFilter;
+
+    let checkerboard = ImageBuffer::from_fn(8, 8, |x, y| {
+        let v = if (x + y) % 2 == 0 { 0u8 } else { 255 };
+        Rgba([v, v, v, 255])
+    });
+    let png = encode_png(&checkerboard);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let mut average = |linear| {
+        let options = ConversionOptionsBuilder::new()
+            .resize(1, 1)
+            .downscale_filter(ResizeFilter::Triangle)
+            .linear_resize(linear)
+            .build();
+        let result = converter
+            .convert_format(&png, ImageFormat::Png, ImageFormat::Png, Some(options))
+            .unwrap();
+        image::load_from_memory(result.data()).unwrap().to_rgb8().get_pixel(0, 0)[0]
+    };
+
+    // 线性光中50%亮度编码为sRGB约为188，直接平均编码值则约为128
+    let linear = average(true);
+    let naive = average(false);
+    assert!(linear.abs_diff(188) <= 2, "linear resize gave {}", linear);
+    assert!(naive.abs_diff(128) <= 2, "naive resize gave {}", naive);
+}
+
+#[test]
+fn test_integer_downscale_uses_box_average() {
+    // 四个纯色象限，左上象限再混入一个黑白棋盘块
+    let colors = [[200u8, 40, 10, 255], [10, 220, 30, 255], [0, 0, 250, 128], [90, 90, 90, 0]];
+    let mut img = ImageBuffer::from_fn(4, 4, |x, y| Rgba(colors[(y / 2 * 2 + x / 2) as usize]));
+    let png = encode_png(&img);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let downscale = |converter: &mut FormatConverter, png: &[u8]| {
+        let options = ConversionOptionsBuilder::new().resize(2, 2).build();
+        let result = converter
+            .convert_format(png, ImageFormat::Png, ImageFormat::Png, Some(options))
+            .unwrap();
+        image::load_from_memory(result.data()).unwrap().to_rgba8()
+    };
+
+    // 每个输出像素等于对应2×2块的平均值，不向相邻象限渗色
+    let output = downscale(&mut converter, &png);
+    for (x, y, pixel) in output.enumerate_pixels() {
+        assert_eq!(pixel.0, colors[(y * 2 + x) as usize], "pixel ({}, {})", x, y);
+    }
+
+    // 块内取值不同时四舍五入取平均，重复运行逐位一致
+    img.put_pixel(0, 0, Rgba([0, 0, 0, 255]));
+    img.put_pixel(1, 1, Rgba([255, 255, 255, 255]));
+    let png = encode_png(&img);
+    let first = downscale(&mut converter, &png);
+    // 红色 (0 + 200 + 200 + 255) / 4 = 163.75，绿色 (0 + 40 + 40 + 255) / 4 = 83.75
+    assert_eq!(first.get_pixel(0, 0).0, [164, 84, 69, 255]);
+    assert_eq!(downscale(&mut converter, &png).as_raw(), first.as_raw());
+}
+
+#[test]
+fn test_target_pixel_format_drops_alpha_and_keeps_png() {
+    use rustimage_core::codecs::PixelFormat;
+
+    let img = ImageBuffer::from_fn(8, 8, |x, _| Rgba([200u8, 40, 10, if x < 4 { 255 } else { 0 }]));
+    let png = encode_png(&img);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let options = ConversionOptionsBuilder::new().target_pixel_format(PixelFormat::Rgb8).build();
+    let result = converter
+        .convert_format(&png, ImageFormat::Png, ImageFormat::Png, Some(options))
+        .unwrap();
+
+    // 输出仍是PNG但不再带透明通道，透明像素与默认白色背景合成
+    let decoded = image::load_from_memory_with_format(result.data(), image::ImageFormat::Png).unwrap();
+    assert_eq!(decoded.color(), image::ColorType::Rgb8);
+    let rgb = decoded.to_rgb8();
+    assert_eq!(rgb.get_pixel(0, 0).0, [200, 40, 10]);
+    assert_eq!(rgb.get_pixel(7, 0).0, [255, 255, 255]);
+
+    let options = ConversionOptionsBuilder::new().target_pixel_format(PixelFormat::Gray8).build();
+    let result = converter
+        .convert_format(&png, ImageFormat::Png, ImageFormat::Png, Some(options))
+        .unwrap();
+    let decoded = image::load_from_memory(result.data()).unwrap();
+    assert_eq!(decoded.color(), image::ColorType::L8);
+
+    // 目标格式无法表示的像素格式在转换前被拒绝
+    let options = ConversionOptionsBuilder::new().target_pixel_format(PixelFormat::Rgba8).build();
+    assert!(matches!(
+        converter.convert_format(&png, ImageFormat::Png, ImageFormat::Jpeg, Some(options)),
+        Err(ImageError::UnsupportedOperation { .. })
+    ));
+}
+
+#[test]
+fn test_color_summary_finds_dominant_red() {
+    // 右侧四分之一为蓝色，其余为带轻微噪点的红色
+    let img = ImageBuffer::from_fn(128, 96, |x, y| {
+        if x >= 96 {
+            Rgba([0, 0, 255, 255])
+        } else {
+            Rgba([250 - ((x + y) % 3) as u8, (x * y % 4) as u8, 0, 255])
+        }
+    });
+    let converter = FormatConverter::with_defaults().unwrap();
+    let summary = converter.color_summary(&encode_png(&img), ImageFormat::Png).unwrap();
+
+    let (dominant, weight) = summary.dominant[0];
+    assert!(dominant.r > 230 && dominant.g < 20 && dominant.b < 20, "dominant {:?}", dominant);
+    assert!(weight > 0.6, "weight {}", weight);
+    let total: f32 = summary.dominant.iter().map(|&(_, weight)| weight).sum();
+    assert!((total - 1.0).abs() < 1e-3);
+
+    // 平均色偏红且混入了蓝色
+    assert!(summary.average.r > 150 && summary.average.b > 30, "average {:?}", summary.average);
+}
+
+#[test]
+fn test_blurhash_length_matches_component_counts() {
+    // 左黑右白的双色图
+    let img = ImageBuffer::from_fn(64, 48, |x, _| {
+        let v = if x < 32 { 0 } else { 255 };
+        Rgba([v, v, v, 255])
+    });
+    let png = encode_png(&img);
+    let converter = FormatConverter::with_defaults().unwrap();
+
+    for (x, y) in [(1, 1), (4, 3), (9, 9)] {
+        let hash = converter.blurhash(&png, ImageFormat::Png, x, y).unwrap();
+        assert_eq!(hash.len(), (6 + 2 * (x * y - 1)) as usize, "{}×{} -> {}", x, y, hash);
+        assert!(hash.is_ascii());
+    }
+
+    // 尺寸标记编码分量数：(4 - 1) + (3 - 1) * 9 = 21 -> 'L'
+    let hash = converter.blurhash(&png, ImageFormat::Png, 4, 3).unwrap();
+    assert!(hash.starts_with('L'), "{}", hash);
+
+    for (x, y) in [(0, 3), (4, 10)] {
+        assert!(matches!(
+            converter.blurhash(&png, ImageFormat::Png, x, y),
+            Err(ImageError::InvalidParameters { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_bit_reduction_dither_breaks_up_banding() {
+    // 16位灰度渐变只跨越4个8位级别，直接量化会形成宽色带
+    let (width, height) = (256u32, 8u32);
+    let img = ImageBuffer::from_fn(width, height, |x, _| image::Luma([0x4000 + (x * 4) as u16]));
+    let mut png16 = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png16)
+        .write_image(u16_bytes(img.as_raw()).as_slice(), width, height, image::ColorType::L16)
+        .unwrap();
+
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let mut transitions = |dither: bool| {
+        let options = ConversionOptionsBuilder::new().bit_reduction_dither(dither).build();
+        let converted = converter
+            .convert_format(&png16, ImageFormat::Png, ImageFormat::Png, Some(options))
+            .unwrap();
+        let decoded = image::load_from_memory(converted.data()).unwrap().to_rgba8();
+        decoded
+            .rows()
+            .map(|row| row.collect::<Vec<_>>().windows(2).filter(|pair| pair[0].0[0] != pair[1].0[0]).count())
+            .sum::<usize>()
+    };
+
+    let banded = transitions(false);
+    let dithered = transitions(true);
+    assert!(banded <= 4 * height as usize, "banded transitions {}", banded);
+    assert!(dithered > banded * 4, "dithered {} vs banded {}", dithered, banded);
+}
+
+/// 16位样本按本机字节序展开 - image库的编码器接受本机字节序的样本
+fn u16_bytes(samples: &[u16]) -> Vec<u8> {
+    samples.iter().flat_map(|sample| sample.to_ne_bytes()).collect()
+}
@@ -0,0 +1,177 @@
+//! 质量评估测试
+//!
+//! 测试转换器在启用质量评估时附带的质量指标
+
+use rustimage_core::{
+    converter::ConverterConfigBuilder,
+    quality::QualityAssessor,
+    ConversionOptionsBuilder, FormatConverter, ImageError, ImageFormat,
+};
+use image::{ImageBuffer, Rgba, ImageEncoder};
+
+/// 创建带渐变和噪点的PNG，确保有损压缩产生可测的误差
+fn create_gradient_png(width: u32, height: u32) -> Vec<u8> {
+    let img = ImageBuffer::from_fn(width, height, |x, y| {
+        let noise = ((x * 31 + y * 17) % 23) as u8 * 5;
+        Rgba([(x * 255 / width) as u8, (y * 255 / height) as u8, noise, 255])
+    });
+
+    let mut png_bytes = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_bytes)
+        .write_image(img.as_raw(), width, height, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+    png_bytes
+}
+
+#[test]
+fn test_lossy_conversion_reports_quality_metrics() {
+    let png_data = create_gradient_png(32, 32);
+    let config = ConverterConfigBuilder::new()
+        .enable_quality_assessment(true)
+        .build();
+    let mut converter = FormatConverter::new(config).unwrap();
+    let options = ConversionOptionsBuilder::new().quality(0.5).build();
+
+    let converted = converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, Some(options))
+        .expect("PNG to JPEG conversion failed");
+
+    let metrics = converted.quality_metrics().expect("quality metrics should be computed");
+    assert!(metrics.psnr() < 100.0, "psnr = {}", metrics.psnr());
+    assert!(metrics.psnr() > 20.0, "psnr = {}", metrics.psnr());
+    assert!(metrics.ssim() > 0.5 && metrics.ssim() <= 1.0);
+    assert!(metrics.perceptual_similarity() > 0.5);
+}
+
+#[test]
+fn test_lossless_conversion_is_perfect() {
+    let png_data = create_gradient_png(16, 16);
+    let config = ConverterConfigBuilder::new()
+        .enable_quality_assessment(true)
+        .build();
+    let mut converter = FormatConverter::new(config).unwrap();
+
+    let converted = converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::WebP, None)
+        .expect("PNG to WebP conversion failed");
+
+    let metrics = converted.quality_metrics().unwrap();
+    assert_eq!(metrics.psnr(), 100.0);
+    assert_eq!(metrics.ssim(), 1.0);
+    assert_eq!(metrics.perceptual_similarity(), 1.0);
+}
+
+#[test]
+fn test_quality_metrics_disabled_by_default() {
+    let png_data = create_gradient_png(8, 8);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let converted = converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, None)
+        .unwrap();
+
+    assert!(converted.quality_metrics().is_none());
+}
+
+#[test]
+fn test_quality_grade_bands() {
+    use rustimage_core::{QualityGrade, QualityMetrics};
+
+    let metrics = |psnr: f32, ssim: f32| -> QualityMetrics {
+        serde_json::from_value(serde_json::json!({
+            "psnr": psnr,
+            "ssim": ssim,
+            "perceptual_similarity": 1.0,
+        }))
+        .unwrap()
+    };
+
+    assert_eq!(metrics(45.0, 0.99).grade(), QualityGrade::Excellent);
+    assert_eq!(metrics(20.0, 0.6).grade(), QualityGrade::Poor);
+    // 两项指标取较弱的一档
+    assert_eq!(metrics(45.0, 0.96).grade(), QualityGrade::Good);
+    assert_eq!(metrics(32.0, 0.99).grade(), QualityGrade::Acceptable);
+    assert!(QualityGrade::Good > QualityGrade::Acceptable);
+
+    assert!(metrics(45.0, 0.99).meets(40.0, 0.98));
+    assert!(!metrics(45.0, 0.97).meets(40.0, 0.98));
+}
+
+#[test]
+fn test_sharp_pattern_scores_higher_than_blurred_copy() {
+    let sharp = ImageBuffer::from_fn(64, 64, |x, y| {
+        let v = if (x / 4 + y / 4) % 2 == 0 { 255 } else { 0 };
+        Rgba([v, v, v, 255])
+    });
+    let blurred = image::imageops::blur(&sharp, 3.0);
+
+    let encode = |img: &ImageBuffer<Rgba<u8>, Vec<u8>>| {
+        let mut png_bytes = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut png_bytes)
+            .write_image(img.as_raw(), 64, 64, image::ColorType::Rgba8)
+            .expect("Failed to encode PNG");
+        png_bytes
+    };
+
+    let assessor = QualityAssessor::new();
+    let sharp_score = assessor.sharpness(&encode(&sharp)).unwrap();
+    let blurred_score = assessor.sharpness(&encode(&blurred)).unwrap();
+
+    assert!((0.0..=1.0).contains(&sharp_score) && (0.0..=1.0).contains(&blurred_score));
+    assert!(
+        sharp_score > blurred_score,
+        "sharp {} should beat blurred {}",
+        sharp_score,
+        blurred_score
+    );
+}
+
+#[test]
+fn test_compare_png_with_jpeg_reencode() {
+    let png_data = create_gradient_png(32, 32);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let options = ConversionOptionsBuilder::new().quality(0.5).build();
+    let jpeg = converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, Some(options))
+        .unwrap();
+
+    let assessor = QualityAssessor::new();
+    let metrics = assessor
+        .compare(&png_data, ImageFormat::Png, jpeg.data(), ImageFormat::Jpeg)
+        .unwrap();
+    assert!(metrics.psnr().is_finite());
+    assert!(metrics.psnr() < 100.0, "psnr = {}", metrics.psnr());
+
+    // 尺寸不一致时报错
+    let smaller = create_gradient_png(16, 16);
+    let error = assessor
+        .compare(&png_data, ImageFormat::Png, &smaller, ImageFormat::Png)
+        .unwrap_err();
+    assert!(matches!(error, ImageError::InvalidDimensions { width: 16, height: 16, .. }), "{:?}", error);
+}
+
+#[test]
+fn test_entropy_of_solid_color_and_noise() {
+    let encode = |img: ImageBuffer<Rgba<u8>, Vec<u8>>| {
+        let mut png_bytes = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut png_bytes)
+            .write_image(img.as_raw(), img.width(), img.height(), image::ColorType::Rgba8)
+            .expect("Failed to encode PNG");
+        png_bytes
+    };
+    let assessor = QualityAssessor::new();
+
+    let solid = encode(ImageBuffer::from_pixel(64, 64, Rgba([200, 40, 90, 255])));
+    let entropy = assessor.entropy(&solid, ImageFormat::Png).unwrap();
+    assert!(entropy < 0.01, "solid entropy = {}", entropy);
+
+    // 灰度噪声 - 线性同余生成器保证亮度在256级上近似均匀分布
+    let mut state = 0x2545_f491u32;
+    let noise = encode(ImageBuffer::from_fn(256, 256, |_, _| {
+        state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        let v = (state >> 24) as u8;
+        Rgba([v, v, v, 255])
+    }));
+    let entropy = assessor.entropy(&noise, ImageFormat::Png).unwrap();
+    assert!(entropy > 7.9 && entropy <= 8.0, "noise entropy = {}", entropy);
+}
@@ -0,0 +1,110 @@
+//! 转换超时测试
+//!
+//! 测试单次转换的超时控制
+
+use rustimage_core::converter::ConverterConfigBuilder;
+use rustimage_core::{ConversionOptionsBuilder, FormatConverter, ImageError, ImageFormat};
+use image::{ImageBuffer, Rgba, ImageEncoder};
+use std::time::{Duration, Instant};
+
+/// 创建带噪点的PNG - 足够大，保证解码耗时远超测试超时
+fn create_noise_png(width: u32, height: u32) -> Vec<u8> {
+    let img = ImageBuffer::from_fn(width, height, |x, y| {
+        let v = x.wrapping_mul(2654435761) ^ y.wrapping_mul(40503);
+        Rgba([v as u8, (v >> 8) as u8, (v >> 16) as u8, 255])
+    });
+
+    let mut png_bytes = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png_bytes)
+        .write_image(img.as_raw(), width, height, image::ColorType::Rgba8)
+        .expect("Failed to encode PNG");
+    png_bytes
+}
+
+#[test]
+fn test_timeout_fires_on_slow_conversion() {
+    let png_data = create_noise_png(1024, 1024);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let options = ConversionOptionsBuilder::new()
+        .timeout(Duration::from_millis(1))
+        .build();
+
+    let result = converter.convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, Some(options));
+
+    match result {
+        Err(ImageError::OperationTimeout { timeout_ms, .. }) => assert_eq!(timeout_ms, 1),
+        other => panic!("expected timeout, got {:?}", other.map(|c| c.converted_size())),
+    }
+}
+
+#[test]
+fn test_repeated_timeouts_bound_detached_workers() {
+    let png_data = create_noise_png(1024, 1024);
+    let config = ConverterConfigBuilder::new().max_detached_workers(2).build();
+    let mut converter = FormatConverter::new(config).unwrap();
+    let options = ConversionOptionsBuilder::new()
+        .timeout(Duration::from_millis(1))
+        .resize(700, 700)
+        .build();
+
+    let mut rejected = 0;
+    for _ in 0..8 {
+        match converter.convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, Some(options.clone())) {
+            Err(ImageError::OperationTimeout { .. }) => {}
+            Err(ImageError::ResourceExhausted { .. }) => rejected += 1,
+            other => panic!("expected timeout or rejection, got {:?}", other.map(|c| c.converted_size())),
+        }
+        assert!(converter.detached_workers() <= 2, "{} detached workers", converter.detached_workers());
+    }
+    assert!(rejected > 0);
+
+    // 后台线程在下一个检查点退出并释放名额
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while converter.detached_workers() > 0 {
+        assert!(Instant::now() < deadline, "detached workers never finished");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_generous_timeout_completes() {
+    let png_data = create_noise_png(16, 16);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let options = ConversionOptionsBuilder::new()
+        .timeout(Duration::from_secs(30))
+        .build();
+
+    let converted = converter
+        .convert_format(&png_data, ImageFormat::Png, ImageFormat::Jpeg, Some(options))
+        .expect("conversion within timeout should succeed");
+    assert_eq!(converted.format(), ImageFormat::Jpeg);
+    assert_eq!(converted.dimensions().width, 16);
+}
+
+#[test]
+fn test_time_budget_degrades_effort_instead_of_failing() {
+    let png_data = create_noise_png(128, 128);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+    let mut convert = |options| {
+        converter
+            .convert_format(&png_data, ImageFormat::Png, ImageFormat::Png, Some(options))
+            .unwrap()
+    };
+
+    let fastest = convert(ConversionOptionsBuilder::new().compression_level(1).build());
+    let strongest = convert(ConversionOptionsBuilder::new().compression_level(9).build());
+    // 两端输出不同，下面的比较才能区分实际使用的级别
+    assert_ne!(fastest.data(), strongest.data());
+
+    let tiny = convert(ConversionOptionsBuilder::new().time_budget(Duration::from_micros(1)).build());
+    assert_eq!(tiny.data(), fastest.data());
+
+    // 预算充足时逐级提高到已配置的压缩级别
+    let generous = convert(
+        ConversionOptionsBuilder::new()
+            .compression_level(9)
+            .time_budget(Duration::from_secs(10))
+            .build(),
+    );
+    assert_eq!(generous.data(), strongest.data());
+}
@@ -0,0 +1,92 @@
+//! WebP编码测试
+//!
+//! 测试无损/有损编码模式的选择
+
+use rustimage_core::{ConversionOptionsBuilder, FormatConverter, ImageFormat};
+use image::{ImageBuffer, ImageEncoder, Rgba, RgbaImage};
+
+/// 创建带渐变和噪声的图像 - 有损编码无法精确还原
+fn create_image() -> RgbaImage {
+    ImageBuffer::from_fn(48, 32, |x, y| {
+        let noise = (x.wrapping_mul(7919) ^ y.wrapping_mul(104729)) as u8;
+        Rgba([(x * 5) as u8, (y * 7) as u8, noise, 255])
+    })
+}
+
+fn encode_png(img: &RgbaImage) -> Vec<u8> {
+    let mut data = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut data)
+        .write_image(img.as_raw(), img.width(), img.height(), image::ColorType::Rgba8)
+        .unwrap();
+    data
+}
+
+/// WebP首个数据块类型 - `VP8L`为无损，`VP8 `为有损
+fn webp_chunk(data: &[u8]) -> &[u8] {
+    &data[12..16]
+}
+
+#[test]
+fn test_lossless_webp_roundtrips_exactly() {
+    let img = create_image();
+    let png = encode_png(&img);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    // 显式无损，以及无损来源的默认选项
+    let explicit = ConversionOptionsBuilder::new().quality(0.5).webp_lossless(true).build();
+    for options in [Some(explicit), None] {
+        let webp = converter
+            .convert_format(&png, ImageFormat::Png, ImageFormat::WebP, options)
+            .unwrap();
+        assert_eq!(webp_chunk(webp.data()), b"VP8L");
+
+        let decoded = image::load_from_memory(webp.data()).unwrap().to_rgba8();
+        assert_eq!(decoded.as_raw(), img.as_raw());
+    }
+}
+
+#[cfg(not(feature = "webp-lossy"))]
+#[test]
+fn test_explicit_lossy_webp_requires_feature() {
+    let png = encode_png(&create_image());
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let options = ConversionOptionsBuilder::new().webp_lossless(false).build();
+    let result = converter.convert_format(&png, ImageFormat::Png, ImageFormat::WebP, Some(options));
+    assert!(matches!(result, Err(rustimage_core::ImageError::UnsupportedOperation { .. })));
+}
+
+#[cfg(feature = "webp-lossy")]
+#[test]
+fn test_lossy_webp_when_quality_set() {
+    let img = create_image();
+    let png = encode_png(&img);
+    let mut converter = FormatConverter::with_defaults().unwrap();
+
+    let options = ConversionOptionsBuilder::new().quality(0.7).build();
+    let webp = converter
+        .convert_format(&png, ImageFormat::Png, ImageFormat::WebP, Some(options))
+        .unwrap();
+    assert_eq!(webp_chunk(webp.data()), b"VP8 ");
+
+    let decoded = image::load_from_memory(webp.data()).unwrap().to_rgba8();
+    assert_eq!(decoded.dimensions(), img.dimensions());
+    assert_ne!(decoded.as_raw(), img.as_raw());
+}
+
+#[test]
+fn test_deterministic_webp_is_byte_identical() {
+    let png = encode_png(&create_image());
+    let options = ConversionOptionsBuilder::new().deterministic(true).build();
+    assert!(options.is_deterministic());
+
+    let first = FormatConverter::with_defaults()
+        .unwrap()
+        .convert_format(&png, ImageFormat::Png, ImageFormat::WebP, Some(options.clone()))
+        .unwrap();
+    let second = FormatConverter::with_high_performance()
+        .unwrap()
+        .convert_format(&png, ImageFormat::Png, ImageFormat::WebP, Some(options))
+        .unwrap();
+    assert_eq!(first.data(), second.data());
+}
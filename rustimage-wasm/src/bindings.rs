@@ -3,6 +3,7 @@
 //! 本模块定义了与 JavaScript 交互的底层绑定
 //! 提供类型安全的 WASM 接口
 
+use std::cell::RefCell;
 use wasm_bindgen::prelude::*;
 
 // =============================================================================
@@ -210,6 +211,7 @@ pub fn initialize_module(config: Option<WasmConfig>) -> Result<(), JsValue> {
 
 /// WASM 模块状态
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct ModuleState {
     initialized: bool,
     active_converters: u32,
@@ -218,28 +220,25 @@ pub struct ModuleState {
     total_memory_allocated: u64,
 }
 
-static mut MODULE_STATE: ModuleState = ModuleState {
-    initialized: false,
-    active_converters: 0,
-    active_monitors: 0,
-    total_conversions: 0,
-    total_memory_allocated: 0,
-};
+thread_local! {
+    /// 模块全局状态（WASM 为单线程，使用 thread_local 避免 static mut）
+    static MODULE_STATE: RefCell<ModuleState> = const {
+        RefCell::new(ModuleState {
+            initialized: false,
+            active_converters: 0,
+            active_monitors: 0,
+            total_conversions: 0,
+            total_memory_allocated: 0,
+        })
+    };
+}
 
 #[wasm_bindgen]
 impl ModuleState {
     /// 获取模块状态
     #[wasm_bindgen(js_name = getState)]
     pub fn get_state() -> ModuleState {
-        unsafe { 
-            ModuleState {
-                initialized: MODULE_STATE.initialized,
-                active_converters: MODULE_STATE.active_converters,
-                active_monitors: MODULE_STATE.active_monitors,
-                total_conversions: MODULE_STATE.total_conversions,
-                total_memory_allocated: MODULE_STATE.total_memory_allocated,
-            }
-        }
+        MODULE_STATE.with(|state| state.borrow().clone())
     }
     
     /// 是否已初始化
@@ -292,9 +291,7 @@ pub(crate) fn update_state<F>(updater: F)
 where 
     F: FnOnce(&mut ModuleState)
 {
-    unsafe {
-        updater(&mut MODULE_STATE);
-    }
+    MODULE_STATE.with(|state| updater(&mut state.borrow_mut()));
 }
 
 /// 标记模块为已初始化
@@ -406,4 +403,53 @@ pub fn health_check() -> Result<String, JsValue> {
         "suggestions": suggestions,
         "environment": env_data
     })).map_err(|e| JsValue::from_str(&e.to_string()))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_state_tracks_active_converters() {
+        let before = ModuleState::get_state().get_active_converters();
+
+        increment_active_converters();
+        increment_active_converters();
+        assert_eq!(ModuleState::get_state().get_active_converters(), before + 2);
+
+        decrement_active_converters();
+        assert_eq!(ModuleState::get_state().get_active_converters(), before + 1);
+    }
+    fn tiny_png() -> Vec<u8> {
+        let image = image::RgbaImage::from_pixel(2, 2, image::Rgba([255, 0, 0, 255]));
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgba8(image)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        png
+    }
+
+    #[test]
+    fn test_converter_lifecycle_updates_module_state() {
+        let base = ModuleState::get_state();
+
+        let mut first = crate::ImageConverter::new().unwrap();
+        let second = crate::ImageConverter::new().unwrap();
+        // 失败路径会构造 JsValue，宿主机上无法运行，因此只验证成功路径
+        assert!(first
+            .convert_format(&tiny_png(), crate::JsImageFormat::Png, crate::JsImageFormat::Jpeg, None)
+            .is_ok());
+
+        let state = ModuleState::get_state();
+        assert_eq!(state.get_active_converters(), base.get_active_converters() + 2);
+        assert_eq!(state.get_total_conversions(), base.get_total_conversions() + 1);
+
+        // destroy 与随后的 drop 只注销一次
+        first.destroy();
+        drop(first);
+        drop(second);
+        assert_eq!(
+            ModuleState::get_state().get_active_converters(),
+            base.get_active_converters()
+        );
+    }
+}
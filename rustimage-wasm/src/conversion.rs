@@ -5,6 +5,8 @@
 //! - 强大的 Rust 后端实现
 //! - 完整的错误处理和性能监控
 
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
 use wasm_bindgen::prelude::*;
 use crate::types::*;
 
@@ -19,7 +21,8 @@ use crate::types::*;
 #[wasm_bindgen]
 pub struct ImageConverter {
     // 私有字段：完全隐藏 Rust 实现细节
-    inner: Option<rustimage_core::FormatConverter>,
+    // 共享所有权：异步转换的 Promise 可能比本对象活得更久
+    inner: Option<Rc<RefCell<rustimage_core::FormatConverter>>>,
 }
 
 /// 批量转换任务
@@ -29,12 +32,14 @@ pub struct BatchConversionTask {
     from_format: JsImageFormat,
     to_format: JsImageFormat,
     options: Option<JsConversionOptions>,
+    id: Option<String>,
 }
 
 /// 批量转换结果
 #[wasm_bindgen]
 pub struct BatchConversionResult {
     results: Vec<Result<JsConvertedImage, String>>,
+    ids: Vec<Option<String>>,
     successful_count: usize,
     total_count: usize,
     total_time_ms: f64,
@@ -52,9 +57,7 @@ impl ImageConverter {
         let converter = rustimage_core::FormatConverter::with_defaults()
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         
-        Ok(ImageConverter {
-            inner: Some(converter),
-        })
+        Ok(ImageConverter::from_core(converter))
     }
     
     /// 创建高性能配置的转换器
@@ -63,9 +66,7 @@ impl ImageConverter {
         let converter = rustimage_core::FormatConverter::with_high_performance()
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         
-        Ok(ImageConverter {
-            inner: Some(converter),
-        })
+        Ok(ImageConverter::from_core(converter))
     }
     
     /// 创建高质量配置的转换器
@@ -74,9 +75,7 @@ impl ImageConverter {
         let converter = rustimage_core::FormatConverter::with_high_quality()
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         
-        Ok(ImageConverter {
-            inner: Some(converter),
-        })
+        Ok(ImageConverter::from_core(converter))
     }
     
     /// 转换图像格式 - 主要接口
@@ -97,8 +96,9 @@ impl ImageConverter {
         to_format: JsImageFormat,
         options: Option<JsConversionOptions>,
     ) -> Result<JsConvertedImage, JsValue> {
-        let converter = self.inner.as_mut()
-            .ok_or_else(|| JsValue::from_str("Converter has been destroyed"))?;
+        let mut converter = self.inner.as_ref()
+            .ok_or_else(|| JsValue::from_str("Converter has been destroyed"))?
+            .borrow_mut();
         
         // 转换类型
         let rust_from_format = from_js_image_format(from_format);
@@ -112,11 +112,106 @@ impl ImageConverter {
             rust_to_format,
             rust_options,
         ).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        crate::bindings::increment_total_conversions();
         
         // 转换结果
         Ok(to_js_converted_image(result))
     }
     
+    /// 直接转换 File/Blob - 自动检测源格式
+    /// 
+    /// 在 Rust 内读取 Blob 的 ArrayBuffer，省去 JS 侧的读取代码和一次拷贝
+    /// 
+    /// # 参数
+    /// * `blob` - 图像文件（File 或 Blob）
+    /// * `to_format` - 目标格式
+    /// * `options` - 转换选项（可选）
+    /// 
+    /// # 返回
+    /// `Promise<JsConvertedImage>`，读取或转换失败时以错误消息 reject
+    #[wasm_bindgen(js_name = convertFromBlob)]
+    pub fn convert_from_blob(
+        &self,
+        blob: web_sys::Blob,
+        to_format: JsImageFormat,
+        options: Option<JsConversionOptions>,
+    ) -> Result<js_sys::Promise, JsValue> {
+        let converter = Rc::clone(
+            self.inner.as_ref()
+                .ok_or_else(|| JsValue::from_str("Converter has been destroyed"))?,
+        );
+        let rust_to_format = from_js_image_format(to_format);
+        let rust_options = options.as_ref().map(from_js_conversion_options);
+        
+        Ok(wasm_bindgen_futures::future_to_promise(async move {
+            let buffer = wasm_bindgen_futures::JsFuture::from(blob.array_buffer()).await?;
+            let image_data = js_sys::Uint8Array::new(&buffer).to_vec();
+            
+            let result = converter
+                .borrow_mut()
+                .convert_auto(&image_data, rust_to_format, rust_options)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+            crate::bindings::increment_total_conversions();
+            
+            Ok(to_js_converted_image(result).into())
+        }))
+    }
+    
+    /// 借助浏览器原生解码器转换 File/Blob - 自动检测源格式
+    /// 
+    /// 通过 `createImageBitmap` + `OffscreenCanvas` 解码为 RGBA 像素，再由 Rust 编码；
+    /// 对大尺寸 JPEG 等格式明显快于纯 Rust 解码。环境不支持或浏览器解码失败时
+    /// 自动回退为 `convertFromBlob` 的纯 Rust 路径
+    /// 
+    /// 浏览器解码不保留元数据和 ICC 配置文件，并可能应用 EXIF 方向
+    /// 
+    /// # 参数
+    /// * `blob` - 图像文件（File 或 Blob）
+    /// * `to_format` - 目标格式
+    /// * `options` - 转换选项（可选）
+    /// 
+    /// # 返回
+    /// `Promise<JsConvertedImage>`，读取或转换失败时以错误消息 reject
+    #[wasm_bindgen(js_name = convertViaBrowserDecode)]
+    pub fn convert_via_browser_decode(
+        &self,
+        blob: web_sys::Blob,
+        to_format: JsImageFormat,
+        options: Option<JsConversionOptions>,
+    ) -> Result<js_sys::Promise, JsValue> {
+        let converter = Rc::clone(
+            self.inner.as_ref()
+                .ok_or_else(|| JsValue::from_str("Converter has been destroyed"))?,
+        );
+        let rust_to_format = from_js_image_format(to_format);
+        let rust_options = options.as_ref().map(from_js_conversion_options);
+        
+        Ok(wasm_bindgen_futures::future_to_promise(async move {
+            let buffer = wasm_bindgen_futures::JsFuture::from(blob.array_buffer()).await?;
+            let image_data = js_sys::Uint8Array::new(&buffer).to_vec();
+            let pixels = decode_with_browser(&blob).await.ok();
+            
+            let mut converter = converter.borrow_mut();
+            let result = match (pixels, converter.detect_format(&image_data)) {
+                (Some((width, height, rgba)), Ok(from_format)) => {
+                    rustimage_core::DecodedImage::from_rgba(
+                        width,
+                        height,
+                        &rgba,
+                        from_format,
+                        image_data.len() as u64,
+                    )
+                    .and_then(|decoded| converter.encode_buffer(&decoded, rust_to_format, rust_options))
+                }
+                _ => converter.convert_auto(&image_data, rust_to_format, rust_options),
+            }
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+            crate::bindings::increment_total_conversions();
+            
+            Ok(to_js_converted_image(result).into())
+        }))
+    }
+    
     /// 批量转换图像格式
     /// 
     /// # 参数
@@ -129,32 +224,22 @@ impl ImageConverter {
         &mut self,
         tasks: Vec<BatchConversionTask>,
     ) -> Result<BatchConversionResult, JsValue> {
-        let converter = self.inner.as_mut()
-            .ok_or_else(|| JsValue::from_str("Converter has been destroyed"))?;
+        let mut converter = self.inner.as_ref()
+            .ok_or_else(|| JsValue::from_str("Converter has been destroyed"))?
+            .borrow_mut();
         
         let start_time = web_sys::js_sys::Date::now();
         let mut results = Vec::new();
+        let mut ids = Vec::new();
         let mut successful_count = 0;
         
         for task in tasks {
-            let rust_from_format = from_js_image_format(task.from_format);
-            let rust_to_format = from_js_image_format(task.to_format);
-            let rust_options = task.options.as_ref().map(from_js_conversion_options);
-            
-            match converter.convert_format(
-                &task.image_data,
-                rust_from_format,
-                rust_to_format,
-                rust_options,
-            ) {
-                Ok(result) => {
-                    results.push(Ok(to_js_converted_image(result)));
-                    successful_count += 1;
-                },
-                Err(e) => {
-                    results.push(Err(e.to_string()));
-                }
+            ids.push(task.id.clone());
+            let result = task.convert_with(&mut converter);
+            if result.is_ok() {
+                successful_count += 1;
             }
+            results.push(result);
         }
         
         let total_time_ms = web_sys::js_sys::Date::now() - start_time;
@@ -163,12 +248,77 @@ impl ImageConverter {
         
         Ok(BatchConversionResult {
             results,
+            ids,
             successful_count,
             total_count,
             total_time_ms,
         })
     }
     
+    /// 带进度回调的批量转换 - 每个任务之间让出事件循环，避免长时间冻结界面
+    /// 
+    /// 任务仍在主线程上逐个执行；需要真正的并行或完全不阻塞界面时，
+    /// 建议在 Web Worker 中调用
+    /// 
+    /// # 参数
+    /// * `tasks` - 转换任务数组
+    /// * `on_progress` - 每个任务完成后调用 `onProgress(completedIndex, total, lastResult)`，
+    ///   `lastResult` 为 `JsConvertedImage` 或错误消息字符串
+    /// 
+    /// # 返回
+    /// `Promise<BatchConversionResult>`，全部任务完成后 resolve
+    #[wasm_bindgen(js_name = batchConvertWithProgress)]
+    pub fn batch_convert_with_progress(
+        &self,
+        tasks: Vec<BatchConversionTask>,
+        on_progress: js_sys::Function,
+    ) -> Result<js_sys::Promise, JsValue> {
+        let converter = Rc::clone(
+            self.inner.as_ref()
+                .ok_or_else(|| JsValue::from_str("Converter has been destroyed"))?,
+        );
+        
+        Ok(wasm_bindgen_futures::future_to_promise(async move {
+            let start_time = web_sys::js_sys::Date::now();
+            let total_count = tasks.len();
+            let mut results = Vec::with_capacity(total_count);
+            let mut ids = Vec::with_capacity(total_count);
+            let mut successful_count = 0;
+            
+            for (index, task) in tasks.into_iter().enumerate() {
+                // 先让出事件循环，使界面在任务之间得以刷新
+                yield_to_event_loop().await?;
+                
+                ids.push(task.id.clone());
+                let result = task.convert_with(&mut converter.borrow_mut());
+                let last_result = match &result {
+                    Ok(image) => {
+                        successful_count += 1;
+                        JsValue::from(image.clone())
+                    }
+                    Err(message) => JsValue::from_str(message),
+                };
+                results.push(result);
+                
+                on_progress.call3(
+                    &JsValue::NULL,
+                    &JsValue::from(index as u32),
+                    &JsValue::from(total_count as u32),
+                    &last_result,
+                )?;
+            }
+            
+            Ok(BatchConversionResult {
+                results,
+                ids,
+                successful_count,
+                total_count,
+                total_time_ms: web_sys::js_sys::Date::now() - start_time,
+            }
+            .into())
+        }))
+    }
+    
     /// 检测图像格式
     /// 
     /// # 参数
@@ -179,7 +329,8 @@ impl ImageConverter {
     #[wasm_bindgen(js_name = detectFormat)]
     pub fn detect_format(&self, image_data: &[u8]) -> Result<JsImageFormat, JsValue> {
         let converter = self.inner.as_ref()
-            .ok_or_else(|| JsValue::from_str("Converter has been destroyed"))?;
+            .ok_or_else(|| JsValue::from_str("Converter has been destroyed"))?
+            .borrow();
         
         let format = converter.detect_format(image_data)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
@@ -197,7 +348,8 @@ impl ImageConverter {
     #[wasm_bindgen(js_name = getFormatInfo)]
     pub fn get_format_info(&self, format: JsImageFormat) -> JsFormatInfo {
         let converter = self.inner.as_ref()
-            .expect("Converter should be available for format info");
+            .expect("Converter should be available for format info")
+            .borrow();
         
         let rust_format = from_js_image_format(format);
         let info = converter.get_format_info(rust_format);
@@ -209,7 +361,8 @@ impl ImageConverter {
     #[wasm_bindgen(js_name = getSupportedFormats)]
     pub fn get_supported_formats(&self) -> js_sys::Array {
         let converter = self.inner.as_ref()
-            .expect("Converter should be available for supported formats");
+            .expect("Converter should be available for supported formats")
+            .borrow();
         
         let formats = converter.get_supported_formats()
             .into_iter()
@@ -228,7 +381,8 @@ impl ImageConverter {
     #[wasm_bindgen(js_name = getConversionStats)]
     pub fn get_conversion_stats(&self) -> Result<String, JsValue> {
         let converter = self.inner.as_ref()
-            .ok_or_else(|| JsValue::from_str("Converter has been destroyed"))?;
+            .ok_or_else(|| JsValue::from_str("Converter has been destroyed"))?
+            .borrow();
         
         let stats = converter.get_conversion_statistics();
         
@@ -244,10 +398,65 @@ impl ImageConverter {
         })).map_err(|e| JsValue::from_str(&e.to_string()))
     }
     
+    /// 释放转换器保留的缓存内存
+    /// 
+    /// 以下一次转换时的重新分配换取更低的常驻内存，适合长时间运行、
+    /// 转换间隔较长的页面。WASM 线性内存不会缩小，释放的空间由分配器
+    /// 在后续转换中复用，从而抑制内存继续增长
+    #[wasm_bindgen(js_name = releaseBuffers)]
+    pub fn release_buffers(&self) -> Result<(), JsValue> {
+        self.inner.as_ref()
+            .ok_or_else(|| JsValue::from_str("Converter has been destroyed"))?
+            .borrow_mut()
+            .clear_caches();
+        Ok(())
+    }
+    
     /// 销毁转换器，释放资源
     #[wasm_bindgen(js_name = destroy)]
     pub fn destroy(&mut self) {
-        self.inner.take();
+        if self.inner.take().is_some() {
+            crate::bindings::decrement_active_converters();
+        }
+    }
+}
+
+impl ImageConverter {
+    /// 包装核心转换器并登记到模块状态
+    fn from_core(converter: rustimage_core::FormatConverter) -> ImageConverter {
+        crate::bindings::increment_active_converters();
+        let inner = Rc::new(RefCell::new(converter));
+        LIVE_CONVERTERS.with(|live| live.borrow_mut().push(Rc::downgrade(&inner)));
+        ImageConverter {
+            inner: Some(inner),
+        }
+    }
+}
+
+thread_local! {
+    /// 存活转换器的弱引用，用于汇总缓存占用
+    static LIVE_CONVERTERS: RefCell<Vec<Weak<RefCell<rustimage_core::FormatConverter>>>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// 所有存活转换器当前缓存占用的字节数之和
+/// 
+/// 正在执行异步转换的转换器无法借用，不计入
+pub(crate) fn total_cached_bytes() -> usize {
+    LIVE_CONVERTERS.with(|live| {
+        let mut live = live.borrow_mut();
+        live.retain(|converter| converter.strong_count() > 0);
+        live.iter()
+            .filter_map(Weak::upgrade)
+            .filter_map(|converter| converter.try_borrow().ok().map(|c| c.cached_bytes()))
+            .sum()
+    })
+}
+
+impl Drop for ImageConverter {
+    /// JS 侧调用 `free()` 而未先 `destroy()` 时同样注销
+    fn drop(&mut self) {
+        self.destroy();
     }
 }
 
@@ -266,9 +475,22 @@ impl BatchConversionTask {
             from_format,
             to_format,
             options,
+            id: None,
         }
     }
     
+    /// 设置任务标识（如文件名），批量结果中原样带回
+    #[wasm_bindgen(js_name = setId)]
+    pub fn set_id(&mut self, id: Option<String>) {
+        self.id = id;
+    }
+    
+    /// 获取任务标识
+    #[wasm_bindgen(js_name = getId)]
+    pub fn get_id(&self) -> Option<String> {
+        self.id.clone()
+    }
+    
     /// 获取图像数据
     #[wasm_bindgen(js_name = getImageData)]
     pub fn get_image_data(&self) -> Vec<u8> {
@@ -288,6 +510,77 @@ impl BatchConversionTask {
     }
 }
 
+impl BatchConversionTask {
+    /// 使用给定转换器执行任务 - 错误转为消息字符串
+    fn convert_with(&self, converter: &mut rustimage_core::FormatConverter) -> Result<JsConvertedImage, String> {
+        converter
+            .convert_format(
+                &self.image_data,
+                from_js_image_format(self.from_format),
+                from_js_image_format(self.to_format),
+                self.options.as_ref().map(from_js_conversion_options),
+            )
+            .map(|image| {
+                crate::bindings::increment_total_conversions();
+                to_js_converted_image(image)
+            })
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// 全局 setTimeout - 在窗口和 Worker 中均可用
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &js_sys::Function, timeout_ms: i32) -> JsValue;
+    
+    /// 全局 createImageBitmap - 不可用时抛出 ReferenceError
+    #[wasm_bindgen(catch, js_name = createImageBitmap)]
+    fn create_image_bitmap(
+        image: &web_sys::Blob,
+        options: &web_sys::ImageBitmapOptions,
+    ) -> Result<js_sys::Promise, JsValue>;
+}
+
+/// 通过零延迟的 setTimeout 让出事件循环
+async fn yield_to_event_loop() -> Result<(), JsValue> {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        set_timeout(&resolve, 0);
+    });
+    wasm_bindgen_futures::JsFuture::from(promise).await.map(|_| ())
+}
+
+/// 使用浏览器原生解码器将 Blob 解码为 `(width, height, rgba)`
+/// 
+/// 不预乘 alpha、不做色彩空间转换，使像素尽量接近纯 Rust 解码的结果
+async fn decode_with_browser(blob: &web_sys::Blob) -> Result<(u32, u32, Vec<u8>), JsValue> {
+    use wasm_bindgen::JsCast;
+    
+    let options = web_sys::ImageBitmapOptions::new();
+    options.set_premultiply_alpha(web_sys::PremultiplyAlpha::None);
+    options.set_color_space_conversion(web_sys::ColorSpaceConversion::None);
+    
+    let bitmap: web_sys::ImageBitmap =
+        wasm_bindgen_futures::JsFuture::from(create_image_bitmap(blob, &options)?)
+            .await?
+            .dyn_into()?;
+    let (width, height) = (bitmap.width(), bitmap.height());
+    
+    let canvas = web_sys::OffscreenCanvas::new(width, height);
+    let pixels = canvas.and_then(|canvas| {
+        let context: web_sys::OffscreenCanvasRenderingContext2d = canvas
+            .get_context("2d")?
+            .ok_or_else(|| JsValue::from_str("OffscreenCanvas 2d context unavailable"))?
+            .dyn_into()?;
+        context.draw_image_with_image_bitmap(&bitmap, 0.0, 0.0)?;
+        context.get_image_data(0.0, 0.0, width as f64, height as f64)
+    });
+    // 无论成功与否都尽早释放位图占用的显存
+    bitmap.close();
+    
+    Ok((width, height, pixels?.data().0))
+}
+
 #[wasm_bindgen]
 impl BatchConversionResult {
     /// 获取成功转换的数量
@@ -346,6 +639,22 @@ impl BatchConversionResult {
         self.results.get(index).and_then(|r| r.as_ref().err().cloned())
     }
     
+    /// 获取指定索引任务的标识
+    #[wasm_bindgen(js_name = getId)]
+    pub fn get_id(&self, index: usize) -> Option<String> {
+        self.ids.get(index).cloned().flatten()
+    }
+    
+    /// 获取所有失败任务的标识 - 未设置标识的任务不计入
+    #[wasm_bindgen(js_name = getFailedIds)]
+    pub fn get_failed_ids(&self) -> Vec<String> {
+        self.results.iter()
+            .zip(&self.ids)
+            .filter(|(result, _)| result.is_err())
+            .filter_map(|(_, id)| id.clone())
+            .collect()
+    }
+    
     /// 获取所有成功的结果
     #[wasm_bindgen(js_name = getSuccessfulResults)]
     pub fn get_successful_results(&self) -> Vec<JsConvertedImage> {
@@ -410,6 +719,23 @@ pub fn validate_image_data(image_data: &[u8]) -> bool {
     }
 }
 
+/// 便利函数：完整解码验证图像数据
+///
+/// 与只检查文件签名的`validateImageData`不同，截断或损坏的数据也会被拒绝。
+/// 成功时返回`{ width, height }`，失败时抛出解码错误
+#[wasm_bindgen(js_name = validateImageDeep)]
+pub fn validate_image_deep(
+    image_data: &[u8],
+    expected_format: Option<JsImageFormat>,
+) -> Result<JsValue, JsValue> {
+    let converter = rustimage_core::FormatConverter::with_defaults()
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let dimensions = converter
+        .validate_decodable(image_data, expected_format.map(from_js_image_format))
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    serde_wasm_bindgen::to_value(&dimensions).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
 /// 便利函数：获取图像基本信息（不进行转换）
 #[wasm_bindgen(js_name = getImageInfo)]
 pub fn get_image_info(image_data: &[u8]) -> Result<String, JsValue> {
@@ -426,4 +752,23 @@ pub fn get_image_info(image_data: &[u8]) -> Result<String, JsValue> {
         "supports_animation": format_info.supports_animation(),
         "data_size": image_data.len()
     })).map_err(|e| JsValue::from_str(&e.to_string()))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_buffers_clears_cached_bytes() {
+        let converter = ImageConverter::new().unwrap();
+        assert!(total_cached_bytes() > 0);
+
+        converter.release_buffers().unwrap();
+        assert_eq!(total_cached_bytes(), 0);
+
+        // 已销毁的转换器不再计入
+        let other = ImageConverter::new().unwrap();
+        let cached = total_cached_bytes();
+        drop(other);
+        assert!(total_cached_bytes() < cached);
+    }
+}
@@ -49,6 +49,7 @@ impl PerformanceMonitor {
         let monitor = rustimage_core::performance::PerformanceMonitor::new(enabled)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         
+        crate::bindings::increment_active_monitors();
         Ok(PerformanceMonitor {
             inner: Some(monitor),
             session_start: web_sys::js_sys::Date::now(),
@@ -70,6 +71,7 @@ impl PerformanceMonitor {
         let monitor = rustimage_core::performance::PerformanceMonitor::with_config(rust_config)
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
         
+        crate::bindings::increment_active_monitors();
         Ok(PerformanceMonitor {
             inner: Some(monitor),
             session_start: web_sys::js_sys::Date::now(),
@@ -190,7 +192,16 @@ impl PerformanceMonitor {
     /// 销毁监控器
     #[wasm_bindgen(js_name = destroy)]
     pub fn destroy(&mut self) {
-        self.inner.take();
+        if self.inner.take().is_some() {
+            crate::bindings::decrement_active_monitors();
+        }
+    }
+}
+
+impl Drop for PerformanceMonitor {
+    /// JS 侧调用 `free()` 而未先 `destroy()` 时同样注销
+    fn drop(&mut self) {
+        self.destroy();
     }
 }
 
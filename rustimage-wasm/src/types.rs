@@ -22,6 +22,10 @@ pub enum JsImageFormat {
     Tiff,
     Gif,
     Ico,
+    Pnm,
+    Tga,
+    Heic,
+    Jxl,
 }
 
 /// JavaScript 兼容的转换选项
@@ -75,6 +79,15 @@ pub struct JsFormatInfo {
     supports_animation: bool,
 }
 
+/// JavaScript 兼容的格式检测结果
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct JsFormatDetection {
+    pub(crate) format: JsImageFormat,
+    pub(crate) confidence: f32,
+    pub(crate) source: &'static str,
+}
+
 // =============================================================================
 // WASM 绑定实现
 // =============================================================================
@@ -91,6 +104,10 @@ pub fn format_from_string(format_str: &str) -> Result<JsImageFormat, JsValue> {
         "tiff" | "tif" => Ok(JsImageFormat::Tiff),
         "gif" => Ok(JsImageFormat::Gif),
         "ico" => Ok(JsImageFormat::Ico),
+        "pnm" | "ppm" | "pgm" | "pbm" => Ok(JsImageFormat::Pnm),
+        "tga" => Ok(JsImageFormat::Tga),
+        "heic" | "heif" => Ok(JsImageFormat::Heic),
+        "jxl" => Ok(JsImageFormat::Jxl),
         _ => Err(JsValue::from_str(&format!("Unsupported format: {}", format_str))),
     }
 }
@@ -107,6 +124,10 @@ pub fn format_to_string(format: JsImageFormat) -> String {
         JsImageFormat::Tiff => "tiff".to_string(),
         JsImageFormat::Gif => "gif".to_string(),
         JsImageFormat::Ico => "ico".to_string(),
+        JsImageFormat::Pnm => "pnm".to_string(),
+        JsImageFormat::Tga => "tga".to_string(),
+        JsImageFormat::Heic => "heic".to_string(),
+        JsImageFormat::Jxl => "jxl".to_string(),
     }
 }
 
@@ -287,20 +308,12 @@ impl JsConvertedImage {
     }
     
     /// 创建 Blob URL（在浏览器中）
+    /// 
+    /// 适合较大的图像：数据不经过编码膨胀，但用完后须调用
+    /// `URL.revokeObjectURL` 释放，否则会一直占用内存
     #[wasm_bindgen(js_name = createBlobUrl)]
     pub fn create_blob_url(&self) -> Result<String, JsValue> {
-        use web_sys::*;
-        
-        let mime_type = match self.format {
-            JsImageFormat::Jpeg => "image/jpeg",
-            JsImageFormat::Png => "image/png",
-            JsImageFormat::WebP => "image/webp",
-            JsImageFormat::Avif => "image/avif",
-            JsImageFormat::Bmp => "image/bmp",
-            JsImageFormat::Tiff => "image/tiff",
-            JsImageFormat::Gif => "image/gif",
-            JsImageFormat::Ico => "image/x-icon",
-        };
+        let mime_type = self.mime_type();
         
         let uint8_array = js_sys::Uint8Array::new_with_length(self.data.len() as u32);
         uint8_array.copy_from(&self.data);
@@ -316,6 +329,54 @@ impl JsConvertedImage {
         
         Ok(url)
     }
+    
+    /// 生成 `data:` URL，可直接用作 `<img src>`
+    /// 
+    /// 无需释放，适合小图标；base64 使体积增加约 33%，
+    /// 较大的图像建议使用 `createBlobUrl`
+    #[wasm_bindgen(js_name = toDataUrl)]
+    pub fn to_data_url(&self) -> String {
+        format!("data:{};base64,{}", self.mime_type(), encode_base64(&self.data))
+    }
+}
+
+impl JsConvertedImage {
+    /// 输出格式对应的 MIME 类型
+    fn mime_type(&self) -> &'static str {
+        match self.format {
+            JsImageFormat::Jpeg => "image/jpeg",
+            JsImageFormat::Png => "image/png",
+            JsImageFormat::WebP => "image/webp",
+            JsImageFormat::Avif => "image/avif",
+            JsImageFormat::Bmp => "image/bmp",
+            JsImageFormat::Tiff => "image/tiff",
+            JsImageFormat::Gif => "image/gif",
+            JsImageFormat::Ico => "image/x-icon",
+            JsImageFormat::Pnm => "image/x-portable-anymap",
+            JsImageFormat::Tga => "image/x-tga",
+            JsImageFormat::Heic => "image/heic",
+            JsImageFormat::Jxl => "image/jxl",
+        }
+    }
+}
+
+/// 标准 base64 编码（RFC 4648，带填充）
+fn encode_base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let triple = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(triple >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
 }
 
 #[wasm_bindgen]
@@ -428,6 +489,27 @@ impl JsFormatInfo {
     }
 }
 
+#[wasm_bindgen]
+impl JsFormatDetection {
+    /// 获取检测到的格式
+    #[wasm_bindgen(js_name = getFormat)]
+    pub fn get_format(&self) -> JsImageFormat {
+        self.format
+    }
+    
+    /// 获取置信度 [0.0, 1.0]
+    #[wasm_bindgen(js_name = getConfidence)]
+    pub fn get_confidence(&self) -> f32 {
+        self.confidence
+    }
+    
+    /// 获取判定依据："content"、"extension" 或 "content+extension"
+    #[wasm_bindgen(js_name = getSource)]
+    pub fn get_source(&self) -> String {
+        self.source.to_string()
+    }
+}
+
 // =============================================================================
 // 内部类型转换函数
 // =============================================================================
@@ -443,6 +525,10 @@ pub(crate) fn to_js_image_format(format: rustimage_core::ImageFormat) -> JsImage
         rustimage_core::ImageFormat::Tiff => JsImageFormat::Tiff,
         rustimage_core::ImageFormat::Gif => JsImageFormat::Gif,
         rustimage_core::ImageFormat::Ico => JsImageFormat::Ico,
+        rustimage_core::ImageFormat::Pnm => JsImageFormat::Pnm,
+        rustimage_core::ImageFormat::Tga => JsImageFormat::Tga,
+        rustimage_core::ImageFormat::Heic => JsImageFormat::Heic,
+        rustimage_core::ImageFormat::Jxl => JsImageFormat::Jxl,
     }
 }
 
@@ -457,6 +543,10 @@ pub(crate) fn from_js_image_format(format: JsImageFormat) -> rustimage_core::Ima
         JsImageFormat::Tiff => rustimage_core::ImageFormat::Tiff,
         JsImageFormat::Gif => rustimage_core::ImageFormat::Gif,
         JsImageFormat::Ico => rustimage_core::ImageFormat::Ico,
+        JsImageFormat::Pnm => rustimage_core::ImageFormat::Pnm,
+        JsImageFormat::Tga => rustimage_core::ImageFormat::Tga,
+        JsImageFormat::Heic => rustimage_core::ImageFormat::Heic,
+        JsImageFormat::Jxl => rustimage_core::ImageFormat::Jxl,
     }
 }
 
@@ -530,4 +620,52 @@ pub(crate) fn to_js_format_info(rust_info: rustimage_core::FormatInfo) -> JsForm
         supports_transparency: rust_info.capabilities.supports_transparency(),
         supports_animation: rust_info.capabilities.supports_animation(),
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试用 base64 解码，用于验证往返
+    fn decode_base64(encoded: &str) -> Vec<u8> {
+        const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut decoded = Vec::new();
+        let mut acc = 0u32;
+        let mut bits = 0;
+        for c in encoded.bytes().take_while(|&c| c != b'=') {
+            acc = (acc << 6) | ALPHABET.iter().position(|&a| a == c).unwrap() as u32;
+            bits += 6;
+            if bits >= 8 {
+                bits -= 8;
+                decoded.push((acc >> bits) as u8);
+            }
+        }
+        decoded
+    }
+
+    #[test]
+    fn test_base64_known_vectors() {
+        assert_eq!(encode_base64(b""), "");
+        assert_eq!(encode_base64(b"f"), "Zg==");
+        assert_eq!(encode_base64(b"fo"), "Zm8=");
+        assert_eq!(encode_base64(b"foo"), "Zm9v");
+        assert_eq!(encode_base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_to_data_url_roundtrip() {
+        let data: Vec<u8> = (0..=255).chain(0..7).collect();
+        let image = JsConvertedImage {
+            data: data.clone(),
+            width: 1,
+            height: 1,
+            format: JsImageFormat::WebP,
+            conversion_time_ms: 0.0,
+            original_size: 0,
+            compression_ratio: 1.0,
+        };
+
+        let url = image.to_data_url();
+        let payload = url.strip_prefix("data:image/webp;base64,").unwrap();
+        assert_eq!(decode_base64(payload), data);
+    }
+}
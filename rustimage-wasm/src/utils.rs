@@ -91,6 +91,8 @@ pub fn log_error_message(message: &str) {
 // =============================================================================
 
 /// 获取 WASM 内存信息
+/// 
+/// `converter_cache_bytes` 为所有存活转换器的缓存占用，可通过 `releaseBuffers` 释放
 #[wasm_bindgen(js_name = getWasmMemoryInfo)]
 pub fn get_wasm_memory_info() -> Result<String, JsValue> {
     use js_sys::WebAssembly;
@@ -106,6 +108,7 @@ pub fn get_wasm_memory_info() -> Result<String, JsValue> {
         "buffer_size_bytes": length,
         "buffer_size_mb": length as f64 / (1024.0 * 1024.0),
         "pages": length / 65536, // WASM page size is 64KB
+        "converter_cache_bytes": crate::conversion::total_cached_bytes(),
     })).map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
@@ -170,10 +173,50 @@ pub fn detect_format_from_extension(filename: &str) -> Option<crate::types::JsIm
         "tiff" | "tif" => Some(crate::types::JsImageFormat::Tiff),
         "gif" => Some(crate::types::JsImageFormat::Gif),
         "ico" => Some(crate::types::JsImageFormat::Ico),
+        "ppm" | "pgm" | "pbm" | "pnm" => Some(crate::types::JsImageFormat::Pnm),
+        "tga" => Some(crate::types::JsImageFormat::Tga),
+        "heic" | "heif" | "hif" => Some(crate::types::JsImageFormat::Heic),
+        "jxl" => Some(crate::types::JsImageFormat::Jxl),
         _ => None,
     }
 }
 
+/// 综合文件内容与文件名检测图像格式
+/// 
+/// 优先使用文件头签名，无法识别时回退到扩展名；两者都有结果但不一致时返回错误，
+/// 适合服务器返回错误 MIME 类型的场景
+#[wasm_bindgen(js_name = detectFormatSmart)]
+pub fn detect_format_smart(
+    data: &[u8],
+    filename: Option<String>,
+) -> Result<crate::types::JsFormatDetection, JsValue> {
+    let extension = filename.as_deref().and_then(|name| name.rsplit_once('.')).map_or("", |(_, ext)| ext);
+    let from_extension = filename.as_deref().and_then(detect_format_from_extension);
+    let from_content = rustimage_core::codecs::FormatDetector::detect_detailed_with_hint(data, extension);
+    
+    let (format, confidence, source) = match (from_content, from_extension) {
+        (Ok(detection), Some(hinted)) => {
+            let detected = crate::types::to_js_image_format(detection.format());
+            if detected != hinted {
+                return Err(JsValue::from_str(&format!(
+                    "Content is {} but filename suggests {}",
+                    crate::types::format_to_string(detected),
+                    crate::types::format_to_string(hinted)
+                )));
+            }
+            // 内容与扩展名一致，置信度适当提高
+            (detected, (detection.confidence() + 0.1).min(1.0), "content+extension")
+        }
+        (Ok(detection), None) => {
+            (crate::types::to_js_image_format(detection.format()), detection.confidence(), "content")
+        }
+        (Err(_), Some(hinted)) => (hinted, 0.3, "extension"),
+        (Err(e), None) => return Err(JsValue::from_str(&e.to_string())),
+    };
+    
+    Ok(crate::types::JsFormatDetection { format, confidence, source })
+}
+
 /// 根据 MIME 类型检测图像格式
 #[wasm_bindgen(js_name = detectFormatFromMimeType)]
 pub fn detect_format_from_mime_type(mime_type: &str) -> Option<crate::types::JsImageFormat> {
@@ -186,6 +229,10 @@ pub fn detect_format_from_mime_type(mime_type: &str) -> Option<crate::types::JsI
         "image/tiff" => Some(crate::types::JsImageFormat::Tiff),
         "image/gif" => Some(crate::types::JsImageFormat::Gif),
         "image/x-icon" | "image/vnd.microsoft.icon" => Some(crate::types::JsImageFormat::Ico),
+        "image/x-portable-anymap" | "image/x-portable-pixmap" => Some(crate::types::JsImageFormat::Pnm),
+        "image/x-tga" | "image/x-targa" => Some(crate::types::JsImageFormat::Tga),
+        "image/heic" | "image/heif" => Some(crate::types::JsImageFormat::Heic),
+        "image/jxl" => Some(crate::types::JsImageFormat::Jxl),
         _ => None,
     }
 }
@@ -210,6 +257,10 @@ pub fn generate_suggested_filename(
         crate::types::JsImageFormat::Tiff => "tiff",
         crate::types::JsImageFormat::Gif => "gif",
         crate::types::JsImageFormat::Ico => "ico",
+        crate::types::JsImageFormat::Pnm => "ppm",
+        crate::types::JsImageFormat::Tga => "tga",
+        crate::types::JsImageFormat::Heic => "heic",
+        crate::types::JsImageFormat::Jxl => "jxl",
     };
     
     format!("{}.{}", base_name, extension)
@@ -277,6 +328,10 @@ pub fn calculate_size_range(
         crate::types::JsImageFormat::Tiff => (0.3, 4.0, "TIFF (various compression)"),
         crate::types::JsImageFormat::Gif => (0.1, 1.0, "GIF (LZW compression)"),
         crate::types::JsImageFormat::Ico => (0.1, 1.0, "ICO (multiple sizes)"),
+        crate::types::JsImageFormat::Pnm => (1.0, 1.0, "PNM (uncompressed)"),
+        crate::types::JsImageFormat::Tga => (0.5, 1.4, "TGA (RLE compression)"),
+        crate::types::JsImageFormat::Heic => (0.02, 0.4, "HEIC (HEVC compression)"),
+        crate::types::JsImageFormat::Jxl => (0.1, 0.8, "JPEG XL (lossless compression)"),
     };
     
     let base_size = pixel_count * 3; // RGB baseline